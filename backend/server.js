@@ -2,14 +2,11 @@ require('dotenv').config();
 const express = require('express');
 const cors = require('cors');
 const bodyParser = require('body-parser');
-const { exec } = require('child_process');
 const path = require('path');
-const crypto = require('crypto');
 const fs = require('fs');
-const { spawn } = require('child_process');
+const crypto = require('crypto');
 
 const app = express();
-const PORT = process.env.PORT || 3000;
 
 // Important: Set this constant to false for real SP1 proofs
 const SIMULATION_MODE = false;
@@ -25,27 +22,6 @@ app.use(express.static(path.join(__dirname, '../web')));
 const clients = new Map();
 
 const ROOT_DIR = path.resolve(__dirname, '..');
-const POSSIBLE_COMPILED_DIRS = [
-    path.join(ROOT_DIR, 'target/release'),
-    path.join(ROOT_DIR, 'script/target/release'),
-    path.join(ROOT_DIR, 'script'),
-    path.join(ROOT_DIR, 'bin')
-];
-
-let COMPILED_DIR = null;
-for (const dir of POSSIBLE_COMPILED_DIRS) {
-    if (fs.existsSync(dir)) {
-        COMPILED_DIR = dir;
-        console.log(`Found compiled directory: ${dir}`);
-        break;
-    }
-}
-
-if (!COMPILED_DIR) {
-    console.warn('Warning: Could not find compiled directory. Using root directory as fallback.');
-    COMPILED_DIR = ROOT_DIR;
-}
-
 const PROOF_OUTPUT_DIR = path.join(ROOT_DIR, 'proofs');
 
 try {
@@ -63,6 +39,23 @@ try {
     }
 }
 
+// Native bindings to the prover, built with `npm run build` in /node
+let verifier = null;
+try {
+    verifier = require('../node');
+    verifier.configure({ storeDir: PROOF_OUTPUT_DIR });
+} catch (err) {
+    console.warn(`Native verifier unavailable (${err.message})`);
+}
+
+// Listen address, `server.bind` in bladewarrior.toml or BLADEWARRIOR_SERVER_BIND
+const SERVER_BIND = verifier
+    ? verifier.serverBind()
+    : process.env.BLADEWARRIOR_SERVER_BIND || '0.0.0.0:3000';
+const BIND_SEPARATOR = SERVER_BIND.lastIndexOf(':');
+const HOST = SERVER_BIND.slice(0, BIND_SEPARATOR).replace(/^\[(.*)\]$/, '$1');
+const PORT = Number(SERVER_BIND.slice(BIND_SEPARATOR + 1));
+
 // Root route
 app.get('/', (req, res) => {
     res.sendFile(path.join(__dirname, '../web/index.html'));
@@ -93,7 +86,7 @@ app.get('/debug', (req, res) => {
 // Game score verification endpoint
 app.post('/api/verify', async (req, res) => {
     try {
-        const { playerName, score, timestamp, gameHash } = req.body;
+        const { playerName, score, timestamp, gameHash, mode = 'endless', checkpoints, events, session, replay } = req.body;
         
         if (!playerName || score === undefined || !timestamp || !gameHash) {
         return res.status(400).json({
@@ -102,24 +95,25 @@ app.post('/api/verify', async (req, res) => {
             });
         }
         
+        // Without a session the guest sees a game of zero ticks and rejects any score
+        if (!session || session.startedAt === undefined || session.endedAt === undefined || session.ticks === undefined) {
+            return res.status(400).json({
+                success: false,
+                message: 'Missing game session (startedAt, endedAt, ticks) in request'
+            });
+        }
+        
         console.log(`Verifying score for player ${playerName}: ${score} points`);
         
         const verificationId = `${playerName}-${timestamp}`;
         
-        const proofFileName = `${playerName.replace(/[^a-z0-9]/gi, '_')}_${score}_${Date.now()}.bin`;
-        const proofFilePath = path.join(PROOF_OUTPUT_DIR, proofFileName);
-        
         res.json({
             success: true,
             message: "Verification process started",
-            verificationId,
-            proofFile: proofFileName 
+            verificationId
         });
         
-        fs.writeFileSync(proofFilePath, Buffer.alloc(0));
-        console.log(`Empty proof file created at: ${proofFilePath}`);
-
-        runVerification(playerName, score, timestamp, gameHash, verificationId, proofFilePath);
+        runVerification(playerName, score, timestamp, gameHash, mode, checkpoints, events, session, replay, verificationId);
             
     } catch (error) {
         console.error("Server error:", error);
@@ -155,14 +149,16 @@ app.get('/api/verify/log', (req, res) => {
     });
 });
 
-function cleanOutputString(str) {
-    return str.replace(/^stdout:\s*/gm, '')     
-              .replace(/^stderr:\s*/gm, '')     
-              .replace(/\s+stdout:\s*/gm, ' ')  
-              .replace(/\s+stderr:\s*/gm, ' '); 
-}
+// Progress shown for each prover stage event
+const STAGE_PROGRESS = {
+    keygen: { started: [20, "Generating proving keys..."], finished: [40, "Keys created!"] },
+    execute: { started: [45, "Computing witness..."], finished: [50, "Witness computed!"] },
+    prove: { started: [50, "Building Zero-Knowledge proof..."], finished: [80, "Zero-Knowledge proof created!"] },
+    verify: { started: [85, "Verifying proof..."], finished: [95, "Proof has been verified!"] },
+    save: { started: [97, "Saving proof..."], finished: [99, "Proof saved!"] }
+};
 
-function runVerification(playerName, score, timestamp, gameHash, verificationId, proofFilePath) {
+function runVerification(playerName, score, timestamp, gameHash, mode, checkpoints, events, session, replay, verificationId) {
     // Function to send updates to client
     const sendUpdate = (data) => {
         const client = clients.get(verificationId);
@@ -171,12 +167,9 @@ function runVerification(playerName, score, timestamp, gameHash, verificationId,
         }
     };
     
-    // Find prove executable
-    const proveScript = findExecutable('prove', 'cargo run --bin prove --');
-    
-    // If not found, use simulation
-    if (!proveScript && process.env.SIMULATION_MODE !== 'true') {
-        console.log("Prover executable not found, falling back to simulation mode");
+    // Without the native verifier, use simulation
+    if (!verifier && process.env.SIMULATION_MODE !== 'true') {
+        console.log("Native verifier not built, falling back to simulation mode");
         process.env.SIMULATION_MODE = 'true';
     }
     
@@ -189,187 +182,76 @@ function runVerification(playerName, score, timestamp, gameHash, verificationId,
     if (SIMULATION_MODE) {
         console.log("Running in simulation mode - no actual ZK proof generated");
         simulateVerification(sendUpdate, playerName, score);
-    } else {
-        console.log("Running real verification using SP1 prover");
-        
-        // Convert game hash from hex to bytes if needed
-        let gameHashArg = gameHash;
-        if (gameHash.length === 64 && /^[0-9a-f]+$/i.test(gameHash)) {
-            gameHashArg = gameHash; // Already in hex format
-        }
-        
-        // Prepare command for prover
-        const args = [
-            '--prove',
-            '--timestamp', timestamp.toString(),
-            '--player', playerName,
-            '--score', score.toString(),
-            '--game-hash', gameHashArg
-        ];
-        
-        // Log command
-        console.log(`Running command: ${proveScript} ${args.join(' ')}`);
-        
-        // Create child process
-        let childProcess;
-        if (proveScript.includes('cargo run')) {
-            childProcess = spawn('cargo', ['run', '--bin', 'prove', '--', ...args], {
-                cwd: path.join(ROOT_DIR, 'script'),
-                env: {
-                    ...process.env, 
-                    RUST_BACKTRACE: '1',
-                    RUST_LOG: 'info' 
-                },
-                stdio: ['ignore', 'pipe', 'pipe'],
-                windowsHide: true
-            });
-        } else {
-            childProcess = spawn(proveScript, args, {
-                cwd: COMPILED_DIR,
-                env: {
-                    ...process.env, 
-                    RUST_BACKTRACE: '1',
-                    RUST_LOG: 'info' 
-                },
-                stdio: ['ignore', 'pipe', 'pipe'],
-                windowsHide: true
-            });
+        return;
+    }
+    
+    console.log("Running real verification using SP1 prover");
+    sendUpdate({ log: "Starting SP1 Zero-Knowledge Prover...", progress: 10 });
+    
+    let progress = 10;
+    const onProgress = (update) => {
+        if (update.status === 'failed') {
+            console.error(`${update.stage} failed: ${update.reason}`);
+            sendUpdate({ log: `Error: ${update.stage} failed: ${update.reason}`, progress });
+            return;
         }
-        
-        let output = '';
-        let errorOutput = '';
-        
-        // Initial update
-        sendUpdate({ log: "Starting SP1 Zero-Knowledge Prover...", progress: 10 });
-        
-        // Fix: Use childProcess instead of process for stdout/stderr listeners
-        childProcess.stdout.on('data', (data) => {
-            // Clean output format
-            const rawDataStr = data.toString().trim();
-            const dataStr = cleanOutputString(rawDataStr);
-            
-            output += dataStr + "\n";
-            
-            let progressValue = 10; // Default
-            
-            const isRealError = dataStr.includes('Error:') || 
-                               dataStr.includes('Failed to') || 
-                               dataStr.includes('exception');
-            
-            if (isRealError) {
-                console.error(dataStr);
-            } else {
-                console.log(dataStr);
-            }
-            
-            sendUpdate({ log: dataStr, progress: progressValue });
-            
-            if (dataStr.includes('GENERATING KEYS')) {
-                progressValue = 20;
-                sendUpdate({ log: "Generating proving keys...", progress: progressValue });
-            } else if (dataStr.includes('PROVING AND VERIFICATION KEYS GENERATED')) {
-                progressValue = 40;
-                sendUpdate({ log: "Keys created!", progress: progressValue });
-            } else if (dataStr.includes('GENERATING PROOF')) {
-                progressValue = 50;
-                sendUpdate({ log: "Building Zero-Knowledge proof...", progress: progressValue });
-            } else if (dataStr.includes('ZERO-KNOWLEDGE PROOF GENERATED')) {
-                progressValue = 80;
-                sendUpdate({ log: "Zero-Knowledge proof created!", progress: progressValue });
-            } else if (dataStr.includes('VERIFYING PROOF')) {
-                progressValue = 85;
-                sendUpdate({ log: "Verification proof...", progress: progressValue });
-            } else if (dataStr.includes('PROOF VERIFIED SUCCESSFULLY')) {
-                progressValue = 95;
-                sendUpdate({ log: "Proof has been verified!", progress: progressValue });
-            } else if (dataStr.includes('VERIFICATION_SUCCESS=true')) {
-                progressValue = 100;
+        const [stageProgress, log] = STAGE_PROGRESS[update.stage][update.status];
+        progress = stageProgress;
+        const detail = update.cycles != null ? ` (${update.cycles} cycles)` : '';
+        console.log(`${update.stage} ${update.status}${detail}`);
+        sendUpdate({ log: log + detail, progress });
+    };
+    
+    const submission = {
+        timestamp: Number(timestamp),
+        player: playerName,
+        score: Number(score),
+        gameHash,
+        mode,
+        // Per-wave checkpoints have their Merkle root committed by the guest
+        checkpoints: Array.isArray(checkpoints) ? checkpoints : undefined,
+        // Event log is analysed by the prover and its anti-cheat report stored with the proof
+        events: Array.isArray(events) ? events : undefined,
+        // The guest checks the game's duration against its tick count
+        startedAt: Number(session.startedAt),
+        endedAt: Number(session.endedAt),
+        ticks: Number(session.ticks),
+        // Hex encoded replay, kept with the proof so a challenge can re-run the game
+        replay: typeof replay === 'string' ? replay : undefined
+    };
+    
+    verifier.prove(submission, onProgress)
+        .then((result) => {
+            if (result.success) {
+                console.log(`Proof saved to: ${result.proofPath}`);
                 sendUpdate({ 
                     log: "🎉 Verification of Game Score ZK Success! 🎉", 
                     progress: 100,
                     completed: true,
-                success: true,
-                    proofFile: path.basename(proofFilePath) 
+                    success: true,
+                    proofHash: result.proofHash
                 });
-            } else if (dataStr.includes('Proof saved to')) {
-                const match = dataStr.match(/Proof saved to: (.*)/);
-                if (match && match[1]) {
-                    sendUpdate({ log: `Proof send to: ${match[1]}`, progress: progressValue });
-                }
-            }
-        });
-        
-        childProcess.stderr.on('data', (data) => {
-            const rawErrorStr = data.toString().trim();
-            const errorStr = cleanOutputString(rawErrorStr);
-
-            errorOutput += errorStr + "\n";
-            
-            let progressValue = 10; 
-            
-            if (errorStr.includes('Game Score Verification:') || 
-                errorStr.includes('Timestamp:') || 
-                errorStr.includes('Player:') || 
-                errorStr.includes('Score:') || 
-                errorStr.includes('Verification Result:') ||
-                errorStr.includes('Valid:')) {
-                
-                console.log(errorStr);
-                
-                sendUpdate({ log: errorStr, progress: progressValue });
             } else {
-                console.error(errorStr);
-                
-                sendUpdate({ log: `Error: ${errorStr}`, progress: progressValue });
-            }
-        });
-        
-        childProcess.on('close', (code) => {
-            console.log(`Child process exited with code ${code}`);
-            
-            if (code !== 0) {
-                console.error('Verification failed');
-                sendUpdate({ 
-                    log: `Verification failed send code error: ${code}`, 
-                    progress: 100,
-                    completed: true,
-                    success: false
-                });
-            } else if (!output.includes('VERIFICATION_SUCCESS=true')) {
+                const message = result.failure ? result.failure.message : 'the guest rejected the score';
+                console.error(`Verification failed: ${message}`);
                 sendUpdate({ 
-                    log: "Verification success", 
+                    log: `Verification failed: ${message}`, 
                     progress: 100,
                     completed: true,
-                    success: true
+                    success: false,
+                    reason: result.failure ? result.failure.kind : 'rejected'
                 });
             }
+        })
+        .catch((error) => {
+            console.error('Verification failed:', error);
+            sendUpdate({ 
+                log: `Verification failed: ${error.message}`, 
+                progress: 100,
+                completed: true,
+                success: false
+            });
         });
-    }
-}
-
-function findExecutable(name, fallback) {
-    const extensions = process.platform === 'win32' ? ['.exe', '.cmd', '.bat', ''] : [''];
-    
-    for (const ext of extensions) {
-        const filePath = path.join(COMPILED_DIR, `${name}${ext}`);
-        if (fs.existsSync(filePath)) {
-            return filePath;
-        }
-    }
-    
-    const pathEnv = process.env.PATH || '';
-    const pathDirs = pathEnv.split(path.delimiter);
-    
-    for (const dir of pathDirs) {
-        for (const ext of extensions) {
-            const filePath = path.join(dir, `${name}${ext}`);
-            if (fs.existsSync(filePath)) {
-                return filePath;
-            }
-        }
-    }
-    
-    return fallback;
 }
 
 function simulateVerification(sendUpdate, playerName, score) {
@@ -402,6 +284,116 @@ function simulateVerification(sendUpdate, playerName, score) {
     processNextStep();
 }
 
+const PROOF_HASH_PATTERN = /^[0-9a-f]{64}$/;
+
+// Challenges re-run games on the prover, so they need the bearer token in
+// BLADEWARRIOR_CHALLENGE_TOKEN and each client address gets only a few an hour
+const CHALLENGE_TOKEN = process.env.BLADEWARRIOR_CHALLENGE_TOKEN || '';
+const CHALLENGE_WINDOW_MS = 60 * 60 * 1000;
+const CHALLENGES_PER_WINDOW = 5;
+const challengeWindows = new Map();
+
+function requireChallengeToken(req, res, next) {
+    if (!CHALLENGE_TOKEN) {
+        return res.status(503).json({
+            success: false,
+            message: 'Challenges are disabled; set BLADEWARRIOR_CHALLENGE_TOKEN to enable them'
+        });
+    }
+    const header = req.get('authorization') || '';
+    const given = Buffer.from(header.replace(/^Bearer /, ''));
+    const expected = Buffer.from(CHALLENGE_TOKEN);
+    if (!header.startsWith('Bearer ') || given.length !== expected.length
+        || !crypto.timingSafeEqual(given, expected)) {
+        return res.status(401).json({ success: false, message: 'A valid challenge token is required' });
+    }
+    next();
+}
+
+function limitChallenges(req, res, next) {
+    const now = Date.now();
+    for (const [client, window] of challengeWindows) {
+        if (now - window.startedAt >= CHALLENGE_WINDOW_MS) {
+            challengeWindows.delete(client);
+        }
+    }
+    const window = challengeWindows.get(req.ip) || { startedAt: now, count: 0 };
+    if (window.count >= CHALLENGES_PER_WINDOW) {
+        res.set('Retry-After', String(Math.ceil((window.startedAt + CHALLENGE_WINDOW_MS - now) / 1000)));
+        return res.status(429).json({ success: false, message: 'Too many challenges; try again later' });
+    }
+    window.count += 1;
+    challengeWindows.set(req.ip, window);
+    next();
+}
+
+// Challenge an accepted score; the prover re-runs its replay and returns a signed verdict
+app.post('/api/challenges', requireChallengeToken, limitChallenges, async (req, res) => {
+    const { proofHash, challenger, reason = '' } = req.body;
+    
+    if (!proofHash || !PROOF_HASH_PATTERN.test(proofHash) || !challenger) {
+        return res.status(400).json({
+            success: false,
+            message: 'A valid proofHash and challenger are required'
+        });
+    }
+    
+    if (!verifier) {
+        return res.status(503).json({
+            success: false,
+            message: 'Challenges need the native verifier; build it with `npm run build` in /node'
+        });
+    }
+    
+    try {
+        const verdict = await verifier.challenge({ proofHash, challenger, reason });
+        res.json({ success: true, verdict });
+    } catch (error) {
+        console.error("Error running challenge:", error);
+        res.status(500).json({
+            success: false,
+            message: `Error running challenge: ${error.message}`
+        });
+    }
+});
+
+// Verdicts recorded for a proof, oldest first
+app.get('/api/challenges/:proofHash', (req, res) => {
+    const { proofHash } = req.params;
+    if (!PROOF_HASH_PATTERN.test(proofHash)) {
+        return res.status(400).json({ success: false, message: 'Invalid proof hash' });
+    }
+    
+    const verdictsFile = path.join(PROOF_OUTPUT_DIR, `${proofHash}.challenges.json`);
+    try {
+        const verdicts = fs.existsSync(verdictsFile)
+            ? JSON.parse(fs.readFileSync(verdictsFile, 'utf8'))
+            : [];
+        res.json({ success: true, verdicts });
+    } catch (error) {
+        console.error("Error reading verdicts:", error);
+        res.status(500).json({
+            success: false,
+            message: `Error reading verdicts: ${error.message}`
+        });
+    }
+});
+
+// Seed of a day's challenge, today's unless ?date=YYYY-MM-DD is given
+app.get('/api/daily', (req, res) => {
+    if (!verifier) {
+        return res.status(503).json({
+            success: false,
+            message: 'Daily challenges need the native verifier; build it with `npm run build` in /node'
+        });
+    }
+    try {
+        res.json({ success: true, challenge: verifier.dailyChallenge(req.query.date) });
+    } catch (error) {
+        res.status(400).json({ success: false, message: error.message });
+    }
+});
+
 app.get('/api/proofs', (req, res) => {
     try {
         const proofFiles = fs.readdirSync(PROOF_OUTPUT_DIR)
@@ -429,10 +421,10 @@ app.get('/api/proofs', (req, res) => {
     }
 });
 
-app.listen(PORT, '0.0.0.0', () => {
-    console.log(`Blade Warrior Game Verification Server running at http://localhost:${PORT}`);
+app.listen(PORT, HOST, () => {
+    console.log(`Blade Warrior Game Verification Server running at http://${SERVER_BIND}`);
     console.log(`Simulation Mode: ${process.env.SIMULATION_MODE === 'true' ? 'ENABLED' : 'DISABLED'}`);
-    console.log(`Using compiled directory: ${COMPILED_DIR}`);
+    console.log(`Native verifier: ${verifier ? 'LOADED' : 'NOT BUILT'}`);
     console.log(`Proof output directory: ${PROOF_OUTPUT_DIR}`);
     console.log(`Use the frontend to verify game scores with real ZK proofs!`);
 });
\ No newline at end of file
@@ -0,0 +1,278 @@
+//! ABI encoding for GameScorePublicValues
+//!
+//! Guests always commit the current layout. Every layout committed by an
+//! earlier release stays decodable, so proofs from several program releases
+//! can be read side by side.
+
+use std::fmt;
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+
+use crate::checkpoint::EMPTY_ROOT;
+use crate::{GameMode, GameScorePublicValues, ABI_VERSION};
+
+/// Layout committed before versioning; it has no version field
+///
+/// Proofs in layouts without a mode were all played in [`GameMode::Endless`],
+/// layouts without checkpoints decode with an empty checkpoint root and
+/// layouts without a session decode with a zero duration and tick count.
+pub mod v0 {
+    alloy_sol_types::sol! {
+        struct GameScoreData {
+            uint64 timestamp;
+            bytes32 playerNameHash;
+            uint32 score;
+            bytes32 gameHash;
+            uint32 verified;
+        }
+    }
+}
+
+/// Adds `abiVersion` and `rulesVersion` as the leading fields
+pub mod v1 {
+    alloy_sol_types::sol! {
+        struct GameScoreData {
+            uint32 abiVersion;
+            uint32 rulesVersion;
+            uint64 timestamp;
+            bytes32 playerNameHash;
+            uint32 score;
+            bytes32 gameHash;
+            uint32 verified;
+        }
+    }
+}
+
+/// Adds the game `mode` after the version fields
+pub mod v2 {
+    alloy_sol_types::sol! {
+        struct GameScoreData {
+            uint32 abiVersion;
+            uint32 rulesVersion;
+            uint32 mode;
+            uint64 timestamp;
+            bytes32 playerNameHash;
+            uint32 score;
+            bytes32 gameHash;
+            uint32 verified;
+        }
+    }
+}
+
+/// Appends the checkpoint Merkle root and the number of checkpoints
+pub mod v3 {
+    alloy_sol_types::sol! {
+        struct GameScoreData {
+            uint32 abiVersion;
+            uint32 rulesVersion;
+            uint32 mode;
+            uint64 timestamp;
+            bytes32 playerNameHash;
+            uint32 score;
+            bytes32 gameHash;
+            uint32 verified;
+            bytes32 checkpointRoot;
+            uint32 checkpointCount;
+        }
+    }
+}
+
+/// Appends the game's duration in seconds and its tick count
+pub mod v4 {
+    alloy_sol_types::sol! {
+        struct GameScoreData {
+            uint32 abiVersion;
+            uint32 rulesVersion;
+            uint32 mode;
+            uint64 timestamp;
+            bytes32 playerNameHash;
+            uint32 score;
+            bytes32 gameHash;
+            uint32 verified;
+            bytes32 checkpointRoot;
+            uint32 checkpointCount;
+            uint32 durationSecs;
+            uint64 ticks;
+        }
+    }
+}
+
+/// Current layout
+pub use v4::GameScoreData;
+
+/// Encoded size of a v0 payload (five static words)
+const V0_LEN: usize = 5 * 32;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Payload declares a layout this release does not know
+    UnsupportedVersion(u32),
+    /// Payload is too short to carry a version
+    Truncated(usize),
+    Abi(alloy_sol_types::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported ABI version {}", version)
+            }
+            DecodeError::Truncated(len) => write!(f, "public values too short ({} bytes)", len),
+            DecodeError::Abi(e) => write!(f, "invalid ABI encoding: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<alloy_sol_types::Error> for DecodeError {
+    fn from(e: alloy_sol_types::Error) -> Self {
+        DecodeError::Abi(e)
+    }
+}
+
+impl From<GameScorePublicValues> for GameScoreData {
+    fn from(value: GameScorePublicValues) -> Self {
+        Self {
+            abiVersion: ABI_VERSION,
+            rulesVersion: value.rules_version,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            playerNameHash: FixedBytes(value.player_name_hash),
+            score: value.score,
+            gameHash: FixedBytes(value.game_hash),
+            verified: value.verified,
+            checkpointRoot: FixedBytes(value.checkpoint_root),
+            checkpointCount: value.checkpoint_count,
+            durationSecs: value.duration_secs,
+            ticks: value.ticks,
+        }
+    }
+}
+
+impl From<v0::GameScoreData> for GameScorePublicValues {
+    fn from(value: v0::GameScoreData) -> Self {
+        Self {
+            abi_version: 0,
+            rules_version: 0,
+            mode: GameMode::Endless.id(),
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            game_hash: value.gameHash.0,
+            verified: value.verified,
+            checkpoint_root: EMPTY_ROOT,
+            checkpoint_count: 0,
+            duration_secs: 0,
+            ticks: 0,
+        }
+    }
+}
+
+impl From<v1::GameScoreData> for GameScorePublicValues {
+    fn from(value: v1::GameScoreData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: GameMode::Endless.id(),
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            game_hash: value.gameHash.0,
+            verified: value.verified,
+            checkpoint_root: EMPTY_ROOT,
+            checkpoint_count: 0,
+            duration_secs: 0,
+            ticks: 0,
+        }
+    }
+}
+
+impl From<v2::GameScoreData> for GameScorePublicValues {
+    fn from(value: v2::GameScoreData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            game_hash: value.gameHash.0,
+            verified: value.verified,
+            checkpoint_root: EMPTY_ROOT,
+            checkpoint_count: 0,
+            duration_secs: 0,
+            ticks: 0,
+        }
+    }
+}
+
+impl From<v3::GameScoreData> for GameScorePublicValues {
+    fn from(value: v3::GameScoreData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            game_hash: value.gameHash.0,
+            verified: value.verified,
+            checkpoint_root: value.checkpointRoot.0,
+            checkpoint_count: value.checkpointCount,
+            duration_secs: 0,
+            ticks: 0,
+        }
+    }
+}
+
+impl From<v4::GameScoreData> for GameScorePublicValues {
+    fn from(value: v4::GameScoreData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            game_hash: value.gameHash.0,
+            verified: value.verified,
+            checkpoint_root: value.checkpointRoot.0,
+            checkpoint_count: value.checkpointCount,
+            duration_secs: value.durationSecs,
+            ticks: value.ticks,
+        }
+    }
+}
+
+/// Encode in the current layout, whatever `abi_version` the values carry
+pub fn encode(values: GameScorePublicValues) -> Vec<u8> {
+    GameScoreData::abi_encode(&GameScoreData::from(values))
+}
+
+/// ABI version of an encoded payload
+///
+/// Versioned layouts start with `abiVersion` as a `uint32` word; the
+/// unversioned v0 layout is recognised by its length.
+pub fn version(bytes: &[u8]) -> Result<u32, DecodeError> {
+    if bytes.len() == V0_LEN {
+        return Ok(0);
+    }
+    let word = bytes.get(..32).ok_or(DecodeError::Truncated(bytes.len()))?;
+    let version = <alloy_sol_types::sol_data::Uint<32>>::abi_decode(word, true)?;
+    Ok(version)
+}
+
+/// Decode public values committed by any guest release
+pub fn decode(bytes: &[u8]) -> Result<GameScorePublicValues, DecodeError> {
+    match version(bytes)? {
+        0 => Ok(v0::GameScoreData::abi_decode(bytes, true)?.into()),
+        1 => Ok(v1::GameScoreData::abi_decode(bytes, true)?.into()),
+        2 => Ok(v2::GameScoreData::abi_decode(bytes, true)?.into()),
+        3 => Ok(v3::GameScoreData::abi_decode(bytes, true)?.into()),
+        4 => Ok(v4::GameScoreData::abi_decode(bytes, true)?.into()),
+        version => Err(DecodeError::UnsupportedVersion(version)),
+    }
+}
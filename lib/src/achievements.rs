@@ -0,0 +1,237 @@
+//! Achievements earned during a game, computed by the simulation.
+//!
+//! The achievements guest runs a replay through [`Simulation`] with an
+//! [`AchievementTracker`] watching every tick, and commits the earned set as
+//! a bitmask of [`Achievement::bit`]s next to the player's name hash and the
+//! replay hash. Achievements can then be checked like scores instead of being
+//! trusted from the client.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+
+use crate::replay::Replay;
+use crate::sim::{SimEvent, Simulation, TICK_RATE};
+
+/// Layout version of [`AchievementData`], independent of [`crate::ABI_VERSION`]
+pub const ACHIEVEMENTS_ABI_VERSION: u32 = 1;
+
+/// Wave [`Achievement::Flawless`] must be reached on
+pub const FLAWLESS_WAVE: u32 = 10;
+/// Kills [`Achievement::Frenzy`] needs inside [`FRENZY_WINDOW_TICKS`]
+pub const FRENZY_KILLS: usize = 50;
+pub const FRENZY_WINDOW_TICKS: u64 = 30 * TICK_RATE as u64;
+/// Ticks a game must last for [`Achievement::Survivor`]
+pub const SURVIVOR_TICKS: u64 = 5 * 60 * TICK_RATE as u64;
+
+alloy_sol_types::sol! {
+    struct AchievementData {
+        uint32 abiVersion;
+        uint32 rulesVersion;
+        uint32 mode;
+        bytes32 playerNameHash;
+        bytes32 replayHash;
+        uint32 achievements;
+        uint32 verified;
+    }
+}
+
+/// Achievement, committed as the bit at its [`id`](Achievement::id)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    /// Reach wave 10 without losing a life
+    Flawless,
+    /// Kill 50 enemies within 30 seconds
+    Frenzy,
+    /// Stay alive for 5 minutes
+    Survivor,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 3] =
+        [Achievement::Flawless, Achievement::Frenzy, Achievement::Survivor];
+
+    pub fn id(&self) -> u32 {
+        match self {
+            Achievement::Flawless => 0,
+            Achievement::Frenzy => 1,
+            Achievement::Survivor => 2,
+        }
+    }
+
+    pub fn bit(&self) -> u32 {
+        1 << self.id()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::Flawless => "flawless",
+            Achievement::Frenzy => "frenzy",
+            Achievement::Survivor => "survivor",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::Flawless => "Reach wave 10 without losing a life",
+            Achievement::Frenzy => "Kill 50 enemies within 30 seconds",
+            Achievement::Survivor => "Survive for 5 minutes",
+        }
+    }
+
+    /// Achievements whose bits are set in `mask`, ignoring unknown bits
+    pub fn in_mask(mask: u32) -> Vec<Achievement> {
+        Self::ALL.into_iter().filter(|achievement| mask & achievement.bit() != 0).collect()
+    }
+
+    /// Bitmask with every achievement in `achievements` set
+    pub fn mask(achievements: &[Achievement]) -> u32 {
+        achievements.iter().fold(0, |mask, achievement| mask | achievement.bit())
+    }
+}
+
+impl fmt::Display for Achievement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Achievement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|achievement| achievement.name() == s).ok_or_else(|| {
+            format!("unknown achievement {} (expected flawless, frenzy or survivor)", s)
+        })
+    }
+}
+
+/// Watches a game tick by tick and records the achievements it earns
+#[derive(Debug, Clone, Default)]
+pub struct AchievementTracker {
+    earned: u32,
+    /// Ticks of the latest kills, oldest first, at most [`FRENZY_KILLS`]
+    recent_kills: VecDeque<u64>,
+}
+
+impl AchievementTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bitmask of the achievements earned so far
+    pub fn earned(&self) -> u32 {
+        self.earned
+    }
+
+    /// Record the tick `sim` just stepped through, which produced `events`
+    pub fn observe(&mut self, sim: &Simulation, events: &[SimEvent]) {
+        // Lives never come back, so full lives now means none were ever lost
+        if sim.wave() >= FLAWLESS_WAVE && sim.lives() == sim.mode().rules().lives {
+            self.earned |= Achievement::Flawless.bit();
+        }
+
+        for event in events {
+            if let SimEvent::EnemyKilled { .. } = event {
+                if self.recent_kills.len() == FRENZY_KILLS {
+                    self.recent_kills.pop_front();
+                }
+                self.recent_kills.push_back(sim.tick());
+            }
+        }
+        let frenzy = self.recent_kills.len() == FRENZY_KILLS
+            && self
+                .recent_kills
+                .front()
+                .is_some_and(|&first| sim.tick() - first < FRENZY_WINDOW_TICKS);
+        if frenzy {
+            self.earned |= Achievement::Frenzy.bit();
+        }
+
+        if sim.tick() >= SURVIVOR_TICKS && sim.lives() > 0 {
+            self.earned |= Achievement::Survivor.bit();
+        }
+    }
+}
+
+/// Run a replay as [`Replay::run`] does, returning the final state and the achievements earned
+pub fn run(replay: &Replay) -> (Simulation, u32) {
+    let mut sim = Simulation::new(replay.mode, replay.seed);
+    let mut tracker = AchievementTracker::new();
+    for input in &replay.inputs {
+        if sim.is_over() {
+            break;
+        }
+        let events = sim.step(*input);
+        tracker.observe(&sim, &events);
+    }
+    (sim, tracker.earned())
+}
+
+/// Public values committed by the achievements guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementPublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    /// [`GameMode::id`](crate::GameMode::id) of the replayed game
+    pub mode: u32,
+    pub player_name_hash: [u8; 32],
+    /// [`replay_hash`](crate::replay::replay_hash) of the encoded replay
+    pub replay_hash: [u8; 32],
+    /// Bitmask of earned [`Achievement`]s
+    pub achievements: u32,
+    pub verified: u32,
+}
+
+impl AchievementPublicValues {
+    pub fn earned(&self) -> Vec<Achievement> {
+        Achievement::in_mask(self.achievements)
+    }
+}
+
+impl From<AchievementPublicValues> for AchievementData {
+    fn from(value: AchievementPublicValues) -> Self {
+        Self {
+            abiVersion: ACHIEVEMENTS_ABI_VERSION,
+            rulesVersion: value.rules_version,
+            mode: value.mode,
+            playerNameHash: FixedBytes(value.player_name_hash),
+            replayHash: FixedBytes(value.replay_hash),
+            achievements: value.achievements,
+            verified: value.verified,
+        }
+    }
+}
+
+impl From<AchievementData> for AchievementPublicValues {
+    fn from(value: AchievementData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: value.mode,
+            player_name_hash: value.playerNameHash.0,
+            replay_hash: value.replayHash.0,
+            achievements: value.achievements,
+            verified: value.verified,
+        }
+    }
+}
+
+/// Encode in the current achievements layout
+pub fn encode(values: AchievementPublicValues) -> Vec<u8> {
+    AchievementData::abi_encode(&AchievementData::from(values))
+}
+
+/// Decode public values committed by the achievements guest
+pub fn decode(bytes: &[u8]) -> Result<AchievementPublicValues, crate::abi::DecodeError> {
+    let values = AchievementPublicValues::from(AchievementData::abi_decode(bytes, true)?);
+    if values.abi_version != ACHIEVEMENTS_ABI_VERSION {
+        return Err(crate::abi::DecodeError::UnsupportedVersion(values.abi_version));
+    }
+    Ok(values)
+}
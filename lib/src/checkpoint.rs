@@ -0,0 +1,179 @@
+//! Per-wave checkpoints and the Merkle tree committed over them.
+//!
+//! The guest commits only the root, so anyone holding a checkpoint and its
+//! [`InclusionProof`] can check it against a proof's public values without
+//! seeing the rest of the game.
+//!
+//! Leaves and inner nodes are hashed with distinct prefixes so a node can
+//! never be passed off as a checkpoint. A node without a sibling is carried
+//! up to the next level unchanged.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::modes::ModeRules;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Root committed for a game with no checkpoints
+pub const EMPTY_ROOT: [u8; 32] = [0u8; 32];
+
+/// Game state recorded as a wave is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Wave number, starting at 1
+    pub wave: u32,
+    /// Game tick the wave started on
+    pub tick: u64,
+    pub score: u32,
+    pub lives: u32,
+    /// Client hash of the full game state at this tick
+    pub state_hash: [u8; 32],
+}
+
+impl Checkpoint {
+    /// Leaf hash of this checkpoint
+    pub fn leaf_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(self.wave.to_be_bytes());
+        hasher.update(self.tick.to_be_bytes());
+        hasher.update(self.score.to_be_bytes());
+        hasher.update(self.lives.to_be_bytes());
+        hasher.update(self.state_hash);
+        hasher.finalize().into()
+    }
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Hashes of the next level up
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Merkle root over `checkpoints` in order
+pub fn merkle_root(checkpoints: &[Checkpoint]) -> [u8; 32] {
+    root_over(checkpoints.iter().map(Checkpoint::leaf_hash).collect())
+}
+
+/// Root of the tree over already hashed leaves, shared with other trees
+pub(crate) fn root_over(mut level: Vec<[u8; 32]>) -> [u8; 32] {
+    if level.is_empty() {
+        return EMPTY_ROOT;
+    }
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level[0]
+}
+
+/// Whether the checkpoints describe a game that could end with `final_score`
+///
+/// Waves strictly increase, ticks and scores never go backwards, lives never
+/// grow, and every score is reachable under `rules` and at most `final_score`.
+pub fn sequence_valid(checkpoints: &[Checkpoint], final_score: u32, rules: &ModeRules) -> bool {
+    let each_valid = checkpoints.iter().all(|checkpoint| {
+        checkpoint.wave >= 1
+            && checkpoint.lives <= rules.lives
+            && checkpoint.score <= final_score
+            && rules.score_valid(checkpoint.score)
+    });
+    let ordered = checkpoints.windows(2).all(|pair| {
+        let (prev, next) = (&pair[0], &pair[1]);
+        next.wave > prev.wave
+            && next.tick >= prev.tick
+            && next.score >= prev.score
+            && next.lives <= prev.lives
+    });
+    each_valid && ordered
+}
+
+/// Sibling path from one checkpoint up to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Position of the checkpoint among all checkpoints
+    pub index: usize,
+    /// Number of checkpoints the root was built over
+    ///
+    /// The root does not commit to it, so verifiers compare it with the
+    /// committed checkpoint count.
+    pub leaf_count: usize,
+    /// Sibling hashes from the leaf level upwards; levels where the node
+    /// was carried up without a sibling are skipped
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl InclusionProof {
+    /// Build the proof for `checkpoints[index]`
+    pub fn new(checkpoints: &[Checkpoint], index: usize) -> Option<Self> {
+        Self::over_leaves(checkpoints.iter().map(Checkpoint::leaf_hash).collect(), index)
+    }
+
+    /// Build the proof for the leaf at `index` of already hashed leaves
+    pub(crate) fn over_leaves(mut level: Vec<[u8; 32]>, index: usize) -> Option<Self> {
+        if index >= level.len() {
+            return None;
+        }
+        let leaf_count = level.len();
+        let mut position = index;
+        let mut siblings = Vec::new();
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            level = parent_level(&level);
+            position /= 2;
+        }
+        Some(Self { index, leaf_count, siblings })
+    }
+
+    /// Whether `checkpoint` sits at `index` under `root`
+    pub fn verify(&self, checkpoint: &Checkpoint, root: &[u8; 32]) -> bool {
+        self.verify_leaf(checkpoint.leaf_hash(), root)
+    }
+
+    /// Whether the leaf hashing to `leaf` sits at `index` under `root`
+    pub(crate) fn verify_leaf(&self, leaf: [u8; 32], root: &[u8; 32]) -> bool {
+        self.root_from_leaf(leaf).is_some_and(|computed| &computed == root)
+    }
+
+    /// Root of a `leaf_count` tree with the leaf hashing to `leaf` at
+    /// `index`, or `None` if the path does not fit such a tree
+    pub(crate) fn root_from_leaf(&self, leaf: [u8; 32]) -> Option<[u8; 32]> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let mut hash = leaf;
+        let mut siblings = self.siblings.iter();
+        let (mut position, mut width) = (self.index, self.leaf_count);
+        while width > 1 {
+            // The last node of an odd level has no sibling
+            if (position ^ 1) < width {
+                let sibling = siblings.next()?;
+                hash = if position % 2 == 0 {
+                    node_hash(&hash, sibling)
+                } else {
+                    node_hash(sibling, &hash)
+                };
+            }
+            position /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none().then_some(hash)
+    }
+}
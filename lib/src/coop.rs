@@ -0,0 +1,423 @@
+//! Two-player co-op games.
+//!
+//! Both players share the arena, the enemies and one pool of lives, and each
+//! plays from their own input stream and keeps their own score. Enemies chase
+//! whichever player is nearer, a kill goes to the player whose blade struck,
+//! and a hit on either player costs the team a life. Waves, spawns and the
+//! size of the lives pool follow the mode's single-player [`ModeRules`].
+//!
+//! Co-op replays use the single-player layout with their own magic and two
+//! inputs per tick, the first player's first:
+//!
+//! ```text
+//! magic "BWCO" | version u8 | mode u32 | seed u64 | tick count u32
+//! then per tick: player 1 input | player 2 input
+//! ```
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::modes::{GameMode, ModeRules};
+use crate::replay::{self, ReplayError, ReplayHeader, ReplayStream};
+use crate::sim::{
+    self, Enemy, Input, Player, Rng, ARENA_HEIGHT, ARENA_WIDTH, INVULNERABLE_TICKS, TICK_RATE,
+};
+
+/// Players in a co-op game
+pub const PLAYERS: usize = 2;
+
+pub const COOP_REPLAY_MAGIC: [u8; 4] = *b"BWCO";
+
+/// Layout version written by [`CoopReplay::encode`]
+pub const COOP_REPLAY_VERSION: u8 = 1;
+
+/// Layout version of [`CoopData`], independent of [`crate::ABI_VERSION`]
+pub const COOP_ABI_VERSION: u32 = 1;
+
+alloy_sol_types::sol! {
+    struct CoopData {
+        uint32 abiVersion;
+        uint32 rulesVersion;
+        uint32 mode;
+        uint64 timestamp;
+        bytes32 replayHash;
+        bytes32[2] playerNameHashes;
+        uint32[2] scores;
+        uint32 wave;
+        uint32 verified;
+    }
+}
+
+/// Something that happened during a co-op tick
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CoopEvent {
+    /// `score` is the killing player's score after the kill
+    EnemyKilled {
+        player: usize,
+        enemy: u32,
+        x: f64,
+        y: f64,
+        score: u32,
+    },
+    /// An enemy reached `player`; the shared `lives` are unchanged while they are invulnerable
+    PlayerHit {
+        player: usize,
+        enemy: u32,
+        lives: u32,
+    },
+    WaveStarted {
+        wave: u32,
+    },
+    GameOver {
+        scores: [u32; PLAYERS],
+        wave: u32,
+    },
+}
+
+/// State of one co-op game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoopSimulation {
+    mode: GameMode,
+    rules: ModeRules,
+    rng: Rng,
+    tick: u64,
+    wave: u32,
+    /// Shared by both players
+    lives: u32,
+    next_enemy_id: u32,
+    over: bool,
+    players: [Player; PLAYERS],
+    scores: [u32; PLAYERS],
+    kills: [u32; PLAYERS],
+    enemies: Vec<Enemy>,
+}
+
+impl CoopSimulation {
+    /// Start a co-op game of `mode` with the first wave spawned
+    pub fn new(mode: GameMode, seed: u64) -> Self {
+        let rules = mode.rules();
+        let mut sim = Self {
+            mode,
+            rules,
+            rng: Rng::new(seed),
+            tick: 0,
+            wave: 1,
+            lives: rules.lives,
+            next_enemy_id: 0,
+            over: false,
+            players: [
+                Player::at(ARENA_WIDTH / 3.0, ARENA_HEIGHT / 2.0),
+                Player::at(ARENA_WIDTH * 2.0 / 3.0, ARENA_HEIGHT / 2.0),
+            ],
+            scores: [0; PLAYERS],
+            kills: [0; PLAYERS],
+            enemies: Vec::new(),
+        };
+        sim.start_wave();
+        sim
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Lives left in the shared pool
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn players(&self) -> &[Player; PLAYERS] {
+        &self.players
+    }
+
+    /// Each player's score from their own kills
+    pub fn scores(&self) -> [u32; PLAYERS] {
+        self.scores
+    }
+
+    pub fn kills(&self) -> [u32; PLAYERS] {
+        self.kills
+    }
+
+    pub fn team_score(&self) -> u32 {
+        self.scores.iter().fold(0, |total, &score| total.saturating_add(score))
+    }
+
+    pub fn enemies(&self) -> &[Enemy] {
+        &self.enemies
+    }
+
+    /// SHA-256 over everything that affects later ticks
+    pub fn state_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.mode.id().to_le_bytes());
+        hasher.update(self.rng.state().to_le_bytes());
+        hasher.update(self.tick.to_le_bytes());
+        hasher.update(self.wave.to_le_bytes());
+        hasher.update(self.lives.to_le_bytes());
+        hasher.update([self.over as u8]);
+        for index in 0..PLAYERS {
+            let player = &self.players[index];
+            hasher.update(self.scores[index].to_le_bytes());
+            hasher.update(self.kills[index].to_le_bytes());
+            hasher.update(player.x.to_le_bytes());
+            hasher.update(player.y.to_le_bytes());
+            hasher.update(player.blade_angle.to_le_bytes());
+            hasher.update(player.invulnerable_ticks.to_le_bytes());
+        }
+        hasher.update((self.enemies.len() as u32).to_le_bytes());
+        for enemy in &self.enemies {
+            hasher.update(enemy.id.to_le_bytes());
+            hasher.update(enemy.x.to_le_bytes());
+            hasher.update(enemy.y.to_le_bytes());
+            hasher.update(enemy.speed.to_le_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Advance one tick with each player's input; does nothing once the game is over
+    pub fn step(&mut self, inputs: [Input; PLAYERS]) -> Vec<CoopEvent> {
+        let mut events = Vec::new();
+        if self.over {
+            return events;
+        }
+        self.tick += 1;
+
+        for (player, input) in self.players.iter_mut().zip(inputs) {
+            player.apply(input);
+        }
+
+        // Newest enemies first, as in the single-player game
+        let mut index = self.enemies.len();
+        while index > 0 && !self.over {
+            index -= 1;
+            let target = self.nearest_player(&self.enemies[index]);
+            let enemy = &mut self.enemies[index];
+            sim::chase(enemy, &self.players[target]);
+            let enemy = *enemy;
+
+            // The first player's blade takes the kill if both connect
+            let killer = (0..PLAYERS).find(|&p| sim::blade_hits(&self.players[p], &enemy));
+            let hit = (0..PLAYERS).find(|&p| sim::touches_player(&self.players[p], &enemy));
+            if sim::out_of_arena(&enemy) {
+                self.enemies.remove(index);
+            } else if let Some(player) = killer {
+                self.enemies.remove(index);
+                self.scores[player] =
+                    self.scores[player].saturating_add(self.rules.points_per_kill);
+                self.kills[player] += 1;
+                events.push(CoopEvent::EnemyKilled {
+                    player,
+                    enemy: enemy.id,
+                    x: enemy.x,
+                    y: enemy.y,
+                    score: self.scores[player],
+                });
+            } else if let Some(player) = hit {
+                self.enemies.remove(index);
+                if self.players[player].invulnerable_ticks == 0 {
+                    self.lives = self.lives.saturating_sub(1);
+                    self.players[player].invulnerable_ticks = INVULNERABLE_TICKS;
+                }
+                events.push(CoopEvent::PlayerHit { player, enemy: enemy.id, lives: self.lives });
+                self.over = self.lives == 0;
+            }
+        }
+
+        let time_up = self
+            .rules
+            .time_limit_secs
+            .is_some_and(|limit| self.tick >= u64::from(limit) * u64::from(TICK_RATE));
+        if time_up {
+            self.over = true;
+        }
+
+        if self.over {
+            events.push(CoopEvent::GameOver { scores: self.scores, wave: self.wave });
+        } else if self.enemies.is_empty() {
+            self.wave += 1;
+            self.start_wave();
+            events.push(CoopEvent::WaveStarted { wave: self.wave });
+        }
+        events
+    }
+
+    /// Index of the player closest to `enemy`, the first player on a tie
+    fn nearest_player(&self, enemy: &Enemy) -> usize {
+        let distance = |player: &Player| sim::distance(player.x - enemy.x, player.y - enemy.y);
+        let mut nearest = 0;
+        for index in 1..PLAYERS {
+            if distance(&self.players[index]) < distance(&self.players[nearest]) {
+                nearest = index;
+            }
+        }
+        nearest
+    }
+
+    fn start_wave(&mut self) {
+        sim::spawn_wave(
+            &mut self.rng,
+            &self.rules,
+            self.wave,
+            &mut self.next_enemy_id,
+            &mut self.enemies,
+        );
+    }
+}
+
+/// Seed and both players' per-tick inputs of one co-op game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoopReplay {
+    pub mode: GameMode,
+    pub seed: u64,
+    /// Both players' inputs per tick, starting at tick 1
+    pub inputs: Vec<[Input; PLAYERS]>,
+}
+
+impl CoopReplay {
+    pub fn new(mode: GameMode, seed: u64) -> Self {
+        Self { mode, seed, inputs: Vec::new() }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let inputs: Vec<Input> = self.inputs.iter().flatten().copied().collect();
+        replay::encode_frames(
+            COOP_REPLAY_MAGIC,
+            COOP_REPLAY_VERSION,
+            self.mode,
+            self.seed,
+            PLAYERS,
+            &inputs,
+        )
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let (mode, seed, inputs) =
+            replay::decode_frames(bytes, COOP_REPLAY_MAGIC, COOP_REPLAY_VERSION, PLAYERS)?;
+        let inputs = inputs.chunks_exact(PLAYERS).map(|tick| [tick[0], tick[1]]).collect();
+        Ok(Self { mode, seed, inputs })
+    }
+
+    /// Run every tick from a fresh game; ticks after game over are ignored
+    pub fn run(&self) -> CoopSimulation {
+        let mut sim = CoopSimulation::new(self.mode, self.seed);
+        for inputs in &self.inputs {
+            if sim.is_over() {
+                break;
+            }
+            sim.step(*inputs);
+        }
+        sim
+    }
+}
+
+/// Run a co-op replay arriving in `chunks` as [`replay::run_chunks`] does
+/// a single-player one
+pub fn run_chunks<C: AsRef<[u8]>>(
+    chunks: impl IntoIterator<Item = C>,
+) -> ([u8; 32], Result<(ReplayHeader, CoopSimulation), ReplayError>) {
+    let mut stream = ReplayStream::with_layout(COOP_REPLAY_MAGIC, COOP_REPLAY_VERSION, PLAYERS);
+    let mut sim = None;
+    for chunk in chunks {
+        stream.push(chunk.as_ref());
+        if let Some(header) = stream.header() {
+            let sim = sim.get_or_insert_with(|| CoopSimulation::new(header.mode, header.seed));
+            while let Some(inputs) = stream.next_inputs::<PLAYERS>() {
+                if !sim.is_over() {
+                    sim.step(inputs);
+                }
+            }
+        }
+    }
+    let (hash, header) = stream.finish();
+    let run = header.map(|header| {
+        (header, sim.unwrap_or_else(|| CoopSimulation::new(header.mode, header.seed)))
+    });
+    (hash, run)
+}
+
+/// Public values committed by the co-op guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoopPublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    /// [`GameMode::id`] of the replayed game
+    pub mode: u32,
+    pub timestamp: u64,
+    /// [`replay_hash`](crate::replay::replay_hash) of the encoded co-op replay
+    pub replay_hash: [u8; 32],
+    /// Name hashes in input order
+    pub player_name_hashes: [[u8; 32]; PLAYERS],
+    /// Scores in input order
+    pub scores: [u32; PLAYERS],
+    /// Wave the game ended on
+    pub wave: u32,
+    pub verified: u32,
+}
+
+impl CoopPublicValues {
+    pub fn team_score(&self) -> u32 {
+        self.scores.iter().fold(0, |total, &score| total.saturating_add(score))
+    }
+}
+
+impl From<CoopPublicValues> for CoopData {
+    fn from(value: CoopPublicValues) -> Self {
+        Self {
+            abiVersion: COOP_ABI_VERSION,
+            rulesVersion: value.rules_version,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            replayHash: FixedBytes(value.replay_hash),
+            playerNameHashes: value.player_name_hashes.map(FixedBytes),
+            scores: value.scores,
+            wave: value.wave,
+            verified: value.verified,
+        }
+    }
+}
+
+impl From<CoopData> for CoopPublicValues {
+    fn from(value: CoopData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            replay_hash: value.replayHash.0,
+            player_name_hashes: value.playerNameHashes.map(|hash| hash.0),
+            scores: value.scores,
+            wave: value.wave,
+            verified: value.verified,
+        }
+    }
+}
+
+/// Encode in the current co-op layout
+pub fn encode(values: CoopPublicValues) -> Vec<u8> {
+    CoopData::abi_encode(&CoopData::from(values))
+}
+
+/// Decode public values committed by the co-op guest
+pub fn decode(bytes: &[u8]) -> Result<CoopPublicValues, crate::abi::DecodeError> {
+    let values = CoopPublicValues::from(CoopData::abi_decode(bytes, true)?);
+    if values.abi_version != COOP_ABI_VERSION {
+        return Err(crate::abi::DecodeError::UnsupportedVersion(values.abi_version));
+    }
+    Ok(values)
+}
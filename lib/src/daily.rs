@@ -0,0 +1,164 @@
+//! Daily challenges: one seed per UTC day that everyone plays.
+//!
+//! The seed is the first eight bytes, little-endian, of
+//! `SHA-256("<DAILY_DOMAIN>:<YYYY-MM-DD>")`, so any client can derive it from
+//! the date alone. The daily guest recomputes it from the committed day,
+//! rejects replays played on any other seed and requires the game's
+//! timestamp to fall within that day.
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Published domain string daily seeds are derived under
+pub const DAILY_DOMAIN: &str = "bladewarrior-daily-v1";
+
+/// Layout version of [`DailyData`], independent of [`crate::ABI_VERSION`]
+pub const DAILY_ABI_VERSION: u32 = 1;
+
+pub const SECS_PER_DAY: u64 = 86_400;
+
+alloy_sol_types::sol! {
+    struct DailyData {
+        uint32 abiVersion;
+        uint32 rulesVersion;
+        uint32 day;
+        uint64 seed;
+        uint32 mode;
+        uint64 timestamp;
+        bytes32 playerNameHash;
+        uint32 score;
+        bytes32 replayHash;
+        uint32 verified;
+    }
+}
+
+/// UTC day containing `timestamp`, counted from 1970-01-01
+pub fn day_of(timestamp: u64) -> u32 {
+    (timestamp / SECS_PER_DAY) as u32
+}
+
+/// Unix time `day` starts at; it ends one [`SECS_PER_DAY`] later
+pub fn day_start(day: u32) -> u64 {
+    u64::from(day) * SECS_PER_DAY
+}
+
+/// `day` as a `YYYY-MM-DD` date
+pub fn date(day: u32) -> String {
+    // Days to civil date in the proleptic Gregorian calendar, using eras of 400 years
+    let z = i64::from(day) + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// Day of a `YYYY-MM-DD` date, if it is a valid date from 1970-01-01 on
+pub fn parse_date(date: &str) -> Option<u32> {
+    let mut parts = date.splitn(3, '-');
+    let (year, month, day_of_month) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day_of_month.len() != 2 {
+        return None;
+    }
+    let year: i64 = year.parse().ok()?;
+    let month: i64 = month.parse().ok()?;
+    let day_of_month: i64 = day_of_month.parse().ok()?;
+    if !(1..=12).contains(&month) || day_of_month < 1 {
+        return None;
+    }
+
+    // Inverse of `date`, with the year starting in March
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day_of_month - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let day = u32::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+    // Rejects days past the end of the month, such as 2023-02-29
+    (self::date(day) == date).then_some(day)
+}
+
+/// Seed everyone plays on `day`
+pub fn daily_seed(day: u32) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(DAILY_DOMAIN.as_bytes());
+    hasher.update(b":");
+    hasher.update(date(day).as_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+    u64::from_le_bytes(digest[..8].try_into().unwrap())
+}
+
+/// Public values committed by the daily guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyPublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    /// Day of the challenge, see [`day_of`]
+    pub day: u32,
+    /// [`daily_seed`] of the day, recomputed by the guest
+    pub seed: u64,
+    /// [`GameMode::id`](crate::GameMode::id) of the replayed game
+    pub mode: u32,
+    pub timestamp: u64,
+    pub player_name_hash: [u8; 32],
+    pub score: u32,
+    /// [`replay_hash`](crate::replay::replay_hash) of the encoded replay
+    pub replay_hash: [u8; 32],
+    pub verified: u32,
+}
+
+impl From<DailyPublicValues> for DailyData {
+    fn from(value: DailyPublicValues) -> Self {
+        Self {
+            abiVersion: DAILY_ABI_VERSION,
+            rulesVersion: value.rules_version,
+            day: value.day,
+            seed: value.seed,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            playerNameHash: FixedBytes(value.player_name_hash),
+            score: value.score,
+            replayHash: FixedBytes(value.replay_hash),
+            verified: value.verified,
+        }
+    }
+}
+
+impl From<DailyData> for DailyPublicValues {
+    fn from(value: DailyData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            day: value.day,
+            seed: value.seed,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            replay_hash: value.replayHash.0,
+            verified: value.verified,
+        }
+    }
+}
+
+/// Encode in the current daily layout
+pub fn encode(values: DailyPublicValues) -> Vec<u8> {
+    DailyData::abi_encode(&DailyData::from(values))
+}
+
+/// Decode public values committed by the daily guest
+pub fn decode(bytes: &[u8]) -> Result<DailyPublicValues, crate::abi::DecodeError> {
+    let values = DailyPublicValues::from(DailyData::abi_decode(bytes, true)?);
+    if values.abi_version != DAILY_ABI_VERSION {
+        return Err(crate::abi::DecodeError::UnsupportedVersion(values.abi_version));
+    }
+    Ok(values)
+}
@@ -0,0 +1,223 @@
+//! Fixed-point numbers and deterministic trigonometry for the simulation.
+//!
+//! Floating-point `atan2`, `sin`, `cos` and `hypot` come from each
+//! platform's math library and may differ in the last bits between the
+//! native, wasm and guest builds. The simulation computes them here instead,
+//! in signed 32.32 fixed point with integer arithmetic only, so every build
+//! gets the same bits. Angles are in radians; `sqrt` and `hypot` round down
+//! to the nearest step, and `atan2`, `sin` and `cos` use 32 CORDIC
+//! iterations, which keeps them within 1e-8 of the exact value.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use serde::{Deserialize, Serialize};
+
+/// Fractional bits of a [`Fixed`]
+pub const FRAC_BITS: u32 = 32;
+
+/// CORDIC iterations run by `atan2` and `sin_cos`, one per fractional bit
+const CORDIC_ITERATIONS: usize = 32;
+
+/// `atan(2^-i)` for each CORDIC iteration, in 32.32 fixed point
+const ATAN_TABLE: [i64; CORDIC_ITERATIONS] = [
+    3373259426, 1991351318, 1052175346, 534100635, 268086748, 134174063, 67103403, 33553749,
+    16777131, 8388597, 4194303, 2097152, 1048576, 524288, 262144, 131072, 65536, 32768, 16384,
+    8192, 4096, 2048, 1024, 512, 256, 128, 64, 32, 16, 8, 4, 2,
+];
+
+/// Inverse of the CORDIC gain after [`CORDIC_ITERATIONS`], in 32.32 fixed point
+const CORDIC_INV_GAIN: i64 = 2608131496;
+
+/// Signed fixed-point number with [`FRAC_BITS`] fractional bits
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << FRAC_BITS);
+    pub const PI: Fixed = Fixed(13493037705);
+    pub const HALF_PI: Fixed = Fixed(6746518852);
+    pub const TWO_PI: Fixed = Fixed(26986075409);
+
+    /// Number whose bits are `raw`, i.e. `raw / 2^32`
+    pub const fn from_raw(raw: i64) -> Self {
+        Self(raw)
+    }
+
+    pub const fn raw(self) -> i64 {
+        self.0
+    }
+
+    pub const fn from_int(value: i32) -> Self {
+        Self((value as i64) << FRAC_BITS)
+    }
+
+    /// Nearest fixed-point number to `value`, saturating at the ends of the
+    /// range; NaN becomes zero
+    pub fn from_f64(value: f64) -> Self {
+        // Scaling by a power of two and rounding are exact in IEEE 754
+        Self((value * (1u64 << FRAC_BITS) as f64).round() as i64)
+    }
+
+    /// The value as an `f64`, exact for magnitudes below 2^21
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1u64 << FRAC_BITS) as f64
+    }
+
+    pub fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    /// Square root, rounded down; zero for negative numbers
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self(isqrt((self.0 as u128) << FRAC_BITS) as i64)
+    }
+
+    /// Length of the vector `(self, other)`, rounded down, without the
+    /// intermediate squares overflowing; saturates at the top of the range
+    pub fn hypot(self, other: Self) -> Self {
+        let (x, y) = (self.0.unsigned_abs() as u128, other.0.unsigned_abs() as u128);
+        // Both squares are below 2^126, so their sum fits
+        let length = isqrt(x * x + y * y);
+        Self(i64::try_from(length).unwrap_or(i64::MAX))
+    }
+
+    /// Angle of the vector `(x, self)` from the positive x axis, in
+    /// `(-PI, PI]`; zero for the zero vector
+    pub fn atan2(self, x: Self) -> Self {
+        let (mut x, mut y) = (x.0, self.0);
+        if x == 0 && y == 0 {
+            return Self::ZERO;
+        }
+
+        // Scale so the larger side has its top bit at 2^59: as precise as
+        // possible while the CORDIC gain still cannot overflow
+        let top = 63 - (x.unsigned_abs() | y.unsigned_abs()).leading_zeros() as i32;
+        let shift = 59 - top;
+        if shift >= 0 {
+            (x, y) = (x << shift, y << shift);
+        } else {
+            (x, y) = (x >> -shift, y >> -shift);
+        }
+
+        // Turn the left half plane into the right one
+        let mut angle = 0;
+        if x < 0 {
+            angle = if y >= 0 { Self::PI.0 } else { -Self::PI.0 };
+            (x, y) = (-x, -y);
+        }
+
+        // Rotate the vector onto the x axis, adding up the angles turned
+        for (i, step) in ATAN_TABLE.iter().enumerate() {
+            let (dx, dy) = (x >> i, y >> i);
+            if y > 0 {
+                (x, y) = (x + dy, y - dx);
+                angle += step;
+            } else {
+                (x, y) = (x - dy, y + dx);
+                angle -= step;
+            }
+        }
+        // Rounding can carry a vector on the negative x axis past the end of the range
+        Self(angle.clamp(-Self::PI.0 + 1, Self::PI.0))
+    }
+
+    /// Sine and cosine of the angle `self`
+    pub fn sin_cos(self) -> (Self, Self) {
+        // Bring the angle into [-PI/2, PI/2], where CORDIC converges,
+        // flipping the result for the half turn taken off
+        let mut angle = self.0.rem_euclid(Self::TWO_PI.0);
+        if angle > Self::PI.0 {
+            angle -= Self::TWO_PI.0;
+        }
+        let flip = angle.abs() > Self::HALF_PI.0;
+        if angle > Self::HALF_PI.0 {
+            angle -= Self::PI.0;
+        } else if angle < -Self::HALF_PI.0 {
+            angle += Self::PI.0;
+        }
+
+        // Rotate the unit vector, pre-scaled by the gain, through the angle
+        let (mut x, mut y) = (CORDIC_INV_GAIN, 0i64);
+        for (i, step) in ATAN_TABLE.iter().enumerate() {
+            let (dx, dy) = (x >> i, y >> i);
+            if angle >= 0 {
+                (x, y) = (x - dy, y + dx);
+                angle -= step;
+            } else {
+                (x, y) = (x + dy, y - dx);
+                angle += step;
+            }
+        }
+        if flip {
+            (Self(-y), Self(-x))
+        } else {
+            (Self(y), Self(x))
+        }
+    }
+
+    pub fn sin(self) -> Self {
+        self.sin_cos().0
+    }
+
+    pub fn cos(self) -> Self {
+        self.sin_cos().1
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: Fixed) -> Fixed {
+        Fixed(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    /// Product rounded toward negative infinity, saturating
+    fn mul(self, other: Fixed) -> Fixed {
+        let product = (self.0 as i128 * other.0 as i128) >> FRAC_BITS;
+        Fixed(product.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+}
+
+/// Largest integer whose square is at most `value`
+fn isqrt(value: u128) -> u128 {
+    // Bit by bit, from the highest power of four not above `value`
+    let mut remainder = value;
+    let mut root = 0u128;
+    let mut bit = if value == 0 { 0 } else { 1u128 << ((127 - value.leading_zeros()) & !1) };
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
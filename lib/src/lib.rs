@@ -1,48 +1,76 @@
 use serde::{Serialize, Deserialize};
-use alloy_sol_types::private::FixedBytes;
+use sha2::{Digest, Sha256};
+
+/// Maximum valid score accepted by the guest
+pub const MAX_SCORE: u32 = 10000;
+
+/// Maximum points per minute of play accepted by the guest, in any mode
+pub const MAX_SCORE_PER_MINUTE: u32 = 16000;
+
+/// Maximum difference in seconds between the game timestamp and the prover's current time
+pub const MAX_TIMESTAMP_SKEW_SECS: u64 = 3600;
+
+/// Layout version of the committed public values, see [`abi`]
+pub const ABI_VERSION: u32 = 4;
+
+/// Version of the rules the guest checks scores against
+///
+/// Bump whenever guest validation changes so proofs made under different
+/// rules can be told apart.
+pub const RULES_VERSION: u32 = 5;
 
 /// Structure for game score verification public data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameScorePublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    /// [`GameMode::id`] of the mode the score was played in
+    pub mode: u32,
     pub timestamp: u64,
     pub player_name_hash: [u8; 32],
     pub score: u32,
     pub game_hash: [u8; 32],
     pub verified: u32,
+    /// [`checkpoint::merkle_root`] of the per-wave checkpoints
+    pub checkpoint_root: [u8; 32],
+    pub checkpoint_count: u32,
+    /// [`session::Session::duration_secs`] of the game
+    pub duration_secs: u32,
+    /// Ticks the game's simulation ran
+    pub ticks: u64,
 }
 
-/// ABI encoding for GameScorePublicValues
-pub mod abi {
-    use super::*;
-    use alloy_sol_types::{sol, SolType};
-
-    sol! {
-        struct GameScoreData {
-            uint64 timestamp;
-            bytes32 playerNameHash;
-            uint32 score;
-            bytes32 gameHash;
-            uint32 verified;
-        }
-    }
-
-    impl From<GameScorePublicValues> for GameScoreData {
-        fn from(value: GameScorePublicValues) -> Self {
-            Self {
-                timestamp: value.timestamp,
-                playerNameHash: FixedBytes(value.player_name_hash),
-                score: value.score,
-                gameHash: FixedBytes(value.game_hash),
-                verified: value.verified,
-            }
-        }
-    }
-
-    pub fn encode(values: GameScorePublicValues) -> Vec<u8> {
-        GameScoreData::abi_encode(&GameScoreData::from(values))
-    }
+/// SHA-256 of the player name, committed instead of the name itself
+pub fn player_name_hash(player_name: &[u8]) -> [u8; 32] {
+    Sha256::digest(player_name).into()
 }
 
+/// Hash identifying a game: SHA-256 over the name bytes followed by the
+/// score and timestamp in little-endian order
+pub fn game_hash(player_name: &str, score: u32, timestamp: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(player_name.as_bytes());
+    hasher.update(score.to_le_bytes());
+    hasher.update(timestamp.to_le_bytes());
+    hasher.finalize().into()
+}
+
+pub mod abi;
+pub mod achievements;
+pub mod checkpoint;
+pub mod coop;
+pub mod daily;
+pub mod fixed;
+pub mod modes;
+pub mod rank;
+pub mod replay;
+pub mod session;
+pub mod sim;
+pub mod tier;
+pub mod tournament;
+
+pub use modes::GameMode;
+
 /// Game score verification
 #[cfg(feature = "sp1-zkvm")]
 pub fn verify_game_score(score: u32, max_score: u32) -> bool {
@@ -0,0 +1,165 @@
+//! Game modes and the scoring rules each one is proved under.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{MAX_SCORE, MAX_SCORE_PER_MINUTE};
+
+/// Game mode, committed in the public values by its [`id`](GameMode::id)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    /// Waves continue until all lives are lost
+    #[default]
+    Endless,
+    /// Score as much as possible in three minutes
+    TimeAttack,
+    /// A single life with double points per kill
+    Hardcore,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Endless, GameMode::TimeAttack, GameMode::Hardcore];
+
+    pub fn id(&self) -> u32 {
+        match self {
+            GameMode::Endless => 0,
+            GameMode::TimeAttack => 1,
+            GameMode::Hardcore => 2,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Endless => "endless",
+            GameMode::TimeAttack => "time_attack",
+            GameMode::Hardcore => "hardcore",
+        }
+    }
+
+    pub fn rules(&self) -> ModeRules {
+        match self {
+            GameMode::Endless => ModeRules {
+                lives: 3,
+                time_limit_secs: None,
+                points_per_kill: 10,
+                max_score: MAX_SCORE,
+                max_score_per_minute: 7500,
+                waves: WaveParams::STANDARD,
+            },
+            GameMode::TimeAttack => ModeRules {
+                lives: 3,
+                time_limit_secs: Some(180),
+                points_per_kill: 10,
+                max_score: 5000,
+                max_score_per_minute: 9000,
+                waves: WaveParams {
+                    base_enemies: 5,
+                    enemies_per_wave: 2,
+                    max_enemies: 20,
+                    base_speed_permille: 1200,
+                    speed_per_wave_permille: 100,
+                    max_speed_permille: 2500,
+                },
+            },
+            GameMode::Hardcore => ModeRules {
+                lives: 1,
+                time_limit_secs: None,
+                points_per_kill: 20,
+                max_score: MAX_SCORE,
+                max_score_per_minute: MAX_SCORE_PER_MINUTE,
+                waves: WaveParams {
+                    speed_per_wave_permille: 150,
+                    max_speed_permille: 3000,
+                    ..WaveParams::STANDARD
+                },
+            },
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name() == normalized)
+            .ok_or_else(|| format!("unknown game mode {} (expected endless, time_attack or hardcore)", s))
+    }
+}
+
+/// How waves grow; speeds are multipliers of the base enemy speed in thousandths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaveParams {
+    pub base_enemies: u32,
+    pub enemies_per_wave: u32,
+    pub max_enemies: u32,
+    pub base_speed_permille: u32,
+    pub speed_per_wave_permille: u32,
+    pub max_speed_permille: u32,
+}
+
+impl WaveParams {
+    /// Wave growth of the original game
+    pub const STANDARD: WaveParams = WaveParams {
+        base_enemies: 3,
+        enemies_per_wave: 1,
+        max_enemies: 15,
+        base_speed_permille: 1000,
+        speed_per_wave_permille: 100,
+        max_speed_permille: 2500,
+    };
+
+    /// Number of enemies spawned in `wave` (1-based)
+    pub fn enemies_in_wave(&self, wave: u32) -> u32 {
+        self.base_enemies
+            .saturating_add(self.enemies_per_wave.saturating_mul(wave))
+            .min(self.max_enemies)
+    }
+
+    /// Enemy speed multiplier for `wave`, in thousandths
+    pub fn speed_permille(&self, wave: u32) -> u32 {
+        self.base_speed_permille
+            .saturating_add(self.speed_per_wave_permille.saturating_mul(wave))
+            .min(self.max_speed_permille)
+    }
+}
+
+/// Scoring rules of one mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeRules {
+    pub lives: u32,
+    pub time_limit_secs: Option<u32>,
+    pub points_per_kill: u32,
+    pub max_score: u32,
+    /// Most points a minute of play may earn, a little above what perfect
+    /// aim reaches in the simulation; see [`crate::session`]
+    pub max_score_per_minute: u32,
+    pub waves: WaveParams,
+}
+
+impl ModeRules {
+    /// Score is within the cap and reachable in whole kills
+    pub fn score_valid(&self, score: u32) -> bool {
+        score <= self.max_score && self.whole_kills(score)
+    }
+
+    /// Score is a whole number of kills
+    pub fn whole_kills(&self, score: u32) -> bool {
+        score % self.points_per_kill == 0
+    }
+}
@@ -0,0 +1,206 @@
+//! Rank-annotated leaderboard trees and private top-K membership.
+//!
+//! A board is committed as the Merkle root over its rows in rank order, each
+//! leaf carrying its own rank, so leaf `i` must be rank `i + 1`, hashed
+//! together with the number of rows. The rank guest takes a row and its
+//! [`InclusionProof`] as private input and commits only the root, the board
+//! size, `k` and the player's name hash, showing the player is in the top `k`
+//! without revealing their score or anyone else's row.
+//!
+//! The guest sees one row, so it cannot tell whether the board behind a root
+//! is well ordered; [`check_board`] runs where the board is built, and a root
+//! is only as trustworthy as whoever published it.
+//!
+//! Inner nodes are hashed as in [`crate::checkpoint`]; leaves and the root
+//! use their own prefixes so a checkpoint can never pass for a board row.
+
+use std::fmt;
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::checkpoint::{self, InclusionProof};
+
+/// Layout version of [`RankData`], independent of [`crate::ABI_VERSION`]
+pub const RANK_ABI_VERSION: u32 = 1;
+
+const LEAF_PREFIX: u8 = 0x02;
+const ROOT_PREFIX: u8 = 0x03;
+
+alloy_sol_types::sol! {
+    struct RankData {
+        uint32 abiVersion;
+        uint32 mode;
+        bytes32 boardRoot;
+        uint32 leafCount;
+        uint32 k;
+        bytes32 playerNameHash;
+        uint32 member;
+    }
+}
+
+/// One row of a committed board, taken from the public values of its proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankLeaf {
+    /// 1-based position on the board
+    pub rank: u32,
+    pub mode: u32,
+    pub player_name_hash: [u8; 32],
+    pub score: u32,
+    pub timestamp: u64,
+    pub game_hash: [u8; 32],
+}
+
+impl RankLeaf {
+    pub fn leaf_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(self.rank.to_be_bytes());
+        hasher.update(self.mode.to_be_bytes());
+        hasher.update(self.player_name_hash);
+        hasher.update(self.score.to_be_bytes());
+        hasher.update(self.timestamp.to_be_bytes());
+        hasher.update(self.game_hash);
+        hasher.finalize().into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardError {
+    /// Row `index` does not carry rank `index + 1`
+    RankOutOfOrder { index: usize, rank: u32 },
+    /// Row `index` outscores the row above it
+    ScoreOutOfOrder { index: usize },
+    /// Row `index` is from a different mode than the first row
+    MixedModes { index: usize },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::RankOutOfOrder { index, rank } => {
+                write!(f, "row {} has rank {}, expected {}", index, rank, index + 1)
+            }
+            BoardError::ScoreOutOfOrder { index } => {
+                write!(f, "row {} scores higher than the row above it", index)
+            }
+            BoardError::MixedModes { index } => {
+                write!(f, "row {} is from a different mode than the board", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+/// Check rows are ranked 1, 2, ... in order of non-increasing score, all in one mode
+pub fn check_board(leaves: &[RankLeaf]) -> Result<(), BoardError> {
+    for (index, leaf) in leaves.iter().enumerate() {
+        if leaf.rank as usize != index + 1 {
+            return Err(BoardError::RankOutOfOrder { index, rank: leaf.rank });
+        }
+        if leaf.mode != leaves[0].mode {
+            return Err(BoardError::MixedModes { index });
+        }
+        if index > 0 && leaf.score > leaves[index - 1].score {
+            return Err(BoardError::ScoreOutOfOrder { index });
+        }
+    }
+    Ok(())
+}
+
+/// Root committing to a board: its size and the Merkle root over its rows
+pub fn board_root(leaves: &[RankLeaf]) -> [u8; 32] {
+    let tree_root = checkpoint::root_over(leaves.iter().map(RankLeaf::leaf_hash).collect());
+    bind_size(leaves.len() as u32, &tree_root)
+}
+
+fn bind_size(leaf_count: u32, tree_root: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ROOT_PREFIX]);
+    hasher.update(leaf_count.to_be_bytes());
+    hasher.update(tree_root);
+    hasher.finalize().into()
+}
+
+/// Inclusion proof for the row with `rank`
+pub fn rank_proof(leaves: &[RankLeaf], rank: u32) -> Option<InclusionProof> {
+    let index = (rank as usize).checked_sub(1)?;
+    InclusionProof::over_leaves(leaves.iter().map(RankLeaf::leaf_hash).collect(), index)
+}
+
+/// Whether `leaf` is the row at its own rank on the board of
+/// `proof.leaf_count` rows under `root`
+pub fn verify_rank(leaf: &RankLeaf, proof: &InclusionProof, root: &[u8; 32]) -> bool {
+    let Ok(leaf_count) = u32::try_from(proof.leaf_count) else {
+        return false;
+    };
+    leaf.rank >= 1
+        && proof.index == leaf.rank as usize - 1
+        && proof
+            .root_from_leaf(leaf.leaf_hash())
+            .is_some_and(|tree_root| &bind_size(leaf_count, &tree_root) == root)
+}
+
+/// Public values committed by the rank guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankPublicValues {
+    pub abi_version: u32,
+    /// Mode of the board, as recorded in the proved row
+    pub mode: u32,
+    /// Root of a board built by whoever publishes it; the guest checks the
+    /// row against it but not the order of the rows it never sees, so
+    /// verifiers should only accept roots of boards they have checked with
+    /// [`check_board`]
+    pub board_root: [u8; 32],
+    /// Number of rows on the board, bound by `board_root`
+    pub leaf_count: u32,
+    pub k: u32,
+    pub player_name_hash: [u8; 32],
+    /// 1 if the player holds a row ranked `k` or better
+    pub member: u32,
+}
+
+impl From<RankPublicValues> for RankData {
+    fn from(value: RankPublicValues) -> Self {
+        Self {
+            abiVersion: RANK_ABI_VERSION,
+            mode: value.mode,
+            boardRoot: FixedBytes(value.board_root),
+            leafCount: value.leaf_count,
+            k: value.k,
+            playerNameHash: FixedBytes(value.player_name_hash),
+            member: value.member,
+        }
+    }
+}
+
+impl From<RankData> for RankPublicValues {
+    fn from(value: RankData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            mode: value.mode,
+            board_root: value.boardRoot.0,
+            leaf_count: value.leafCount,
+            k: value.k,
+            player_name_hash: value.playerNameHash.0,
+            member: value.member,
+        }
+    }
+}
+
+/// Encode in the current rank layout
+pub fn encode(values: RankPublicValues) -> Vec<u8> {
+    RankData::abi_encode(&RankData::from(values))
+}
+
+/// Decode public values committed by the rank guest
+pub fn decode(bytes: &[u8]) -> Result<RankPublicValues, crate::abi::DecodeError> {
+    let values = RankPublicValues::from(RankData::abi_decode(bytes, true)?);
+    if values.abi_version != RANK_ABI_VERSION {
+        return Err(crate::abi::DecodeError::UnsupportedVersion(values.abi_version));
+    }
+    Ok(values)
+}
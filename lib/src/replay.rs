@@ -0,0 +1,340 @@
+//! Binary replay format: the seed and every tick's input of one game.
+//!
+//! The client records a replay while playing and submits it with the score;
+//! running it through [`Simulation`] reproduces the game exactly. Layout,
+//! all integers little-endian:
+//!
+//! ```text
+//! magic "BWRP" | version u8 | mode u32 | seed u64 | input count u32
+//! then per input: buttons u8 | aim_x i16 | aim_y i16
+//! ```
+//!
+//! Guests read a replay as [`REPLAY_CHUNK_LEN`]-byte chunks through
+//! [`ReplayStream`], hashing and simulating each before reading the next, so
+//! their memory does not grow with the length of the game.
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+use crate::sim::{Input, SimEvent, Simulation};
+use crate::GameMode;
+
+pub const REPLAY_MAGIC: [u8; 4] = *b"BWRP";
+
+/// Layout version written by [`Replay::encode`]
+pub const REPLAY_VERSION: u8 = 1;
+
+/// Bytes per chunk the host splits a replay into for the guest
+pub const REPLAY_CHUNK_LEN: usize = 4096;
+
+const HEADER_LEN: usize = 4 + 1 + 4 + 8 + 4;
+const INPUT_LEN: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub mode: GameMode,
+    pub seed: u64,
+    /// One input per tick, starting at tick 1
+    pub inputs: Vec<Input>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownMode(u32),
+    /// Fewer bytes than the header and input count call for
+    Truncated {
+        expected: usize,
+        actual: usize,
+    },
+    /// Bytes left over after the last input
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::BadMagic => write!(f, "not a replay (bad magic)"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported replay version {}", version)
+            }
+            ReplayError::UnknownMode(mode) => write!(f, "unknown game mode id {}", mode),
+            ReplayError::Truncated { expected, actual } => {
+                write!(f, "replay truncated: expected {} bytes, got {}", expected, actual)
+            }
+            ReplayError::TrailingBytes(count) => {
+                write!(f, "{} unexpected bytes after the last input", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    pub fn new(mode: GameMode, seed: u64) -> Self {
+        Self { mode, seed, inputs: Vec::new() }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        encode_frames(REPLAY_MAGIC, REPLAY_VERSION, self.mode, self.seed, 1, &self.inputs)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let (mode, seed, inputs) = decode_frames(bytes, REPLAY_MAGIC, REPLAY_VERSION, 1)?;
+        Ok(Self { mode, seed, inputs })
+    }
+
+    /// Run every input from a fresh game; inputs after game over are ignored
+    pub fn run(&self) -> Simulation {
+        let mut sim = Simulation::new(self.mode, self.seed);
+        for input in &self.inputs {
+            if sim.is_over() {
+                break;
+            }
+            sim.step(*input);
+        }
+        sim
+    }
+}
+
+/// Encode `inputs_per_tick` inputs per tick in the layout above; the input
+/// count field holds the number of ticks
+pub(crate) fn encode_frames(
+    magic: [u8; 4],
+    version: u8,
+    mode: GameMode,
+    seed: u64,
+    inputs_per_tick: usize,
+    inputs: &[Input],
+) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + inputs.len() * INPUT_LEN);
+    bytes.extend_from_slice(&magic);
+    bytes.push(version);
+    bytes.extend_from_slice(&mode.id().to_le_bytes());
+    bytes.extend_from_slice(&seed.to_le_bytes());
+    bytes.extend_from_slice(&((inputs.len() / inputs_per_tick) as u32).to_le_bytes());
+    for input in inputs {
+        bytes.push(input.buttons);
+        bytes.extend_from_slice(&input.aim_x.to_le_bytes());
+        bytes.extend_from_slice(&input.aim_y.to_le_bytes());
+    }
+    bytes
+}
+
+/// Decode a layout written by [`encode_frames`] into its mode, seed and inputs
+pub(crate) fn decode_frames(
+    bytes: &[u8],
+    magic: [u8; 4],
+    version: u8,
+    inputs_per_tick: usize,
+) -> Result<(GameMode, u64, Vec<Input>), ReplayError> {
+    let truncated = |expected| ReplayError::Truncated { expected, actual: bytes.len() };
+    let header = bytes.get(..HEADER_LEN).ok_or_else(|| truncated(HEADER_LEN))?;
+    let header = decode_header(header, magic, version)?;
+
+    let expected = header.encoded_len(inputs_per_tick).ok_or_else(|| truncated(usize::MAX))?;
+    if bytes.len() < expected {
+        return Err(truncated(expected));
+    }
+    if bytes.len() > expected {
+        return Err(ReplayError::TrailingBytes(bytes.len() - expected));
+    }
+    let inputs = bytes[HEADER_LEN..].chunks_exact(INPUT_LEN).map(decode_input).collect();
+    Ok((header.mode, header.seed, inputs))
+}
+
+/// Decode the [`HEADER_LEN`] header bytes of a layout written by [`encode_frames`]
+fn decode_header(header: &[u8], magic: [u8; 4], version: u8) -> Result<ReplayHeader, ReplayError> {
+    if header[..4] != magic {
+        return Err(ReplayError::BadMagic);
+    }
+    if header[4] != version {
+        return Err(ReplayError::UnsupportedVersion(header[4]));
+    }
+    let mode_id = u32::from_le_bytes(header[5..9].try_into().unwrap());
+    let mode = GameMode::from_id(mode_id).ok_or(ReplayError::UnknownMode(mode_id))?;
+    let seed = u64::from_le_bytes(header[9..17].try_into().unwrap());
+    let ticks = u32::from_le_bytes(header[17..21].try_into().unwrap());
+    Ok(ReplayHeader { mode, seed, ticks })
+}
+
+fn decode_input(bytes: &[u8]) -> Input {
+    Input {
+        buttons: bytes[0],
+        aim_x: i16::from_le_bytes([bytes[1], bytes[2]]),
+        aim_y: i16::from_le_bytes([bytes[3], bytes[4]]),
+    }
+}
+
+/// Mode, seed and length of a replay, from its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayHeader {
+    pub mode: GameMode,
+    pub seed: u64,
+    pub ticks: u32,
+}
+
+impl ReplayHeader {
+    /// Length of the whole encoded replay, `None` if it overflows `usize`
+    fn encoded_len(&self, inputs_per_tick: usize) -> Option<usize> {
+        (self.ticks as usize)
+            .checked_mul(inputs_per_tick * INPUT_LEN)
+            .and_then(|len| len.checked_add(HEADER_LEN))
+    }
+}
+
+/// Decoder for a replay that arrives in chunks, holding only the bytes not
+/// yet decoded. Every byte pushed is hashed, so [`finish`](Self::finish)
+/// returns the same hash as [`replay_hash`] over the whole replay, and
+/// inputs can be taken as soon as their bytes have arrived.
+#[derive(Debug, Clone)]
+pub struct ReplayStream {
+    magic: [u8; 4],
+    version: u8,
+    inputs_per_tick: usize,
+    hasher: Sha256,
+    /// Bytes pushed but not decoded yet, from `offset` on
+    pending: Vec<u8>,
+    offset: usize,
+    header: Option<ReplayHeader>,
+    /// Inputs the header calls for that have not been taken
+    inputs_left: usize,
+    /// Bytes pushed in total
+    len: usize,
+    error: Option<ReplayError>,
+}
+
+impl Default for ReplayStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplayStream {
+    /// Stream of a single-player replay
+    pub fn new() -> Self {
+        Self::with_layout(REPLAY_MAGIC, REPLAY_VERSION, 1)
+    }
+
+    /// Stream of a layout written by [`encode_frames`]
+    pub(crate) fn with_layout(magic: [u8; 4], version: u8, inputs_per_tick: usize) -> Self {
+        Self {
+            magic,
+            version,
+            inputs_per_tick,
+            hasher: Sha256::new(),
+            pending: Vec::new(),
+            offset: 0,
+            header: None,
+            inputs_left: 0,
+            len: 0,
+            error: None,
+        }
+    }
+
+    /// Hash the next chunk of the replay and queue it for decoding
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+        self.len += chunk.len();
+        if self.error.is_some() {
+            return;
+        }
+        self.pending.drain(..self.offset);
+        self.offset = 0;
+        self.pending.extend_from_slice(chunk);
+
+        if self.header.is_none() && self.pending.len() >= HEADER_LEN {
+            match decode_header(&self.pending[..HEADER_LEN], self.magic, self.version) {
+                Ok(header) => {
+                    self.inputs_left = (header.ticks as usize).saturating_mul(self.inputs_per_tick);
+                    self.header = Some(header);
+                    self.offset = HEADER_LEN;
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                    self.pending = Vec::new();
+                }
+            }
+        }
+    }
+
+    /// The header, once its bytes have been pushed and if it is valid
+    pub fn header(&self) -> Option<ReplayHeader> {
+        self.header
+    }
+
+    /// The next tick's `N` inputs, if the header calls for them and their
+    /// bytes have all been pushed
+    pub fn next_inputs<const N: usize>(&mut self) -> Option<[Input; N]> {
+        let len = N * INPUT_LEN;
+        if self.header.is_none() || self.inputs_left < N {
+            return None;
+        }
+        let bytes = self.pending.get(self.offset..self.offset + len)?;
+        let inputs = std::array::from_fn(|i| decode_input(&bytes[i * INPUT_LEN..]));
+        self.offset += len;
+        self.inputs_left -= N;
+        Some(inputs)
+    }
+
+    /// The next input of a single-player replay, see [`next_inputs`](Self::next_inputs)
+    pub fn next_input(&mut self) -> Option<Input> {
+        self.next_inputs::<1>().map(|[input]| input)
+    }
+
+    /// SHA-256 of every byte pushed, and the header if those bytes were a
+    /// whole replay; with the same errors as decoding it in one piece
+    pub fn finish(self) -> ([u8; 32], Result<ReplayHeader, ReplayError>) {
+        let hash = self.hasher.finalize().into();
+        let truncated = |expected| ReplayError::Truncated { expected, actual: self.len };
+        let header = match (self.error, self.header) {
+            (Some(e), _) => Err(e),
+            (None, None) => Err(truncated(HEADER_LEN)),
+            (None, Some(header)) => match header.encoded_len(self.inputs_per_tick) {
+                None => Err(truncated(usize::MAX)),
+                Some(expected) if self.len < expected => Err(truncated(expected)),
+                Some(expected) if self.len > expected => {
+                    Err(ReplayError::TrailingBytes(self.len - expected))
+                }
+                Some(_) => Ok(header),
+            },
+        };
+        (hash, header)
+    }
+}
+
+/// Run a replay arriving in `chunks`, stepping the simulation as each chunk's
+/// inputs arrive and calling `observe` after every tick; inputs after game
+/// over are ignored. Returns the replay hash and, if the chunks formed a
+/// valid replay, its header and the finished game.
+pub fn run_chunks<C: AsRef<[u8]>>(
+    chunks: impl IntoIterator<Item = C>,
+    mut observe: impl FnMut(&Simulation, &[SimEvent]),
+) -> ([u8; 32], Result<(ReplayHeader, Simulation), ReplayError>) {
+    let mut stream = ReplayStream::new();
+    let mut sim = None;
+    for chunk in chunks {
+        stream.push(chunk.as_ref());
+        if let Some(header) = stream.header() {
+            let sim = sim.get_or_insert_with(|| Simulation::new(header.mode, header.seed));
+            while let Some(input) = stream.next_input() {
+                if !sim.is_over() {
+                    let events = sim.step(input);
+                    observe(sim, &events);
+                }
+            }
+        }
+    }
+    let (hash, header) = stream.finish();
+    let run = header
+        .map(|header| (header, sim.unwrap_or_else(|| Simulation::new(header.mode, header.seed))));
+    (hash, run)
+}
+
+/// SHA-256 of an encoded replay, the commitment to the whole game
+pub fn replay_hash(encoded: &[u8]) -> [u8; 32] {
+    Sha256::digest(encoded).into()
+}
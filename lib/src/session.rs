@@ -0,0 +1,71 @@
+//! When a game was played and for how many ticks, for plausibility checks.
+//!
+//! The client reports the wall-clock start and end of a game and how many
+//! ticks the simulation ran. The guest checks the two durations agree at
+//! [`TICK_RATE`], that timed modes stopped at their time limit and that the
+//! score was not earned faster than the mode allows, and commits the
+//! duration so a score can be read alongside how long it took.
+
+use serde::{Deserialize, Serialize};
+
+use crate::modes::ModeRules;
+use crate::sim::TICK_RATE;
+
+/// Seconds the wall-clock duration may differ from the tick count by, on
+/// top of [`DURATION_TOLERANCE_PERCENT`]; covers both timestamps being
+/// rounded to whole seconds
+pub const DURATION_TOLERANCE_SECS: u64 = 2;
+
+/// Share of the tick duration the wall-clock duration may differ from it
+/// by, for frames the client drops or delays
+pub const DURATION_TOLERANCE_PERCENT: u64 = 10;
+
+/// Start, end and length in ticks of one game
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Unix time the game started
+    pub started_at: u64,
+    /// Unix time the game ended
+    pub ended_at: u64,
+    /// Ticks the simulation ran
+    pub ticks: u64,
+}
+
+impl Session {
+    /// Wall-clock length in seconds, 0 if the game ends before it starts
+    pub fn duration_secs(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+
+    /// Length in seconds the ticks take at [`TICK_RATE`], rounded down
+    pub fn tick_secs(&self) -> u64 {
+        self.ticks / u64::from(TICK_RATE)
+    }
+
+    /// Starts before it ends, and ends no later than `timestamp`, when the
+    /// game was submitted
+    pub fn ordered(&self, timestamp: u64) -> bool {
+        self.started_at <= self.ended_at && self.ended_at <= timestamp
+    }
+
+    /// Wall-clock duration agrees with the tick count
+    pub fn duration_matches_ticks(&self) -> bool {
+        let tolerance =
+            DURATION_TOLERANCE_SECS + self.tick_secs() * DURATION_TOLERANCE_PERCENT / 100;
+        self.duration_secs().abs_diff(self.tick_secs()) <= tolerance
+    }
+
+    /// Ran no longer than the mode's time limit, if it has one
+    pub fn within_time_limit(&self, rules: &ModeRules) -> bool {
+        match rules.time_limit_secs {
+            Some(limit) => self.ticks <= u64::from(limit) * u64::from(TICK_RATE),
+            None => true,
+        }
+    }
+
+    /// `score` was earned at no more than `max_score_per_minute` over the ticks played
+    pub fn score_rate_valid(&self, score: u32, max_score_per_minute: u32) -> bool {
+        u64::from(score) * 60 * u64::from(TICK_RATE)
+            <= u64::from(max_score_per_minute).saturating_mul(self.ticks)
+    }
+}
@@ -0,0 +1,421 @@
+//! Deterministic simulation of the game rules.
+//!
+//! This is the reference copy of what `web/js/game.js` does each frame: the
+//! browser steps it through the wasm build, and replays are run through the
+//! same code natively and in the guest. All randomness comes from a seeded
+//! [`Rng`], so a seed and the per-tick inputs fully determine a game.
+//!
+//! The state (positions, speeds and angles) is `f64`. Only square roots and
+//! trigonometry go through [`crate::fixed`], since each platform's math
+//! library may round them differently, and their results are converted back
+//! to `f64`. Everything else is `f64` arithmetic and comparison, which
+//! IEEE 754 rounds the same on every target and Rust never fuses or
+//! reorders.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::checkpoint::Checkpoint;
+use crate::fixed::Fixed;
+use crate::modes::{GameMode, ModeRules};
+
+/// Simulation ticks per second; the client runs one tick per frame at 60 fps
+pub const TICK_RATE: u32 = 60;
+
+/// Size of the game canvas, in pixels
+pub const ARENA_WIDTH: f64 = 800.0;
+pub const ARENA_HEIGHT: f64 = 600.0;
+
+pub const PLAYER_RADIUS: f64 = 20.0;
+/// Pixels moved per tick along each held direction
+pub const PLAYER_SPEED: f64 = 5.0;
+pub const BLADE_LENGTH: f64 = 40.0;
+pub const BLADE_WIDTH: f64 = 6.0;
+pub const ENEMY_SIZE: f64 = 20.0;
+/// Enemy speed in pixels per tick before the wave multiplier
+pub const BASE_ENEMY_SPEED: f64 = 2.0;
+/// Up to this much is added to each enemy's base speed at random
+pub const ENEMY_SPEED_JITTER: f64 = 0.5;
+/// How far outside the canvas enemies spawn and are dropped
+pub const SPAWN_MARGIN: f64 = 50.0;
+/// Ticks the player cannot be hit after losing a life
+pub const INVULNERABLE_TICKS: u32 = 60;
+
+/// SplitMix64 generator shared by every build of the simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub(crate) fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Uniform in `0..n`
+    pub fn below(&mut self, n: u32) -> u32 {
+        (((self.next_u64() >> 32) * u64::from(n)) >> 32) as u32
+    }
+}
+
+/// Player input for one tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Input {
+    /// Held directions, see [`Input::UP`] and friends
+    pub buttons: u8,
+    /// Pointer position on the canvas the blade points at
+    pub aim_x: i16,
+    pub aim_y: i16,
+}
+
+impl Input {
+    pub const UP: u8 = 1 << 0;
+    pub const DOWN: u8 = 1 << 1;
+    pub const LEFT: u8 = 1 << 2;
+    pub const RIGHT: u8 = 1 << 3;
+
+    pub fn new(buttons: u8, aim_x: i16, aim_y: i16) -> Self {
+        Self { buttons, aim_x, aim_y }
+    }
+
+    pub fn held(&self, button: u8) -> bool {
+        self.buttons & button != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub x: f64,
+    pub y: f64,
+    /// Blade direction in radians
+    pub blade_angle: f64,
+    /// Ticks of invulnerability left
+    pub invulnerable_ticks: u32,
+}
+
+impl Player {
+    /// Player standing at `(x, y)` with the blade pointing right
+    pub(crate) fn at(x: f64, y: f64) -> Self {
+        Self { x, y, blade_angle: 0.0, invulnerable_ticks: 0 }
+    }
+
+    /// Move along the held directions and turn the blade toward the pointer
+    pub(crate) fn apply(&mut self, input: Input) {
+        if input.held(Input::UP) {
+            self.y -= PLAYER_SPEED;
+        }
+        if input.held(Input::DOWN) {
+            self.y += PLAYER_SPEED;
+        }
+        if input.held(Input::LEFT) {
+            self.x -= PLAYER_SPEED;
+        }
+        if input.held(Input::RIGHT) {
+            self.x += PLAYER_SPEED;
+        }
+        self.x = self.x.clamp(PLAYER_RADIUS, ARENA_WIDTH - PLAYER_RADIUS);
+        self.y = self.y.clamp(PLAYER_RADIUS, ARENA_HEIGHT - PLAYER_RADIUS);
+        let (dx, dy) = (f64::from(input.aim_x) - self.x, f64::from(input.aim_y) - self.y);
+        self.blade_angle = Fixed::from_f64(dy).atan2(Fixed::from_f64(dx)).to_f64();
+        self.invulnerable_ticks = self.invulnerable_ticks.saturating_sub(1);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Enemy {
+    /// Spawn order, unique within a game
+    pub id: u32,
+    pub x: f64,
+    pub y: f64,
+    /// Pixels per tick
+    pub speed: f64,
+}
+
+/// Something that happened during a tick
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimEvent {
+    EnemyKilled {
+        enemy: u32,
+        x: f64,
+        y: f64,
+        score: u32,
+    },
+    /// An enemy reached the player; `lives` is unchanged while invulnerable
+    PlayerHit {
+        enemy: u32,
+        lives: u32,
+    },
+    WaveStarted {
+        wave: u32,
+    },
+    GameOver {
+        score: u32,
+        wave: u32,
+    },
+}
+
+/// State of one game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Simulation {
+    mode: GameMode,
+    rules: ModeRules,
+    rng: Rng,
+    tick: u64,
+    score: u32,
+    wave: u32,
+    lives: u32,
+    kills: u32,
+    next_enemy_id: u32,
+    over: bool,
+    player: Player,
+    enemies: Vec<Enemy>,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl Simulation {
+    /// Start a game of `mode` with the first wave spawned
+    pub fn new(mode: GameMode, seed: u64) -> Self {
+        let rules = mode.rules();
+        let mut sim = Self {
+            mode,
+            rules,
+            rng: Rng::new(seed),
+            tick: 0,
+            score: 0,
+            wave: 1,
+            lives: rules.lives,
+            kills: 0,
+            next_enemy_id: 0,
+            over: false,
+            player: Player::at(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0),
+            enemies: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        sim.start_wave();
+        sim
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn kills(&self) -> u32 {
+        self.kills
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn enemies(&self) -> &[Enemy] {
+        &self.enemies
+    }
+
+    /// One checkpoint per wave reached, in order
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    /// SHA-256 over everything that affects later ticks
+    pub fn state_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.mode.id().to_le_bytes());
+        hasher.update(self.rng.state.to_le_bytes());
+        hasher.update(self.tick.to_le_bytes());
+        hasher.update(self.score.to_le_bytes());
+        hasher.update(self.wave.to_le_bytes());
+        hasher.update(self.lives.to_le_bytes());
+        hasher.update(self.kills.to_le_bytes());
+        hasher.update([self.over as u8]);
+        hasher.update(self.player.x.to_le_bytes());
+        hasher.update(self.player.y.to_le_bytes());
+        hasher.update(self.player.blade_angle.to_le_bytes());
+        hasher.update(self.player.invulnerable_ticks.to_le_bytes());
+        hasher.update((self.enemies.len() as u32).to_le_bytes());
+        for enemy in &self.enemies {
+            hasher.update(enemy.id.to_le_bytes());
+            hasher.update(enemy.x.to_le_bytes());
+            hasher.update(enemy.y.to_le_bytes());
+            hasher.update(enemy.speed.to_le_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Advance one tick; does nothing once the game is over
+    pub fn step(&mut self, input: Input) -> Vec<SimEvent> {
+        let mut events = Vec::new();
+        if self.over {
+            return events;
+        }
+        self.tick += 1;
+
+        self.player.apply(input);
+
+        // Newest enemies first, as the client has always iterated them
+        let mut index = self.enemies.len();
+        while index > 0 && !self.over {
+            index -= 1;
+            let enemy = &mut self.enemies[index];
+            chase(enemy, &self.player);
+            let enemy = *enemy;
+
+            if out_of_arena(&enemy) {
+                self.enemies.remove(index);
+            } else if blade_hits(&self.player, &enemy) {
+                self.enemies.remove(index);
+                self.score = self.score.saturating_add(self.rules.points_per_kill);
+                self.kills += 1;
+                events.push(SimEvent::EnemyKilled {
+                    enemy: enemy.id,
+                    x: enemy.x,
+                    y: enemy.y,
+                    score: self.score,
+                });
+            } else if touches_player(&self.player, &enemy) {
+                self.enemies.remove(index);
+                if self.player.invulnerable_ticks == 0 {
+                    self.lives = self.lives.saturating_sub(1);
+                    self.player.invulnerable_ticks = INVULNERABLE_TICKS;
+                }
+                events.push(SimEvent::PlayerHit { enemy: enemy.id, lives: self.lives });
+                self.over = self.lives == 0;
+            }
+        }
+
+        let time_up = self
+            .rules
+            .time_limit_secs
+            .is_some_and(|limit| self.tick >= u64::from(limit) * u64::from(TICK_RATE));
+        if time_up {
+            self.over = true;
+        }
+
+        if self.over {
+            events.push(SimEvent::GameOver { score: self.score, wave: self.wave });
+        } else if self.enemies.is_empty() {
+            self.wave += 1;
+            self.start_wave();
+            events.push(SimEvent::WaveStarted { wave: self.wave });
+        }
+        events
+    }
+
+    /// Spawn the current wave just outside the canvas and record its checkpoint
+    fn start_wave(&mut self) {
+        spawn_wave(
+            &mut self.rng,
+            &self.rules,
+            self.wave,
+            &mut self.next_enemy_id,
+            &mut self.enemies,
+        );
+        let checkpoint = Checkpoint {
+            wave: self.wave,
+            tick: self.tick,
+            score: self.score,
+            lives: self.lives,
+            state_hash: self.state_hash(),
+        };
+        self.checkpoints.push(checkpoint);
+    }
+}
+
+/// Spawn `wave` just outside the canvas, numbering enemies from `next_id`
+pub(crate) fn spawn_wave(
+    rng: &mut Rng,
+    rules: &ModeRules,
+    wave: u32,
+    next_id: &mut u32,
+    enemies: &mut Vec<Enemy>,
+) {
+    let waves = rules.waves;
+    let multiplier = f64::from(waves.speed_permille(wave)) / 1000.0;
+    for _ in 0..waves.enemies_in_wave(wave) {
+        let (x, y) = match rng.below(4) {
+            0 => (rng.next_f64() * ARENA_WIDTH, -SPAWN_MARGIN),
+            1 => (ARENA_WIDTH + SPAWN_MARGIN, rng.next_f64() * ARENA_HEIGHT),
+            2 => (rng.next_f64() * ARENA_WIDTH, ARENA_HEIGHT + SPAWN_MARGIN),
+            _ => (-SPAWN_MARGIN, rng.next_f64() * ARENA_HEIGHT),
+        };
+        let speed = (BASE_ENEMY_SPEED + rng.next_f64() * ENEMY_SPEED_JITTER) * multiplier;
+        enemies.push(Enemy { id: *next_id, x, y, speed });
+        *next_id += 1;
+    }
+}
+
+/// Move the enemy one step straight toward the player
+pub(crate) fn chase(enemy: &mut Enemy, player: &Player) {
+    let (dx, dy) = (player.x - enemy.x, player.y - enemy.y);
+    let distance = distance(dx, dy);
+    if distance > 0.0 {
+        enemy.x += dx / distance * enemy.speed;
+        enemy.y += dy / distance * enemy.speed;
+    }
+}
+
+/// Enemies that drift this far out are dropped without scoring
+pub(crate) fn out_of_arena(enemy: &Enemy) -> bool {
+    enemy.x < -SPAWN_MARGIN
+        || enemy.x > ARENA_WIDTH + SPAWN_MARGIN
+        || enemy.y < -SPAWN_MARGIN
+        || enemy.y > ARENA_HEIGHT + SPAWN_MARGIN
+}
+
+/// Whether the enemy reached the player's body
+pub(crate) fn touches_player(player: &Player, enemy: &Enemy) -> bool {
+    distance(player.x - enemy.x, player.y - enemy.y) - ENEMY_SIZE - PLAYER_RADIUS < 1.0
+}
+
+/// Whether the enemy touches the blade segment
+pub(crate) fn blade_hits(player: &Player, enemy: &Enemy) -> bool {
+    let (sin, cos) = Fixed::from_f64(player.blade_angle).sin_cos();
+    let (bx, by) = (cos.to_f64() * BLADE_LENGTH, sin.to_f64() * BLADE_LENGTH);
+    let along =
+        ((enemy.x - player.x) * bx + (enemy.y - player.y) * by) / (BLADE_LENGTH * BLADE_LENGTH);
+    let t = along.clamp(0.0, 1.0);
+    let (closest_x, closest_y) = (player.x + t * bx, player.y + t * by);
+    distance(enemy.x - closest_x, enemy.y - closest_y) < ENEMY_SIZE + BLADE_WIDTH
+}
+
+/// Length of `(dx, dy)`, the same in every build
+pub(crate) fn distance(dx: f64, dy: f64) -> f64 {
+    Fixed::from_f64(dx).hypot(Fixed::from_f64(dy)).to_f64()
+}
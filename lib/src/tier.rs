@@ -0,0 +1,145 @@
+//! Score tiers: proving a score reached a threshold without revealing it.
+//!
+//! The tier guest commits [`TierPublicValues`] instead of
+//! [`GameScorePublicValues`](crate::GameScorePublicValues). It leaves out the
+//! score and the game hash, which together with the player's name would let
+//! anyone recover the score, and the checkpoint root, whose leaves carry the
+//! running score.
+
+use std::fmt;
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+
+/// Layout version of [`TierData`], independent of [`crate::ABI_VERSION`]
+pub const TIER_ABI_VERSION: u32 = 1;
+
+/// Most thresholds a single proof can be made against
+pub const MAX_THRESHOLDS: usize = 32;
+
+alloy_sol_types::sol! {
+    struct TierData {
+        uint32 abiVersion;
+        uint32 rulesVersion;
+        uint32 mode;
+        uint64 timestamp;
+        bytes32 playerNameHash;
+        uint32[] thresholds;
+        uint32 tier;
+        uint32 verified;
+    }
+}
+
+/// Public values committed by the tier guest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierPublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    /// [`GameMode::id`](crate::GameMode::id) of the mode the score was played in
+    pub mode: u32,
+    pub timestamp: u64,
+    pub player_name_hash: [u8; 32],
+    /// Public thresholds the score was compared against, strictly ascending
+    pub thresholds: Vec<u32>,
+    /// Number of thresholds the score reached, see [`tier_for`]
+    pub tier: u32,
+    pub verified: u32,
+}
+
+impl TierPublicValues {
+    /// Lowest score the committed tier guarantees, if any threshold was reached
+    pub fn min_score(&self) -> Option<u32> {
+        (self.tier as usize).checked_sub(1).and_then(|i| self.thresholds.get(i)).copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThresholdError {
+    Empty,
+    TooMany(usize),
+    /// Thresholds must be strictly ascending
+    NotAscending { index: usize },
+}
+
+impl fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdError::Empty => write!(f, "at least one tier threshold is required"),
+            ThresholdError::TooMany(count) => {
+                write!(f, "{} tier thresholds given, at most {} allowed", count, MAX_THRESHOLDS)
+            }
+            ThresholdError::NotAscending { index } => {
+                write!(f, "tier threshold {} is not above the one before it", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThresholdError {}
+
+/// Check thresholds are non-empty, within [`MAX_THRESHOLDS`] and strictly ascending
+pub fn check_thresholds(thresholds: &[u32]) -> Result<(), ThresholdError> {
+    if thresholds.is_empty() {
+        return Err(ThresholdError::Empty);
+    }
+    if thresholds.len() > MAX_THRESHOLDS {
+        return Err(ThresholdError::TooMany(thresholds.len()));
+    }
+    match thresholds.windows(2).position(|pair| pair[0] >= pair[1]) {
+        Some(index) => Err(ThresholdError::NotAscending { index: index + 1 }),
+        None => Ok(()),
+    }
+}
+
+/// Number of `thresholds` at or below `score`
+///
+/// With ascending thresholds, tier `n > 0` means the score is at least
+/// `thresholds[n - 1]` and, unless `n` is the top tier, below `thresholds[n]`.
+pub fn tier_for(score: u32, thresholds: &[u32]) -> u32 {
+    thresholds.iter().filter(|&&threshold| score >= threshold).count() as u32
+}
+
+impl From<TierPublicValues> for TierData {
+    fn from(value: TierPublicValues) -> Self {
+        Self {
+            abiVersion: TIER_ABI_VERSION,
+            rulesVersion: value.rules_version,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            playerNameHash: FixedBytes(value.player_name_hash),
+            thresholds: value.thresholds,
+            tier: value.tier,
+            verified: value.verified,
+        }
+    }
+}
+
+impl From<TierData> for TierPublicValues {
+    fn from(value: TierData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            mode: value.mode,
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            thresholds: value.thresholds,
+            tier: value.tier,
+            verified: value.verified,
+        }
+    }
+}
+
+/// Encode in the current tier layout
+pub fn encode(values: TierPublicValues) -> Vec<u8> {
+    TierData::abi_encode(&TierData::from(values))
+}
+
+/// Decode public values committed by the tier guest
+pub fn decode(bytes: &[u8]) -> Result<TierPublicValues, crate::abi::DecodeError> {
+    let values = TierPublicValues::from(TierData::abi_decode(bytes, true)?);
+    if values.abi_version != TIER_ABI_VERSION {
+        return Err(crate::abi::DecodeError::UnsupportedVersion(values.abi_version));
+    }
+    Ok(values)
+}
@@ -0,0 +1,131 @@
+//! Tournaments: fixed-seed events that scores are proved against.
+//!
+//! Every entrant plays the same seed and mode inside the tournament's time
+//! window. The tournament guest replays an entry, rejects it if it was
+//! played on any other seed or mode or outside the window, and commits
+//! [`TournamentRules::commitment`] as the tournament id so a proof can only
+//! count towards the event it was made for.
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::GameMode;
+
+/// Layout version of [`TournamentData`], independent of [`crate::ABI_VERSION`]
+pub const TOURNAMENT_ABI_VERSION: u32 = 1;
+
+alloy_sol_types::sol! {
+    struct TournamentData {
+        uint32 abiVersion;
+        uint32 rulesVersion;
+        bytes32 tournamentId;
+        uint32 mode;
+        uint64 seed;
+        uint64 timestamp;
+        bytes32 playerNameHash;
+        uint32 score;
+        bytes32 replayHash;
+        uint32 verified;
+    }
+}
+
+/// What every entry to a tournament must have been played under
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TournamentRules {
+    /// Name the organisers refer to the tournament by
+    pub id: String,
+    pub mode: GameMode,
+    pub seed: u64,
+    /// Unix time games may be played from
+    pub starts_at: u64,
+    /// Unix time games must be played before
+    pub ends_at: u64,
+}
+
+impl TournamentRules {
+    /// SHA-256 over the rules, committed by the guest as the tournament id
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update((self.id.len() as u32).to_le_bytes());
+        hasher.update(self.id.as_bytes());
+        hasher.update(self.mode.id().to_le_bytes());
+        hasher.update(self.seed.to_le_bytes());
+        hasher.update(self.starts_at.to_le_bytes());
+        hasher.update(self.ends_at.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Whether a game played at `timestamp` falls inside the window
+    pub fn in_window(&self, timestamp: u64) -> bool {
+        self.starts_at <= timestamp && timestamp < self.ends_at
+    }
+}
+
+/// Public values committed by the tournament guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TournamentPublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    /// [`TournamentRules::commitment`] of the tournament entered
+    pub tournament_id: [u8; 32],
+    /// [`GameMode::id`] of the replayed game
+    pub mode: u32,
+    /// Seed the replayed game was played on
+    pub seed: u64,
+    pub timestamp: u64,
+    pub player_name_hash: [u8; 32],
+    pub score: u32,
+    /// [`replay_hash`](crate::replay::replay_hash) of the encoded replay
+    pub replay_hash: [u8; 32],
+    pub verified: u32,
+}
+
+impl From<TournamentPublicValues> for TournamentData {
+    fn from(value: TournamentPublicValues) -> Self {
+        Self {
+            abiVersion: TOURNAMENT_ABI_VERSION,
+            rulesVersion: value.rules_version,
+            tournamentId: FixedBytes(value.tournament_id),
+            mode: value.mode,
+            seed: value.seed,
+            timestamp: value.timestamp,
+            playerNameHash: FixedBytes(value.player_name_hash),
+            score: value.score,
+            replayHash: FixedBytes(value.replay_hash),
+            verified: value.verified,
+        }
+    }
+}
+
+impl From<TournamentData> for TournamentPublicValues {
+    fn from(value: TournamentData) -> Self {
+        Self {
+            abi_version: value.abiVersion,
+            rules_version: value.rulesVersion,
+            tournament_id: value.tournamentId.0,
+            mode: value.mode,
+            seed: value.seed,
+            timestamp: value.timestamp,
+            player_name_hash: value.playerNameHash.0,
+            score: value.score,
+            replay_hash: value.replayHash.0,
+            verified: value.verified,
+        }
+    }
+}
+
+/// Encode in the current tournament layout
+pub fn encode(values: TournamentPublicValues) -> Vec<u8> {
+    TournamentData::abi_encode(&TournamentData::from(values))
+}
+
+/// Decode public values committed by the tournament guest
+pub fn decode(bytes: &[u8]) -> Result<TournamentPublicValues, crate::abi::DecodeError> {
+    let values = TournamentPublicValues::from(TournamentData::abi_decode(bytes, true)?);
+    if values.abi_version != TOURNAMENT_ABI_VERSION {
+        return Err(crate::abi::DecodeError::UnsupportedVersion(values.abi_version));
+    }
+    Ok(values)
+}
@@ -0,0 +1,168 @@
+//! Achievements earned by replays, their bitmask and committed public values.
+
+use game_lib::achievements::{
+    self, decode, encode, Achievement, AchievementPublicValues, AchievementTracker,
+    ACHIEVEMENTS_ABI_VERSION, SURVIVOR_TICKS,
+};
+use game_lib::replay::Replay;
+use game_lib::sim::{Enemy, Input, Player, Simulation, ARENA_HEIGHT, ARENA_WIDTH};
+use game_lib::{GameMode, RULES_VERSION};
+
+/// What the bot does on a tick
+#[derive(Clone, Copy)]
+enum Plan {
+    /// Stand still and point the blade at the enemy that arrives first
+    Fight,
+    /// Circle the arena centre with the blade pointed away from the enemy
+    Kite,
+    /// Walk into the enemy with the blade pointed away from it
+    GetHit,
+}
+
+fn first_to_arrive(sim: &Simulation) -> Option<Enemy> {
+    let player = *sim.player();
+    let arrival = |enemy: &Enemy| (enemy.x - player.x).hypot(enemy.y - player.y) / enemy.speed;
+    sim.enemies().iter().copied().min_by(|a, b| arrival(a).total_cmp(&arrival(b)))
+}
+
+fn buttons_toward(player: &Player, x: f64, y: f64) -> u8 {
+    let mut buttons = 0;
+    if x > player.x + 3.0 {
+        buttons |= Input::RIGHT;
+    } else if x < player.x - 3.0 {
+        buttons |= Input::LEFT;
+    }
+    if y > player.y + 3.0 {
+        buttons |= Input::DOWN;
+    } else if y < player.y - 3.0 {
+        buttons |= Input::UP;
+    }
+    buttons
+}
+
+/// Replay of up to `ticks` ticks played by a bot following `plan`
+fn play(
+    mode: GameMode,
+    seed: u64,
+    ticks: u64,
+    mut plan: impl FnMut(&Simulation) -> Plan,
+) -> Replay {
+    let mut sim = Simulation::new(mode, seed);
+    let mut replay = Replay::new(mode, seed);
+    while sim.tick() < ticks && !sim.is_over() {
+        let player = *sim.player();
+        let input = match (plan(&sim), first_to_arrive(&sim)) {
+            (_, None) => Input::new(0, 400, 300),
+            (Plan::Fight, Some(enemy)) => Input::new(0, enemy.x as i16, enemy.y as i16),
+            (plan, Some(enemy)) => {
+                let (away_x, away_y) = (2.0 * player.x - enemy.x, 2.0 * player.y - enemy.y);
+                let buttons = if let Plan::Kite = plan {
+                    let (cx, cy) = (ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0);
+                    let angle = (player.y - cy).atan2(player.x - cx) + 0.4;
+                    buttons_toward(&player, cx + 200.0 * angle.cos(), cy + 200.0 * angle.sin())
+                } else {
+                    buttons_toward(&player, enemy.x, enemy.y)
+                };
+                Input::new(buttons, away_x as i16, away_y as i16)
+            }
+        };
+        replay.inputs.push(input);
+        sim.step(input);
+    }
+    replay
+}
+
+fn earned(replay: &Replay) -> Vec<Achievement> {
+    Achievement::in_mask(achievements::run(replay).1)
+}
+
+#[test]
+fn fighting_every_wave_is_flawless_and_a_frenzy() {
+    let replay = play(GameMode::TimeAttack, 0, u64::MAX, |_| Plan::Fight);
+    let (sim, mask) = achievements::run(&replay);
+    assert!(sim.wave() >= 10 && sim.lives() == 3);
+    // Time attack ends at 3 minutes, short of Survivor
+    assert!(sim.is_over() && sim.tick() < SURVIVOR_TICKS);
+    assert_eq!(Achievement::in_mask(mask), [Achievement::Flawless, Achievement::Frenzy]);
+}
+
+#[test]
+fn losing_a_life_denies_flawless() {
+    let replay = play(GameMode::TimeAttack, 0, u64::MAX, |sim| {
+        if sim.lives() == 3 {
+            Plan::GetHit
+        } else {
+            Plan::Fight
+        }
+    });
+    let (sim, mask) = achievements::run(&replay);
+    assert!(sim.wave() >= 10 && sim.lives() < 3);
+    assert_eq!(Achievement::in_mask(mask), [Achievement::Frenzy]);
+}
+
+#[test]
+fn surviving_five_minutes_without_fighting() {
+    // Thin the first wave to one enemy and lead it around the arena
+    let replay = play(GameMode::Endless, 0, SURVIVOR_TICKS, |sim| {
+        if sim.enemies().len() > 1 {
+            Plan::Fight
+        } else {
+            Plan::Kite
+        }
+    });
+    let (sim, mask) = achievements::run(&replay);
+    assert_eq!((sim.tick(), sim.wave(), sim.lives()), (SURVIVOR_TICKS, 1, 3));
+    assert_eq!(Achievement::in_mask(mask), [Achievement::Survivor]);
+
+    // One tick short earns nothing
+    let mut short = replay.clone();
+    short.inputs.pop();
+    assert!(earned(&short).is_empty());
+}
+
+#[test]
+fn idle_games_earn_nothing() {
+    for mode in [GameMode::Endless, GameMode::TimeAttack, GameMode::Hardcore] {
+        let mut replay = Replay::new(mode, 7);
+        replay.inputs = vec![Input::default(); SURVIVOR_TICKS as usize + 1];
+        let (sim, mask) = achievements::run(&replay);
+        assert!(sim.is_over(), "{:?}", mode);
+        assert_eq!(mask, 0, "{:?}", mode);
+    }
+    assert_eq!(AchievementTracker::new().earned(), 0);
+}
+
+#[test]
+fn mask_and_names() {
+    for achievement in Achievement::ALL {
+        assert_eq!(achievement.bit(), 1 << achievement.id());
+        assert_eq!(achievement.name().parse(), Ok(achievement));
+        assert_eq!(Achievement::in_mask(achievement.bit()), [achievement]);
+    }
+    assert_eq!(Achievement::mask(&Achievement::ALL), 0b111);
+    assert_eq!(Achievement::mask(&[]), 0);
+    // Bits of achievements this build does not know are ignored
+    assert_eq!(Achievement::in_mask(u32::MAX), Achievement::ALL);
+    assert_eq!(Achievement::in_mask(1 << 3), []);
+    assert!("Flawless".parse::<Achievement>().is_err());
+}
+
+#[test]
+fn public_values_round_trip() {
+    let values = AchievementPublicValues {
+        abi_version: ACHIEVEMENTS_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: GameMode::Endless.id(),
+        player_name_hash: game_lib::player_name_hash(b"alice"),
+        replay_hash: [5; 32],
+        achievements: Achievement::mask(&[Achievement::Flawless, Achievement::Survivor]),
+        verified: 1,
+    };
+    assert_eq!(decode(&encode(values)).unwrap(), values);
+    assert_eq!(values.earned(), [Achievement::Flawless, Achievement::Survivor]);
+
+    // `encode` always writes the current version, so patch the first word
+    let mut bytes = encode(values);
+    bytes[31] = (ACHIEVEMENTS_ABI_VERSION + 1) as u8;
+    assert!(decode(&bytes).is_err());
+}
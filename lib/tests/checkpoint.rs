@@ -0,0 +1,157 @@
+//! Checkpoint Merkle roots and inclusion proofs over every small tree shape.
+
+use game_lib::checkpoint::{merkle_root, Checkpoint, InclusionProof, EMPTY_ROOT};
+
+/// Largest number of checkpoints tried, covering several odd levels
+const MAX_LEAVES: usize = 33;
+
+fn game(count: usize) -> Vec<Checkpoint> {
+    (0..count as u32)
+        .map(|i| Checkpoint {
+            wave: i + 1,
+            tick: u64::from(i) * 600,
+            score: i * 150,
+            lives: 3,
+            state_hash: [i as u8; 32],
+        })
+        .collect()
+}
+
+/// Every `(checkpoints, root, index, proof)` for trees of 1 to `MAX_LEAVES` leaves
+fn proofs() -> impl Iterator<Item = (Vec<Checkpoint>, [u8; 32], usize, InclusionProof)> {
+    (1..=MAX_LEAVES).flat_map(|count| {
+        let checkpoints = game(count);
+        let root = merkle_root(&checkpoints);
+        (0..count).map(move |index| {
+            let proof = InclusionProof::new(&checkpoints, index).unwrap();
+            (checkpoints.clone(), root, index, proof)
+        })
+    })
+}
+
+#[test]
+fn every_checkpoint_verifies_for_every_leaf_count() {
+    for (checkpoints, root, index, proof) in proofs() {
+        assert_eq!((proof.index, proof.leaf_count), (index, checkpoints.len()));
+        assert!(
+            proof.verify(&checkpoints[index], &root),
+            "checkpoint {} of {}",
+            index,
+            checkpoints.len()
+        );
+    }
+}
+
+#[test]
+fn edge_trees() {
+    assert_eq!(merkle_root(&[]), EMPTY_ROOT);
+    assert_eq!(InclusionProof::new(&[], 0), None);
+
+    // A single checkpoint is its own root
+    let single = game(1);
+    assert_eq!(merkle_root(&single), single[0].leaf_hash());
+    assert!(InclusionProof::new(&single, 0).unwrap().siblings.is_empty());
+
+    for count in 1..=MAX_LEAVES {
+        assert_eq!(InclusionProof::new(&game(count), count), None);
+    }
+}
+
+#[test]
+fn tampered_checkpoint_fails() {
+    for (checkpoints, root, index, proof) in proofs() {
+        let honest = checkpoints[index];
+        let tampered = [
+            Checkpoint { wave: honest.wave + 1, ..honest },
+            Checkpoint { tick: honest.tick + 1, ..honest },
+            Checkpoint { score: honest.score + 1, ..honest },
+            Checkpoint { lives: honest.lives - 1, ..honest },
+            Checkpoint { state_hash: [0xff; 32], ..honest },
+        ];
+        for checkpoint in &tampered {
+            assert!(!proof.verify(checkpoint, &root), "{:?} of {}", checkpoint, checkpoints.len());
+        }
+        // Another checkpoint of the same game is not at this position
+        if checkpoints.len() > 1 {
+            let other = (index + 1) % checkpoints.len();
+            assert!(!proof.verify(&checkpoints[other], &root));
+        }
+    }
+}
+
+#[test]
+fn tampered_siblings_fail() {
+    for (checkpoints, root, index, proof) in proofs() {
+        let checkpoint = &checkpoints[index];
+        for level in 0..proof.siblings.len() {
+            let mut flipped = proof.clone();
+            flipped.siblings[level][0] ^= 1;
+            assert!(!flipped.verify(checkpoint, &root));
+
+            let mut dropped = proof.clone();
+            dropped.siblings.remove(level);
+            assert!(!dropped.verify(checkpoint, &root));
+        }
+        let mut extra = proof.clone();
+        extra.siblings.push(root);
+        assert!(!extra.verify(checkpoint, &root));
+
+        if proof.siblings.len() > 1 {
+            let mut reversed = proof.clone();
+            reversed.siblings.reverse();
+            assert!(!reversed.verify(checkpoint, &root));
+        }
+    }
+}
+
+#[test]
+fn tampered_index_fails() {
+    for (checkpoints, root, index, proof) in proofs() {
+        for other in (0..checkpoints.len()).filter(|other| *other != index) {
+            let moved = InclusionProof { index: other, ..proof.clone() };
+            assert!(!moved.verify(&checkpoints[index], &root));
+        }
+        let outside = InclusionProof { index: checkpoints.len(), ..proof.clone() };
+        assert!(!outside.verify(&checkpoints[index], &root));
+    }
+}
+
+#[test]
+fn tampered_leaf_count_fails_when_it_changes_the_path() {
+    // Number of siblings on the path to each leaf, by leaf count
+    let path_lengths: Vec<Vec<usize>> = (0..=2 * MAX_LEAVES)
+        .map(|count| {
+            let checkpoints = game(count);
+            (0..count)
+                .map(|index| InclusionProof::new(&checkpoints, index).unwrap().siblings.len())
+                .collect()
+        })
+        .collect();
+    for (checkpoints, root, index, proof) in proofs() {
+        let checkpoint = &checkpoints[index];
+        for leaf_count in [0, index, usize::MAX] {
+            assert!(!InclusionProof { leaf_count, ..proof.clone() }.verify(checkpoint, &root));
+        }
+        for leaf_count in (index + 1..=2 * MAX_LEAVES).filter(|count| *count != checkpoints.len()) {
+            if path_lengths[leaf_count][index] != proof.siblings.len() {
+                let tampered = InclusionProof { leaf_count, ..proof.clone() };
+                assert!(
+                    !tampered.verify(checkpoint, &root),
+                    "{} as {}",
+                    proof.leaf_count,
+                    leaf_count
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn leaf_count_is_not_bound_by_the_root() {
+    // The root only fixes the sibling path, so a count giving the same path
+    // still verifies; callers check it against the committed checkpoint count
+    let checkpoints = game(5);
+    let root = merkle_root(&checkpoints);
+    let proof = InclusionProof::new(&checkpoints, 0).unwrap();
+    assert!(InclusionProof { leaf_count: 8, ..proof }.verify(&checkpoints[0], &root));
+}
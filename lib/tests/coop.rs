@@ -0,0 +1,229 @@
+//! Co-op replays, the shared simulation and committed public values.
+
+use game_lib::coop::{
+    self, run_chunks, CoopEvent, CoopPublicValues, CoopReplay, CoopSimulation, COOP_ABI_VERSION,
+    COOP_REPLAY_MAGIC, COOP_REPLAY_VERSION, PLAYERS,
+};
+use game_lib::replay::{Replay, ReplayError, ReplayHeader, REPLAY_MAGIC};
+use game_lib::sim::{Input, Rng};
+use game_lib::{GameMode, RULES_VERSION};
+
+const HEADER_LEN: usize = 21;
+/// Both players' inputs for one tick
+const TICK_LEN: usize = 2 * 5;
+
+/// Replay of `ticks` random inputs per player, each changing direction every
+/// half second and aiming inside the arena
+fn replay(mode: GameMode, seed: u64, ticks: usize) -> CoopReplay {
+    let mut rng = Rng::new(seed);
+    let mut replay = CoopReplay::new(mode, seed);
+    let mut buttons = [0; PLAYERS];
+    for tick in 0..ticks {
+        if tick % 30 == 0 {
+            buttons = [rng.below(16) as u8, rng.below(16) as u8];
+        }
+        let aim = |rng: &mut Rng| rng.below(600) as i16 + 100;
+        replay.inputs.push([
+            Input::new(buttons[0], aim(&mut rng), aim(&mut rng)),
+            Input::new(buttons[1], aim(&mut rng), aim(&mut rng)),
+        ]);
+    }
+    replay
+}
+
+fn modes() -> [GameMode; 3] {
+    [GameMode::Endless, GameMode::TimeAttack, GameMode::Hardcore]
+}
+
+#[test]
+fn round_trip() {
+    for mode in modes() {
+        for ticks in [0, 1, 2, 1000] {
+            let replay = replay(mode, 7 + ticks as u64, ticks);
+            let encoded = replay.encode();
+            assert_eq!(encoded.len(), HEADER_LEN + TICK_LEN * ticks);
+            assert_eq!(encoded[..4], COOP_REPLAY_MAGIC);
+            assert_eq!(encoded[4], COOP_REPLAY_VERSION);
+            assert_eq!(encoded[17..21], (ticks as u32).to_le_bytes());
+            assert_eq!(CoopReplay::decode(&encoded), Ok(replay));
+        }
+    }
+    // The first player's input is written first each tick
+    let mut replay = CoopReplay::new(GameMode::Hardcore, u64::MAX);
+    replay.inputs = vec![[Input::new(0xff, i16::MIN, i16::MAX), Input::new(0, -1, 0)]];
+    let encoded = replay.encode();
+    assert_eq!(encoded[HEADER_LEN], 0xff);
+    assert_eq!(encoded[HEADER_LEN + 5], 0);
+    assert_eq!(CoopReplay::decode(&encoded), Ok(replay));
+}
+
+#[test]
+fn truncated_input_is_rejected() {
+    let encoded = replay(GameMode::Endless, 1, 10).encode();
+    for len in 0..encoded.len() {
+        let expected = if len < HEADER_LEN { HEADER_LEN } else { encoded.len() };
+        assert_eq!(
+            CoopReplay::decode(&encoded[..len]),
+            Err(ReplayError::Truncated { expected, actual: len })
+        );
+    }
+
+    let mut trailing = encoded.clone();
+    trailing.extend_from_slice(&[0; 5]);
+    assert_eq!(CoopReplay::decode(&trailing), Err(ReplayError::TrailingBytes(5)));
+}
+
+#[test]
+fn every_tick_needs_both_players_inputs() {
+    // Ten single inputs are five ticks for two players, not ten
+    let mut encoded = replay(GameMode::Endless, 1, 5).encode();
+    encoded[17..21].copy_from_slice(&10u32.to_le_bytes());
+    assert_eq!(
+        CoopReplay::decode(&encoded),
+        Err(ReplayError::Truncated { expected: HEADER_LEN + 10 * TICK_LEN, actual: encoded.len() })
+    );
+
+    // The last tick carries only the first player's input
+    let mut short = replay(GameMode::Endless, 1, 3).encode();
+    short.truncate(short.len() - 5);
+    assert_eq!(
+        CoopReplay::decode(&short),
+        Err(ReplayError::Truncated { expected: short.len() + 5, actual: short.len() })
+    );
+}
+
+#[test]
+fn malformed_header_is_rejected() {
+    let encoded = replay(GameMode::Endless, 1, 3).encode();
+
+    let mut version = encoded.clone();
+    version[4] = COOP_REPLAY_VERSION + 1;
+    assert_eq!(
+        CoopReplay::decode(&version),
+        Err(ReplayError::UnsupportedVersion(COOP_REPLAY_VERSION + 1))
+    );
+
+    let mut mode = encoded.clone();
+    mode[5..9].copy_from_slice(&99u32.to_le_bytes());
+    assert_eq!(CoopReplay::decode(&mode), Err(ReplayError::UnknownMode(99)));
+
+    // Single-player and co-op replays are never mistaken for each other
+    let mut single = Replay::new(GameMode::Endless, 1);
+    single.inputs = vec![Input::default(); 6];
+    assert_eq!(CoopReplay::decode(&single.encode()), Err(ReplayError::BadMagic));
+    assert_eq!(Replay::decode(&encoded), Err(ReplayError::BadMagic));
+    let mut relabelled = encoded.clone();
+    relabelled[..4].copy_from_slice(&REPLAY_MAGIC);
+    assert_eq!(CoopReplay::decode(&relabelled), Err(ReplayError::BadMagic));
+}
+
+#[test]
+fn same_replay_same_game() {
+    for mode in modes() {
+        let replay = replay(mode, 42, 3000);
+        let first = replay.run();
+        assert_eq!(replay.run(), first);
+        assert_eq!(CoopReplay::decode(&replay.encode()).unwrap().run(), first);
+
+        let other = CoopReplay { seed: 43, ..replay.clone() }.run();
+        assert_ne!(other.state_hash(), first.state_hash(), "{:?}", mode);
+    }
+}
+
+#[test]
+fn chunked_run_matches_one_shot_run() {
+    let replay = replay(GameMode::TimeAttack, 9, 600);
+    let encoded = replay.encode();
+    let expected = replay.run();
+    for size in [1, 5, 10, HEADER_LEN, 97, encoded.len(), encoded.len() + 1] {
+        let (hash, run) = run_chunks(encoded.chunks(size));
+        assert_eq!(hash, game_lib::replay::replay_hash(&encoded), "{}-byte chunks", size);
+        let (header, sim) = run.unwrap();
+        assert_eq!(header, ReplayHeader { mode: GameMode::TimeAttack, seed: 9, ticks: 600 });
+        assert_eq!(sim, expected);
+    }
+
+    let (_, run) = run_chunks(encoded[..encoded.len() - 1].chunks(64));
+    assert!(matches!(run, Err(ReplayError::Truncated { .. })));
+}
+
+#[test]
+fn kills_are_credited_to_the_striking_player() {
+    let replay = replay(GameMode::Endless, 5, 6000);
+    let points = GameMode::Endless.rules().points_per_kill;
+    let mut sim = CoopSimulation::new(GameMode::Endless, 5);
+    let mut kills = [0; PLAYERS];
+    let mut lives = sim.lives();
+    for inputs in &replay.inputs {
+        for event in sim.step(*inputs) {
+            match event {
+                CoopEvent::EnemyKilled { player, score, .. } => {
+                    kills[player] += 1;
+                    assert_eq!(score, kills[player] * points);
+                }
+                CoopEvent::PlayerHit { player, lives: left, .. } => {
+                    assert!(player < PLAYERS);
+                    assert!(left == lives || left + 1 == lives);
+                    lives = left;
+                }
+                _ => {}
+            }
+        }
+    }
+    assert!(kills.iter().all(|&kills| kills > 0), "{:?}", kills);
+    assert_eq!(sim.kills(), kills);
+    assert_eq!(sim.scores(), kills.map(|kills| kills * points));
+    assert_eq!(sim.team_score(), (kills[0] + kills[1]) * points);
+    assert_eq!(sim.lives(), lives);
+}
+
+#[test]
+fn swapping_the_input_streams_changes_the_game() {
+    // The players start on different sides, so their streams are not interchangeable
+    let replay = replay(GameMode::Endless, 8, 3000);
+    let swapped = CoopReplay {
+        inputs: replay.inputs.iter().map(|[first, second]| [*second, *first]).collect(),
+        ..replay.clone()
+    };
+    assert_ne!(swapped.run().state_hash(), replay.run().state_hash());
+}
+
+#[test]
+fn ticks_after_game_over_are_ignored() {
+    let mut replay = replay(GameMode::TimeAttack, 2, 0);
+    let limit = GameMode::TimeAttack.rules().time_limit_secs.unwrap() as usize;
+    replay.inputs = vec![[Input::default(); PLAYERS]; limit * 60 + 100];
+    let sim = replay.run();
+    // Idle players may lose every life before the time runs out
+    assert!(sim.is_over());
+    assert!(sim.tick() <= limit as u64 * 60);
+
+    let mut over = sim.clone();
+    assert!(over.step([Input::new(Input::UP, 0, 0); PLAYERS]).is_empty());
+    assert_eq!(over, sim);
+}
+
+#[test]
+fn public_values_round_trip() {
+    let values = CoopPublicValues {
+        abi_version: COOP_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: GameMode::Hardcore.id(),
+        timestamp: 1_700_000_000,
+        replay_hash: [7; 32],
+        player_name_hashes: [
+            game_lib::player_name_hash(b"alice"),
+            game_lib::player_name_hash(b"bob"),
+        ],
+        scores: [u32::MAX, 20],
+        wave: 4,
+        verified: 1,
+    };
+    assert_eq!(coop::decode(&coop::encode(values)).unwrap(), values);
+    assert_eq!(values.team_score(), u32::MAX);
+
+    // `encode` always writes the current version, so patch the first word
+    let mut bytes = coop::encode(values);
+    bytes[31] = (COOP_ABI_VERSION + 1) as u8;
+    assert!(coop::decode(&bytes).is_err());
+}
@@ -0,0 +1,117 @@
+//! UTC days, dates and the seeds derived from them.
+
+use game_lib::daily::{
+    daily_seed, date, day_of, day_start, decode, encode, parse_date, DailyPublicValues,
+    DAILY_ABI_VERSION, DAILY_DOMAIN, SECS_PER_DAY,
+};
+use game_lib::{GameMode, RULES_VERSION};
+use sha2::{Digest, Sha256};
+
+/// 2024-01-01
+const NEW_YEAR: u32 = 19_723;
+
+#[test]
+fn days_start_at_utc_midnight() {
+    assert_eq!(day_of(0), 0);
+    assert_eq!(day_of(SECS_PER_DAY - 1), 0);
+    assert_eq!(day_of(SECS_PER_DAY), 1);
+
+    let start = day_start(NEW_YEAR);
+    assert_eq!(start, 1_704_067_200);
+    assert_eq!(day_of(start - 1), NEW_YEAR - 1);
+    assert_eq!(day_of(start), NEW_YEAR);
+    assert_eq!(day_of(start + SECS_PER_DAY - 1), NEW_YEAR);
+    assert_eq!(day_of(start + SECS_PER_DAY), NEW_YEAR + 1);
+}
+
+#[test]
+fn dates_of_known_days() {
+    let known = [
+        (0, "1970-01-01"),
+        (31, "1970-02-01"),
+        (11_016, "2000-02-29"),
+        (NEW_YEAR - 1, "2023-12-31"),
+        (NEW_YEAR, "2024-01-01"),
+        (19_782, "2024-02-29"),
+        (19_783, "2024-03-01"),
+        (2_932_896, "9999-12-31"),
+    ];
+    for (day, expected) in known {
+        assert_eq!(date(day), expected);
+        assert_eq!(parse_date(expected), Some(day), "{}", expected);
+    }
+}
+
+#[test]
+fn every_date_round_trips() {
+    for day in (0..2_932_897).step_by(7).chain(NEW_YEAR - 400..NEW_YEAR + 400) {
+        assert_eq!(parse_date(&date(day)), Some(day), "day {}", day);
+    }
+}
+
+#[test]
+fn invalid_dates_are_rejected() {
+    let invalid = [
+        "",
+        "2024",
+        "2024-01",
+        "2024-1-01",
+        "2024-01-1",
+        "24-01-01",
+        "2024/01/01",
+        "2024-01-01T00:00",
+        "2024-01-01 ",
+        "2024-00-10",
+        "2024-13-01",
+        "2024-01-00",
+        "2024-01-32",
+        "2024-04-31",
+        "2023-02-29",
+        "2100-02-29",
+        "1969-12-31",
+        "0000-01-01",
+        "+202-01-01",
+        "2024-01-+1",
+        "abcd-ef-gh",
+        "10000-01-01",
+    ];
+    for date in invalid {
+        assert_eq!(parse_date(date), None, "{:?}", date);
+    }
+}
+
+#[test]
+fn seed_is_derived_from_the_date() {
+    let digest = Sha256::digest(format!("{}:2024-01-01", DAILY_DOMAIN));
+    let expected = u64::from_le_bytes(digest[..8].try_into().unwrap());
+    assert_eq!(daily_seed(NEW_YEAR), expected);
+
+    // Every day around a year and a leap day gets its own seed
+    let seeds: Vec<u64> = (NEW_YEAR - 5..NEW_YEAR + 70).map(daily_seed).collect();
+    let mut unique = seeds.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(unique.len(), seeds.len());
+}
+
+#[test]
+fn public_values_round_trip() {
+    let values = DailyPublicValues {
+        abi_version: DAILY_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        day: NEW_YEAR,
+        seed: daily_seed(NEW_YEAR),
+        mode: GameMode::Hardcore.id(),
+        timestamp: day_start(NEW_YEAR) + 60,
+        player_name_hash: game_lib::player_name_hash(b"alice"),
+        score: 450,
+        replay_hash: [3; 32],
+        verified: 1,
+    };
+    assert_eq!(decode(&encode(values)).unwrap(), values);
+
+    // `encode` always writes the current version, so patch the first word
+    let mut bytes = encode(values);
+    bytes[31] = (DAILY_ABI_VERSION + 1) as u8;
+    assert!(decode(&bytes).is_err());
+}
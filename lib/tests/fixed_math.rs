@@ -0,0 +1,116 @@
+//! Error bounds of `game_lib::fixed` against `f64`, over random inputs.
+
+use std::f64::consts::PI;
+
+use game_lib::fixed::Fixed;
+use game_lib::sim::Rng;
+
+const SAMPLES: usize = 100_000;
+
+/// Largest error allowed for `atan2`, `sin` and `cos`, in radians or units
+const TRIG_TOLERANCE: f64 = 1e-8;
+
+/// One step of 32.32 fixed point
+const STEP: f64 = 1.0 / (1u64 << 32) as f64;
+
+/// Uniform in `[-range, range)`
+fn uniform(rng: &mut Rng, range: f64) -> f64 {
+    (rng.next_f64() * 2.0 - 1.0) * range
+}
+
+#[test]
+fn f64_round_trip_is_exact_on_the_grid() {
+    let mut rng = Rng::new(1);
+    for _ in 0..SAMPLES {
+        let value = Fixed::from_f64(uniform(&mut rng, 1e6));
+        assert_eq!(Fixed::from_f64(value.to_f64()), value);
+    }
+}
+
+#[test]
+fn sqrt_rounds_down_within_one_step() {
+    let mut rng = Rng::new(2);
+    for _ in 0..SAMPLES {
+        let value = Fixed::from_f64(rng.next_f64() * 1e6);
+        let root = value.sqrt().to_f64();
+        let exact = value.to_f64().sqrt();
+        assert!(root <= exact && exact - root <= STEP, "sqrt({}) = {}", value.to_f64(), root);
+    }
+    assert_eq!(Fixed::from_int(-4).sqrt(), Fixed::ZERO);
+    assert_eq!(Fixed::from_int(9).sqrt(), Fixed::from_int(3));
+}
+
+#[test]
+fn hypot_rounds_down_within_one_step() {
+    let mut rng = Rng::new(3);
+    for _ in 0..SAMPLES {
+        let (x, y) = (uniform(&mut rng, 2000.0), uniform(&mut rng, 2000.0));
+        let (x, y) = (Fixed::from_f64(x), Fixed::from_f64(y));
+        let length = x.hypot(y).to_f64();
+        let exact = x.to_f64().hypot(y.to_f64());
+        assert!(exact - length >= -1e-9 && exact - length <= STEP + 1e-9);
+    }
+    assert_eq!(Fixed::from_int(3).hypot(Fixed::from_int(-4)), Fixed::from_int(5));
+}
+
+#[test]
+fn atan2_is_within_tolerance() {
+    let mut rng = Rng::new(4);
+    for i in 0..SAMPLES {
+        // Mix arena-sized vectors with tiny and huge ones
+        let range = [1e3, 1e-6, 1e8][i % 3];
+        let (x, y) =
+            (Fixed::from_f64(uniform(&mut rng, range)), Fixed::from_f64(uniform(&mut rng, range)));
+        if x == Fixed::ZERO && y == Fixed::ZERO {
+            continue;
+        }
+        let angle = y.atan2(x).to_f64();
+        let exact = y.to_f64().atan2(x.to_f64());
+        // -PI and PI are the same direction
+        let error = (angle - exact).abs().min(2.0 * PI - (angle - exact).abs());
+        assert!(
+            error <= TRIG_TOLERANCE,
+            "atan2({}, {}) = {}, not {}",
+            y.to_f64(),
+            x.to_f64(),
+            angle,
+            exact
+        );
+        assert!(angle > -PI - STEP && angle <= PI + STEP);
+    }
+}
+
+#[test]
+fn atan2_on_the_axes() {
+    let (one, zero) = (Fixed::ONE, Fixed::ZERO);
+    assert_eq!(zero.atan2(zero), zero);
+    assert!(zero.atan2(one).to_f64().abs() <= TRIG_TOLERANCE);
+    assert!((one.atan2(zero).to_f64() - PI / 2.0).abs() <= TRIG_TOLERANCE);
+    assert!(((-one).atan2(zero).to_f64() + PI / 2.0).abs() <= TRIG_TOLERANCE);
+    assert_eq!(zero.atan2(-one), Fixed::PI);
+}
+
+#[test]
+fn sin_cos_are_within_tolerance() {
+    let mut rng = Rng::new(5);
+    for _ in 0..SAMPLES {
+        let angle = Fixed::from_f64(uniform(&mut rng, 100.0));
+        let (sin, cos) = angle.sin_cos();
+        let exact = angle.to_f64();
+        assert!((sin.to_f64() - exact.sin()).abs() <= TRIG_TOLERANCE, "sin({})", exact);
+        assert!((cos.to_f64() - exact.cos()).abs() <= TRIG_TOLERANCE, "cos({})", exact);
+    }
+}
+
+#[test]
+fn sin_cos_invert_atan2() {
+    let mut rng = Rng::new(6);
+    for _ in 0..SAMPLES {
+        let (x, y) = (uniform(&mut rng, 1e3), uniform(&mut rng, 1e3));
+        let angle = Fixed::from_f64(y).atan2(Fixed::from_f64(x));
+        let (sin, cos) = angle.sin_cos();
+        let length = x.hypot(y);
+        assert!((cos.to_f64() * length - x).abs() <= 1e3 * TRIG_TOLERANCE * 2.0);
+        assert!((sin.to_f64() * length - y).abs() <= 1e3 * TRIG_TOLERANCE * 2.0);
+    }
+}
@@ -0,0 +1,149 @@
+//! Board checks, roots and private rank proofs.
+
+use game_lib::checkpoint::InclusionProof;
+use game_lib::rank::{
+    board_root, check_board, decode, encode, rank_proof, verify_rank, BoardError, RankLeaf,
+    RankPublicValues, RANK_ABI_VERSION,
+};
+use game_lib::GameMode;
+
+/// Board of `size` rows, 100 points apart, with ties at ranks 2 and 3
+fn board(size: u32) -> Vec<RankLeaf> {
+    (1..=size)
+        .map(|rank| RankLeaf {
+            rank,
+            mode: GameMode::Endless.id(),
+            player_name_hash: game_lib::player_name_hash(format!("player{}", rank).as_bytes()),
+            score: if rank == 3 { 10_000 - 100 } else { 10_000 - 100 * (rank - 1) },
+            timestamp: 1_700_000_000 + u64::from(rank),
+            game_hash: [rank as u8; 32],
+        })
+        .collect()
+}
+
+#[test]
+fn check_board_accepts_ranked_rows_and_ties() {
+    for size in 0..10 {
+        assert_eq!(check_board(&board(size)), Ok(()), "{} rows", size);
+    }
+}
+
+#[test]
+fn check_board_rejects_misranked_unordered_and_mixed_rows() {
+    let mut skipped = board(5);
+    skipped[2].rank = 4;
+    assert_eq!(check_board(&skipped), Err(BoardError::RankOutOfOrder { index: 2, rank: 4 }));
+
+    let mut zero = board(5);
+    zero[0].rank = 0;
+    assert_eq!(check_board(&zero), Err(BoardError::RankOutOfOrder { index: 0, rank: 0 }));
+
+    let mut unordered = board(5);
+    unordered[3].score = unordered[2].score + 10;
+    assert_eq!(check_board(&unordered), Err(BoardError::ScoreOutOfOrder { index: 3 }));
+
+    let mut mixed = board(5);
+    mixed[4].mode = GameMode::Hardcore.id();
+    assert_eq!(check_board(&mixed), Err(BoardError::MixedModes { index: 4 }));
+}
+
+#[test]
+fn every_row_verifies_at_its_rank() {
+    for size in 1..=17 {
+        let leaves = board(size);
+        let root = board_root(&leaves);
+        for leaf in &leaves {
+            let proof = rank_proof(&leaves, leaf.rank).unwrap();
+            assert_eq!((proof.index, proof.leaf_count), (leaf.rank as usize - 1, size as usize));
+            assert!(verify_rank(leaf, &proof, &root), "rank {} of {}", leaf.rank, size);
+        }
+        assert_eq!(rank_proof(&leaves, 0), None);
+        assert_eq!(rank_proof(&leaves, size + 1), None);
+    }
+}
+
+#[test]
+fn root_commits_to_rows_and_their_count() {
+    let leaves = board(6);
+    assert_ne!(board_root(&leaves), board_root(&leaves[..5]));
+    assert_ne!(board_root(&[]), board_root(&leaves[..1]));
+
+    let mut changed = leaves.clone();
+    changed[5].score -= 10;
+    assert_ne!(board_root(&changed), board_root(&leaves));
+}
+
+#[test]
+fn row_at_the_wrong_index_is_rejected() {
+    let leaves = board(8);
+    let root = board_root(&leaves);
+
+    // Rank 3's path, claimed for rank 2's row and with its own index moved
+    let proof = rank_proof(&leaves, 3).unwrap();
+    assert!(!verify_rank(&leaves[1], &proof, &root));
+    let moved = InclusionProof { index: 1, ..proof.clone() };
+    assert!(!verify_rank(&leaves[2], &moved, &root));
+
+    // A row relabelled with a better rank than it holds
+    let promoted = RankLeaf { rank: 2, ..leaves[2] };
+    assert!(!verify_rank(&promoted, &proof, &root));
+    assert!(!verify_rank(&promoted, &rank_proof(&leaves, 2).unwrap(), &root));
+}
+
+#[test]
+fn rank_zero_is_rejected() {
+    let leaves = board(4);
+    let root = board_root(&leaves);
+    let proof = rank_proof(&leaves, 1).unwrap();
+    let zero = RankLeaf { rank: 0, ..leaves[0] };
+    assert!(!verify_rank(&zero, &proof, &root));
+}
+
+#[test]
+fn row_from_another_player_or_board_is_rejected() {
+    let leaves = board(8);
+    let root = board_root(&leaves);
+    let proof = rank_proof(&leaves, 4).unwrap();
+
+    let stolen = RankLeaf { player_name_hash: game_lib::player_name_hash(b"mallory"), ..leaves[3] };
+    assert!(!verify_rank(&stolen, &proof, &root));
+
+    // The row and path of a different board do not verify against this root
+    let mut other = board(8);
+    other[3].score -= 10;
+    let other_proof = rank_proof(&other, 4).unwrap();
+    assert!(verify_rank(&other[3], &other_proof, &board_root(&other)));
+    assert!(!verify_rank(&other[3], &other_proof, &root));
+}
+
+#[test]
+fn claimed_board_size_is_bound_by_the_root() {
+    // Five rows claimed as eight still have a path of the same length, but
+    // the root commits to the count
+    let leaves = board(5);
+    let root = board_root(&leaves);
+    for leaf_count in [4, 6, 7, 8] {
+        let mut proof = rank_proof(&leaves, 2).unwrap();
+        proof.leaf_count = leaf_count;
+        assert!(!verify_rank(&leaves[1], &proof, &root), "claimed {} rows", leaf_count);
+    }
+}
+
+#[test]
+fn public_values_round_trip() {
+    let values = RankPublicValues {
+        abi_version: RANK_ABI_VERSION,
+        mode: GameMode::TimeAttack.id(),
+        board_root: board_root(&board(3)),
+        leaf_count: 3,
+        k: 10,
+        player_name_hash: game_lib::player_name_hash(b"alice"),
+        member: 1,
+    };
+    assert_eq!(decode(&encode(values)).unwrap(), values);
+
+    // `encode` always writes the current version, so patch the first word
+    let mut bytes = encode(values);
+    bytes[31] = (RANK_ABI_VERSION + 1) as u8;
+    assert!(decode(&bytes).is_err());
+}
@@ -0,0 +1,165 @@
+//! Encoding, decoding and chunked streaming of binary replays.
+
+use game_lib::replay::{
+    replay_hash, run_chunks, Replay, ReplayError, ReplayHeader, ReplayStream, REPLAY_CHUNK_LEN,
+    REPLAY_MAGIC, REPLAY_VERSION,
+};
+use game_lib::sim::{Input, Rng};
+use game_lib::GameMode;
+
+const HEADER_LEN: usize = 21;
+
+/// Replay of `ticks` random inputs, changing direction every half second
+fn replay(mode: GameMode, seed: u64, ticks: usize) -> Replay {
+    let mut rng = Rng::new(seed);
+    let mut replay = Replay::new(mode, seed);
+    let mut buttons = 0;
+    for tick in 0..ticks {
+        if tick % 30 == 0 {
+            buttons = rng.below(16) as u8;
+        }
+        let aim = |rng: &mut Rng| rng.below(1000) as i16 - 100;
+        replay.inputs.push(Input::new(buttons, aim(&mut rng), aim(&mut rng)));
+    }
+    replay
+}
+
+fn modes() -> [GameMode; 3] {
+    [GameMode::Endless, GameMode::TimeAttack, GameMode::Hardcore]
+}
+
+#[test]
+fn round_trip() {
+    for mode in modes() {
+        for ticks in [0, 1, 2, 1000] {
+            let replay = replay(mode, 7 + ticks as u64, ticks);
+            let encoded = replay.encode();
+            assert_eq!(encoded.len(), HEADER_LEN + 5 * ticks);
+            assert_eq!(encoded[..4], REPLAY_MAGIC);
+            assert_eq!(encoded[4], REPLAY_VERSION);
+            assert_eq!(Replay::decode(&encoded), Ok(replay));
+        }
+    }
+    // Extreme aims and seeds survive too
+    let mut replay = Replay::new(GameMode::Hardcore, u64::MAX);
+    replay.inputs = vec![Input::new(0xff, i16::MIN, i16::MAX), Input::new(0, -1, 0)];
+    assert_eq!(Replay::decode(&replay.encode()), Ok(replay));
+}
+
+#[test]
+fn truncated_input_is_rejected() {
+    let encoded = replay(GameMode::Endless, 1, 10).encode();
+    for len in 0..encoded.len() {
+        let expected = if len < HEADER_LEN { HEADER_LEN } else { encoded.len() };
+        assert_eq!(
+            Replay::decode(&encoded[..len]),
+            Err(ReplayError::Truncated { expected, actual: len })
+        );
+    }
+
+    let mut trailing = encoded.clone();
+    trailing.extend_from_slice(&[0, 0]);
+    assert_eq!(Replay::decode(&trailing), Err(ReplayError::TrailingBytes(2)));
+}
+
+#[test]
+fn huge_input_count_is_truncated_not_allocated() {
+    let mut encoded = Replay::new(GameMode::Endless, 1).encode();
+    encoded[17..21].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        Replay::decode(&encoded),
+        Err(ReplayError::Truncated { expected: HEADER_LEN + 5 * u32::MAX as usize, actual: 21 })
+    );
+}
+
+#[test]
+fn bad_header_is_rejected() {
+    let encoded = replay(GameMode::Endless, 1, 3).encode();
+
+    let mut magic = encoded.clone();
+    magic[..4].copy_from_slice(b"BWCO");
+    assert_eq!(Replay::decode(&magic), Err(ReplayError::BadMagic));
+
+    let mut version = encoded.clone();
+    version[4] = REPLAY_VERSION + 1;
+    assert_eq!(Replay::decode(&version), Err(ReplayError::UnsupportedVersion(REPLAY_VERSION + 1)));
+
+    let mut mode = encoded.clone();
+    mode[5..9].copy_from_slice(&99u32.to_le_bytes());
+    assert_eq!(Replay::decode(&mode), Err(ReplayError::UnknownMode(99)));
+}
+
+/// Chunk sizes covering every split of the header and of a single input,
+/// and chunks larger than the whole replay
+fn chunk_sizes(len: usize) -> impl Iterator<Item = usize> {
+    (1..=2 * HEADER_LEN).chain([97, 1000, REPLAY_CHUNK_LEN, len.max(1), len + 1])
+}
+
+#[test]
+fn chunked_hash_matches_one_shot_hash() {
+    for mode in modes() {
+        let replay = replay(mode, 42, 600);
+        let encoded = replay.encode();
+        let expected = replay.run();
+        for size in chunk_sizes(encoded.len()) {
+            let mut ticks = 0;
+            let (hash, run) = run_chunks(encoded.chunks(size), |_, _| ticks += 1);
+            assert_eq!(hash, replay_hash(&encoded), "{:?} in {}-byte chunks", mode, size);
+
+            let (header, sim) = run.unwrap();
+            assert_eq!(header, ReplayHeader { mode, seed: 42, ticks: 600 });
+            assert_eq!(ticks, expected.tick());
+            assert_eq!(sim.state_hash(), expected.state_hash());
+            assert_eq!(sim.score(), expected.score());
+        }
+    }
+}
+
+#[test]
+fn chunked_errors_match_one_shot_errors() {
+    let encoded = replay(GameMode::Endless, 3, 50).encode();
+    let mut bad_magic = encoded.clone();
+    bad_magic[0] ^= 1;
+    let mut trailing = encoded.clone();
+    trailing.push(0);
+    let inputs =
+        [&encoded[..0], &encoded[..HEADER_LEN - 1], &encoded[..100], &bad_magic, &trailing];
+
+    for input in inputs {
+        let expected = Replay::decode(input).unwrap_err();
+        for size in chunk_sizes(input.len()) {
+            let mut stream = ReplayStream::new();
+            for chunk in input.chunks(size) {
+                stream.push(chunk);
+                while stream.next_input().is_some() {}
+            }
+            let (hash, header) = stream.finish();
+            assert_eq!(hash, replay_hash(input));
+            assert_eq!(header, Err(expected.clone()), "{}-byte chunks", size);
+        }
+    }
+}
+
+#[test]
+fn stream_gives_inputs_as_their_bytes_arrive() {
+    let replay = replay(GameMode::Endless, 5, 3);
+    let encoded = replay.encode();
+    let mut stream = ReplayStream::new();
+
+    stream.push(&encoded[..HEADER_LEN - 1]);
+    assert_eq!(stream.header(), None);
+    assert_eq!(stream.next_input(), None);
+
+    stream.push(&encoded[HEADER_LEN - 1..HEADER_LEN + 7]);
+    let header = ReplayHeader { mode: GameMode::Endless, seed: 5, ticks: 3 };
+    assert_eq!(stream.header(), Some(header));
+    assert_eq!(stream.next_input(), Some(replay.inputs[0]));
+    // Only two of the second input's five bytes are here
+    assert_eq!(stream.next_input(), None);
+
+    stream.push(&encoded[HEADER_LEN + 7..]);
+    assert_eq!(stream.next_input(), Some(replay.inputs[1]));
+    assert_eq!(stream.next_input(), Some(replay.inputs[2]));
+    assert_eq!(stream.next_input(), None);
+    assert_eq!(stream.finish(), (replay_hash(&encoded), Ok(header)));
+}
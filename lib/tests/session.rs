@@ -0,0 +1,86 @@
+//! Boundaries of the session checks the score guests run.
+
+use game_lib::session::Session;
+use game_lib::sim::TICK_RATE;
+use game_lib::GameMode;
+
+/// Session of `ticks` ticks that took `duration_secs` of wall-clock time
+fn session(duration_secs: u64, ticks: u64) -> Session {
+    Session { started_at: 1_000_000, ended_at: 1_000_000 + duration_secs, ticks }
+}
+
+fn secs(secs: u64) -> u64 {
+    secs * u64::from(TICK_RATE)
+}
+
+#[test]
+fn ordered_needs_start_before_end_before_submission() {
+    let game = session(60, secs(60));
+    assert!(game.ordered(game.ended_at));
+    assert!(!game.ordered(game.ended_at - 1));
+    let backwards = Session { started_at: 10, ended_at: 9, ticks: 0 };
+    assert!(!backwards.ordered(100));
+    assert_eq!(backwards.duration_secs(), 0);
+}
+
+#[test]
+fn duration_tolerance_is_two_seconds_plus_ten_percent() {
+    // 100 seconds of ticks allow 2 + 10 seconds either way
+    assert!(session(100, secs(100)).duration_matches_ticks());
+    assert!(session(112, secs(100)).duration_matches_ticks());
+    assert!(!session(113, secs(100)).duration_matches_ticks());
+    assert!(session(88, secs(100)).duration_matches_ticks());
+    assert!(!session(87, secs(100)).duration_matches_ticks());
+}
+
+#[test]
+fn partial_seconds_of_ticks_round_down() {
+    // 10.98 seconds of ticks count as 10, allowing 2 + 1 seconds
+    let ticks = secs(11) - 1;
+    assert_eq!(session(0, ticks).tick_secs(), 10);
+    assert!(session(13, ticks).duration_matches_ticks());
+    assert!(!session(14, ticks).duration_matches_ticks());
+}
+
+#[test]
+fn zero_ticks_only_match_a_short_game() {
+    assert!(session(0, 0).duration_matches_ticks());
+    assert!(session(2, 0).duration_matches_ticks());
+    assert!(!session(3, 0).duration_matches_ticks());
+    assert!(!session(600, 0).duration_matches_ticks());
+}
+
+#[test]
+fn time_limit_applies_to_timed_modes_only() {
+    let time_attack = GameMode::TimeAttack.rules();
+    let limit = u64::from(time_attack.time_limit_secs.unwrap());
+    assert!(session(limit, secs(limit)).within_time_limit(&time_attack));
+    assert!(!session(limit, secs(limit) + 1).within_time_limit(&time_attack));
+
+    let endless = GameMode::Endless.rules();
+    assert!(session(3600, secs(3600)).within_time_limit(&endless));
+}
+
+#[test]
+fn score_rate_is_capped_per_minute_of_ticks() {
+    let max = GameMode::Endless.rules().max_score_per_minute;
+    let minute = session(60, secs(60));
+    assert!(minute.score_rate_valid(max, max));
+    assert!(!minute.score_rate_valid(max + 10, max));
+    let half = session(30, secs(30));
+    assert!(half.score_rate_valid(max / 2, max));
+    assert!(!half.score_rate_valid(max / 2 + 10, max));
+}
+
+#[test]
+fn zero_ticks_allow_no_score() {
+    let empty = session(0, 0);
+    assert!(empty.score_rate_valid(0, u32::MAX));
+    assert!(!empty.score_rate_valid(10, u32::MAX));
+}
+
+#[test]
+fn score_rate_does_not_overflow() {
+    let long = session(u64::MAX / 2, u64::MAX);
+    assert!(long.score_rate_valid(u32::MAX, u32::MAX));
+}
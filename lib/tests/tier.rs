@@ -0,0 +1,91 @@
+//! Tier thresholds, the tier a score reaches and committed public values.
+
+use game_lib::tier::{
+    check_thresholds, decode, encode, tier_for, ThresholdError, TierPublicValues, MAX_THRESHOLDS,
+    TIER_ABI_VERSION,
+};
+use game_lib::{GameMode, RULES_VERSION};
+
+const THRESHOLDS: [u32; 3] = [1000, 5000, 10_000];
+
+#[test]
+fn score_at_a_threshold_reaches_its_tier() {
+    assert_eq!(tier_for(1000, &THRESHOLDS), 1);
+    assert_eq!(tier_for(5000, &THRESHOLDS), 2);
+    assert_eq!(tier_for(10_000, &THRESHOLDS), 3);
+    // One point short stays in the tier below
+    assert_eq!(tier_for(999, &THRESHOLDS), 0);
+    assert_eq!(tier_for(4999, &THRESHOLDS), 1);
+    assert_eq!(tier_for(9999, &THRESHOLDS), 2);
+}
+
+#[test]
+fn scores_below_the_lowest_or_above_the_highest_threshold() {
+    assert_eq!(tier_for(0, &THRESHOLDS), 0);
+    assert_eq!(tier_for(10_001, &THRESHOLDS), 3);
+    assert_eq!(tier_for(u32::MAX, &THRESHOLDS), 3);
+    // A threshold of zero is reached by every score
+    assert_eq!(tier_for(0, &[0, 10]), 1);
+}
+
+#[test]
+fn thresholds_must_be_strictly_ascending() {
+    assert_eq!(check_thresholds(&THRESHOLDS), Ok(()));
+    assert_eq!(check_thresholds(&[7]), Ok(()));
+    assert_eq!(check_thresholds(&[0, u32::MAX]), Ok(()));
+
+    assert_eq!(check_thresholds(&[5000, 1000]), Err(ThresholdError::NotAscending { index: 1 }));
+    assert_eq!(
+        check_thresholds(&[1000, 5000, 2000, 10_000]),
+        Err(ThresholdError::NotAscending { index: 2 })
+    );
+    // Duplicates would make two tiers mean the same score
+    assert_eq!(
+        check_thresholds(&[1000, 5000, 5000]),
+        Err(ThresholdError::NotAscending { index: 2 })
+    );
+    assert_eq!(check_thresholds(&[0, 0]), Err(ThresholdError::NotAscending { index: 1 }));
+}
+
+#[test]
+fn threshold_count_is_bounded() {
+    assert_eq!(check_thresholds(&[]), Err(ThresholdError::Empty));
+    let most: Vec<u32> = (1..=MAX_THRESHOLDS as u32).collect();
+    assert_eq!(check_thresholds(&most), Ok(()));
+    let too_many: Vec<u32> = (1..=MAX_THRESHOLDS as u32 + 1).collect();
+    assert_eq!(check_thresholds(&too_many), Err(ThresholdError::TooMany(MAX_THRESHOLDS + 1)));
+}
+
+fn values(tier: u32) -> TierPublicValues {
+    TierPublicValues {
+        abi_version: TIER_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: GameMode::Endless.id(),
+        timestamp: 1_700_000_000,
+        player_name_hash: game_lib::player_name_hash(b"alice"),
+        thresholds: THRESHOLDS.to_vec(),
+        tier,
+        verified: 1,
+    }
+}
+
+#[test]
+fn min_score_of_each_tier() {
+    assert_eq!(values(0).min_score(), None);
+    assert_eq!(values(1).min_score(), Some(1000));
+    assert_eq!(values(3).min_score(), Some(10_000));
+    // A tier past the thresholds guarantees nothing
+    assert_eq!(values(4).min_score(), None);
+}
+
+#[test]
+fn public_values_round_trip() {
+    for tier in 0..=3 {
+        assert_eq!(decode(&encode(values(tier))).unwrap(), values(tier));
+    }
+
+    // `encode` always writes the current version, so patch the first word
+    let mut bytes = encode(values(1));
+    bytes[31] = (TIER_ABI_VERSION + 1) as u8;
+    assert!(decode(&bytes).is_err());
+}
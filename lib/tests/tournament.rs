@@ -0,0 +1,68 @@
+//! Tournament windows, rule commitments and committed public values.
+
+use game_lib::tournament::{
+    decode, encode, TournamentPublicValues, TournamentRules, TOURNAMENT_ABI_VERSION,
+};
+use game_lib::{GameMode, RULES_VERSION};
+
+fn rules() -> TournamentRules {
+    TournamentRules {
+        id: "spring-cup".to_string(),
+        mode: GameMode::TimeAttack,
+        seed: 42,
+        starts_at: 1_700_000_000,
+        ends_at: 1_700_086_400,
+    }
+}
+
+#[test]
+fn window_includes_its_start_but_not_its_end() {
+    let rules = rules();
+    assert!(!rules.in_window(0));
+    assert!(!rules.in_window(rules.starts_at - 1));
+    assert!(rules.in_window(rules.starts_at));
+    assert!(rules.in_window(rules.ends_at - 1));
+    assert!(!rules.in_window(rules.ends_at));
+    assert!(!rules.in_window(u64::MAX));
+
+    let empty = TournamentRules { ends_at: rules.starts_at, ..rules };
+    assert!(!empty.in_window(empty.starts_at));
+}
+
+#[test]
+fn commitment_covers_every_rule() {
+    let base = rules();
+    assert_eq!(base.commitment(), rules().commitment());
+    let changed = [
+        TournamentRules { id: "spring-cup2".to_string(), ..rules() },
+        TournamentRules { mode: GameMode::Hardcore, ..rules() },
+        TournamentRules { seed: 43, ..rules() },
+        TournamentRules { starts_at: base.starts_at + 1, ..rules() },
+        TournamentRules { ends_at: base.ends_at + 1, ..rules() },
+    ];
+    for (i, other) in changed.iter().enumerate() {
+        assert_ne!(other.commitment(), base.commitment(), "rule {}", i);
+    }
+}
+
+#[test]
+fn public_values_round_trip() {
+    let values = TournamentPublicValues {
+        abi_version: TOURNAMENT_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        tournament_id: rules().commitment(),
+        mode: GameMode::TimeAttack.id(),
+        seed: u64::MAX,
+        timestamp: 1_700_000_100,
+        player_name_hash: game_lib::player_name_hash(b"alice"),
+        score: 1230,
+        replay_hash: [9; 32],
+        verified: 1,
+    };
+    assert_eq!(decode(&encode(values)).unwrap(), values);
+
+    // `encode` always writes the current version, so patch the first word
+    let mut bytes = encode(values);
+    bytes[31] = (TOURNAMENT_ABI_VERSION + 1) as u8;
+    assert!(decode(&bytes).is_err());
+}
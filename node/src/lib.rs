@@ -0,0 +1,536 @@
+//! Node.js bindings to the score prover.
+//!
+//! Build with `npm run build` in this directory, which writes `index.js`,
+//! `index.d.ts` and the native module next to them. The backend loads this
+//! instead of running `bladewarrior` and scraping its output: every call runs
+//! on a worker thread, resolves to a plain JS object and, where a call goes
+//! through the proving pipeline, reports each stage to an optional callback.
+//!
+//! Settings are resolved like the CLI's — config file, then `BLADEWARRIOR_*`
+//! environment variables — with [`configure`] standing in for the flags. The
+//! proving keys are generated on first use and shared by later calls.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use game_lib::session::Session;
+use game_lib::{GameMode, GameScorePublicValues};
+use game_verification_script::challenge::{
+    load_or_create_signing_key, Adjudicator, ChallengeRequest,
+};
+use game_verification_script::config::{CliOverrides, Config, ProverMode};
+use game_verification_script::daily::DailyChallenge;
+use game_verification_script::progress::{ProgressEvent, ProgressObserver};
+use game_verification_script::store::{read_proof, ProofStore, StoreEntry};
+use game_verification_script::{current_unix_time, ScoreProver, Submission, VerifyError};
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::de::DeserializeOwned;
+
+/// Progress callback, called with a [`ProgressUpdate`] per stage event
+type ProgressCallback = ThreadsafeFunction<ProgressUpdate, ErrorStrategy::Fatal>;
+
+/// Shared state behind every call, created by [`configure`] or on first use
+struct Engine {
+    config: Config,
+    store: ProofStore,
+    prover: OnceLock<ScoreProver>,
+    /// Borrows `prover`, which lives as long as [`ENGINE`]
+    adjudicator: OnceLock<Adjudicator<'static>>,
+}
+
+static ENGINE: OnceLock<Engine> = OnceLock::new();
+
+impl Engine {
+    fn new(cli: &CliOverrides) -> Result<Self> {
+        let config = Config::load(cli).map_err(error)?;
+        config.apply_prover_mode();
+        let store = ProofStore::open(&config.store_dir.value).map_err(error)?;
+        Ok(Self { config, store, prover: OnceLock::new(), adjudicator: OnceLock::new() })
+    }
+
+    /// Prover with its keys set up; only the first caller sees the keygen stage
+    fn prover(&self, observer: &dyn ProgressObserver) -> &ScoreProver {
+        self.prover.get_or_init(|| ScoreProver::new(self.config.policy(), observer))
+    }
+
+    /// Adjudicator sharing [`Engine::prover`], so its keys are only set up once
+    fn adjudicator(&'static self) -> Result<&'static Adjudicator<'static>> {
+        if let Some(adjudicator) = self.adjudicator.get() {
+            return Ok(adjudicator);
+        }
+        let key_path = self.config.cache_dir.value.join("verdict_signing.key");
+        let key = load_or_create_signing_key(&key_path).map_err(error)?;
+        let prover = self.prover(&JsObserver(None));
+        Ok(self.adjudicator.get_or_init(|| Adjudicator::new(prover, key)))
+    }
+}
+
+fn engine() -> Result<&'static Engine> {
+    if let Some(engine) = ENGINE.get() {
+        return Ok(engine);
+    }
+    let engine = Engine::new(&CliOverrides::default())?;
+    Ok(ENGINE.get_or_init(|| engine))
+}
+
+fn error(e: impl fmt::Display) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+/// Run `f` on the blocking pool so proving does not stall the event loop
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f).await.map_err(error)?
+}
+
+/// Convert a JS number holding a `u64`, such as a Unix timestamp
+fn to_u64(name: &str, value: f64) -> Result<u64> {
+    // u64::MAX rounds up to 2^64 as an f64, so the bound must be exclusive
+    if value.is_finite() && value >= 0.0 && value.fract() == 0.0 && value < u64::MAX as f64 {
+        Ok(value as u64)
+    } else {
+        Err(error(format_args!("{} must be a non-negative integer, got {}", name, value)))
+    }
+}
+
+/// Overrides for the configuration file and environment, as the CLI flags
+#[napi(object)]
+pub struct Options {
+    pub config_file: Option<String>,
+    /// `cpu`, `cuda`, `network` or `mock`
+    pub prover_mode: Option<String>,
+    pub store_dir: Option<String>,
+    pub cache_dir: Option<String>,
+}
+
+/// Resolve the configuration and open the proof store
+///
+/// Optional, and only allowed before any other call; without it the
+/// configuration file and environment alone are used.
+#[napi]
+pub fn configure(options: Option<Options>) -> Result<()> {
+    let options = options.unwrap_or(Options {
+        config_file: None,
+        prover_mode: None,
+        store_dir: None,
+        cache_dir: None,
+    });
+    let cli = CliOverrides {
+        config_file: options.config_file.map(PathBuf::from),
+        prover_mode: options
+            .prover_mode
+            .map(|mode| mode.parse::<ProverMode>())
+            .transpose()
+            .map_err(error)?,
+        store_dir: options.store_dir.map(PathBuf::from),
+        cache_dir: options.cache_dir.map(PathBuf::from),
+        parallelism: None,
+    };
+    ENGINE
+        .set(Engine::new(&cli)?)
+        .map_err(|_| error("configure must be called before any other function"))
+}
+
+/// Address the verification server listens on, `server.bind` in the configuration
+#[napi]
+pub fn server_bind() -> Result<String> {
+    Ok(engine()?.config.server_bind.value.clone())
+}
+
+/// Deserialize an optional JSON array, empty if not given
+fn list<T: DeserializeOwned>(name: &str, value: Option<serde_json::Value>) -> Result<Vec<T>> {
+    match value {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| error(format_args!("invalid {}: {}", name, e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// A score submission, in the shape the web client posts it
+#[napi(object)]
+pub struct SubmissionInput {
+    /// Unix time the game ended
+    pub timestamp: f64,
+    pub player: String,
+    pub score: u32,
+    /// Hex SHA-256 game hash
+    pub game_hash: String,
+    /// Mode name; defaults to `endless`
+    pub mode: Option<String>,
+    /// Per-wave `{wave, tick, score, lives, state_hash}` checkpoints
+    pub checkpoints: Option<serde_json::Value>,
+    /// Client event log for anti-cheat analysis
+    pub events: Option<serde_json::Value>,
+    /// Unix time the game started; defaults to when it ended
+    pub started_at: Option<f64>,
+    /// Unix time the game ended; defaults to `timestamp`
+    pub ended_at: Option<f64>,
+    /// Ticks the game's simulation ran
+    pub ticks: Option<f64>,
+    /// Hex encoded replay of the game, kept so the score can be challenged
+    pub replay: Option<String>,
+}
+
+impl TryFrom<SubmissionInput> for Submission {
+    type Error = Error;
+
+    fn try_from(input: SubmissionInput) -> Result<Self> {
+        let mode = match input.mode {
+            Some(mode) => mode.parse::<GameMode>().map_err(error)?,
+            None => GameMode::default(),
+        };
+        let timestamp = to_u64("timestamp", input.timestamp)?;
+        let ended_at = match input.ended_at {
+            Some(time) => to_u64("endedAt", time)?,
+            None => timestamp,
+        };
+        let session = Session {
+            started_at: match input.started_at {
+                Some(time) => to_u64("startedAt", time)?,
+                None => ended_at,
+            },
+            ended_at,
+            ticks: to_u64("ticks", input.ticks.unwrap_or(0.0))?,
+        };
+        let replay = match input.replay {
+            Some(replay) => {
+                hex::decode(replay).map_err(|e| error(format_args!("invalid replay: {}", e)))?
+            }
+            None => Vec::new(),
+        };
+        Ok(Submission {
+            timestamp,
+            player: input.player,
+            score: input.score,
+            game_hash: input.game_hash,
+            mode,
+            checkpoints: list("checkpoints", input.checkpoints)?,
+            session,
+            events: list("events", input.events)?,
+            replay,
+        })
+    }
+}
+
+/// Decoded public values of a proof, hashes as hex
+#[napi(object)]
+pub struct PublicValues {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    pub mode_id: u32,
+    /// Mode name, if the id is one this build knows
+    pub mode: Option<String>,
+    pub timestamp: f64,
+    pub player_name_hash: String,
+    pub score: u32,
+    pub game_hash: String,
+    pub verified: bool,
+    pub checkpoint_root: String,
+    pub checkpoint_count: u32,
+    pub duration_secs: u32,
+    pub ticks: f64,
+}
+
+impl From<&GameScorePublicValues> for PublicValues {
+    fn from(values: &GameScorePublicValues) -> Self {
+        Self {
+            abi_version: values.abi_version,
+            rules_version: values.rules_version,
+            mode_id: values.mode,
+            mode: GameMode::from_id(values.mode).map(|mode| mode.to_string()),
+            timestamp: values.timestamp as f64,
+            player_name_hash: hex::encode(values.player_name_hash),
+            score: values.score,
+            game_hash: hex::encode(values.game_hash),
+            verified: values.verified == 1,
+            checkpoint_root: hex::encode(values.checkpoint_root),
+            checkpoint_count: values.checkpoint_count,
+            duration_secs: values.duration_secs,
+            ticks: values.ticks as f64,
+        }
+    }
+}
+
+impl From<&StoreEntry> for PublicValues {
+    fn from(entry: &StoreEntry) -> Self {
+        Self {
+            abi_version: entry.abi_version,
+            rules_version: entry.rules_version,
+            mode_id: entry.mode,
+            mode: GameMode::from_id(entry.mode).map(|mode| mode.to_string()),
+            timestamp: entry.timestamp as f64,
+            player_name_hash: entry.player_name_hash.clone(),
+            score: entry.score,
+            game_hash: entry.game_hash.clone(),
+            verified: entry.verified,
+            checkpoint_root: entry.checkpoint_root.clone(),
+            checkpoint_count: entry.checkpoint_count,
+            duration_secs: entry.duration_secs,
+            ticks: entry.ticks as f64,
+        }
+    }
+}
+
+/// Why a submission or proof was rejected
+#[napi(object)]
+pub struct Failure {
+    /// Machine-readable reason, as `VerifyError::kind`
+    pub kind: String,
+    pub message: String,
+}
+
+impl From<&VerifyError> for Failure {
+    fn from(e: &VerifyError) -> Self {
+        Self { kind: e.kind().to_string(), message: e.to_string() }
+    }
+}
+
+/// One stage event from the proving pipeline
+#[napi(object)]
+pub struct ProgressUpdate {
+    /// `keygen`, `execute`, `prove`, `verify` or `save`
+    pub stage: String,
+    /// `started`, `finished` or `failed`
+    pub status: String,
+    /// Time the stage took, once it has finished or failed
+    pub elapsed_ms: Option<f64>,
+    /// Guest cycles, for stages that run the guest
+    pub cycles: Option<f64>,
+    pub reason: Option<String>,
+}
+
+impl From<&ProgressEvent> for ProgressUpdate {
+    fn from(event: &ProgressEvent) -> Self {
+        let stage = event.stage().as_str().to_string();
+        match event {
+            ProgressEvent::Started { .. } => Self {
+                stage,
+                status: "started".to_string(),
+                elapsed_ms: None,
+                cycles: None,
+                reason: None,
+            },
+            ProgressEvent::Finished { elapsed, cycles, .. } => Self {
+                stage,
+                status: "finished".to_string(),
+                elapsed_ms: Some(elapsed.as_secs_f64() * 1000.0),
+                cycles: cycles.map(|cycles| cycles as f64),
+                reason: None,
+            },
+            ProgressEvent::Failed { elapsed, reason, .. } => Self {
+                stage,
+                status: "failed".to_string(),
+                elapsed_ms: Some(elapsed.as_secs_f64() * 1000.0),
+                cycles: None,
+                reason: Some(reason.clone()),
+            },
+        }
+    }
+}
+
+/// Forwards stage events to a JS callback without waiting for it
+struct JsObserver(Option<ProgressCallback>);
+
+impl ProgressObserver for JsObserver {
+    fn on_event(&self, event: &ProgressEvent) {
+        if let Some(callback) = &self.0 {
+            callback.call(ProgressUpdate::from(event), ThreadsafeFunctionCallMode::NonBlocking);
+        }
+    }
+}
+
+/// Outcome of [`prove`]
+#[napi(object)]
+pub struct ProveResult {
+    pub success: bool,
+    /// Store hash of the saved proof
+    pub proof_hash: Option<String>,
+    pub proof_path: Option<String>,
+    pub public_values: Option<PublicValues>,
+    /// Anti-cheat report, if the submission had an event log
+    pub anticheat: Option<serde_json::Value>,
+    pub failure: Option<Failure>,
+}
+
+/// Check, prove, verify and store a submission
+///
+/// A rejected submission resolves with `success: false` and the reason in
+/// `failure`; the promise only rejects if the prover cannot be set up.
+#[napi]
+pub async fn prove(
+    submission: SubmissionInput,
+    on_progress: Option<ProgressCallback>,
+) -> Result<ProveResult> {
+    let submission = Submission::try_from(submission)?;
+    blocking(move || {
+        let engine = engine()?;
+        let observer = JsObserver(on_progress);
+        let prover = engine.prover(&observer);
+        let result = match prover.prove(&submission, current_unix_time(), &engine.store, &observer)
+        {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(ProveResult {
+                    success: false,
+                    proof_hash: None,
+                    proof_path: None,
+                    public_values: None,
+                    anticheat: None,
+                    failure: Some(Failure::from(&e)),
+                })
+            }
+        };
+        let entry = engine.store.get(&result.proof_hash);
+        let anticheat = result.anticheat.as_ref().map(serde_json::to_value).transpose();
+        Ok(ProveResult {
+            success: result.success && entry.as_ref().is_some_and(|entry| entry.verified),
+            public_values: entry.as_ref().map(PublicValues::from),
+            anticheat: anticheat.map_err(error)?,
+            proof_hash: Some(result.proof_hash),
+            proof_path: Some(result.proof_path),
+            failure: None,
+        })
+    })
+    .await
+}
+
+/// Run the guest on a submission without proving
+///
+/// `currentTime` is the Unix time the timestamp is checked against, now by
+/// default. Whether the guest accepted the score is `verified` in the result.
+#[napi]
+pub async fn execute(
+    submission: SubmissionInput,
+    current_time: Option<f64>,
+    on_progress: Option<ProgressCallback>,
+) -> Result<PublicValues> {
+    let submission = Submission::try_from(submission)?;
+    let current_time = match current_time {
+        Some(time) => to_u64("currentTime", time)?,
+        None => current_unix_time(),
+    };
+    blocking(move || {
+        let engine = engine()?;
+        let prover = engine.prover(&JsObserver(on_progress));
+        let bytes = prover.execute(&submission, current_time).map_err(error)?;
+        let values = game_lib::abi::decode(&bytes).map_err(error)?;
+        Ok(PublicValues::from(&values))
+    })
+    .await
+}
+
+/// Outcome of [`verify_proof`]
+#[napi(object)]
+pub struct VerifyResult {
+    /// The proof verifies and the guest accepted the score
+    pub valid: bool,
+    pub public_values: PublicValues,
+    pub failure: Option<Failure>,
+}
+
+/// Verify a stored proof by hash, or a proof file by path
+#[napi]
+pub async fn verify_proof(
+    reference: String,
+    on_progress: Option<ProgressCallback>,
+) -> Result<VerifyResult> {
+    blocking(move || {
+        let engine = engine()?;
+        let proof = match engine.store.get(&reference) {
+            Some(_) => engine.store.load(&reference),
+            None if Path::new(&reference).exists() => read_proof(Path::new(&reference)),
+            None => {
+                return Err(error(format_args!(
+                    "{} is neither a stored proof hash nor a file",
+                    reference
+                )))
+            }
+        }
+        .map_err(error)?;
+        let values = game_lib::abi::decode(proof.public_values.as_slice()).map_err(error)?;
+
+        let prover = engine.prover(&JsObserver(on_progress));
+        let failure = prover.verify(&proof).err().map(|e| Failure::from(&e));
+        Ok(VerifyResult {
+            valid: failure.is_none() && values.verified == 1,
+            public_values: PublicValues::from(&values),
+            failure,
+        })
+    })
+    .await
+}
+
+/// Decode ABI-encoded public values committed by any guest release
+#[napi]
+pub async fn decode_public_values(bytes: Buffer) -> Result<PublicValues> {
+    let values = game_lib::abi::decode(&bytes).map_err(error)?;
+    Ok(PublicValues::from(&values))
+}
+
+/// Seed and time window of a daily challenge
+#[napi(object)]
+pub struct DailyChallengeInfo {
+    /// `YYYY-MM-DD`, in UTC
+    pub date: String,
+    /// Days since 1970-01-01
+    pub day: u32,
+    /// Decimal, as it does not fit a JS number; pass it to the game as a `BigInt`
+    pub seed: String,
+    /// Domain string the seed is derived under
+    pub domain: String,
+    pub starts_at: f64,
+    pub ends_at: f64,
+}
+
+/// The daily challenge of a `YYYY-MM-DD` date, today's by default
+#[napi]
+pub fn daily_challenge(date: Option<String>) -> Result<DailyChallengeInfo> {
+    let challenge = match date {
+        Some(date) => DailyChallenge::for_date(&date).map_err(error)?,
+        None => DailyChallenge::today(),
+    };
+    Ok(DailyChallengeInfo {
+        date: challenge.date,
+        day: challenge.day,
+        seed: challenge.seed.to_string(),
+        domain: challenge.domain,
+        starts_at: challenge.starts_at as f64,
+        ends_at: challenge.ends_at as f64,
+    })
+}
+
+/// A challenge against a stored proof
+#[napi(object)]
+pub struct ChallengeInput {
+    pub proof_hash: String,
+    pub challenger: String,
+    pub reason: Option<String>,
+}
+
+/// Replay a challenged score and record a signed verdict
+///
+/// Resolves with the verdict as the CLI prints it; the signing key is
+/// `verdict_signing.key` in the cache directory, created if missing.
+#[napi]
+pub async fn challenge(request: ChallengeInput) -> Result<serde_json::Value> {
+    blocking(move || {
+        let engine = engine()?;
+        if engine.store.get(&request.proof_hash).is_none() {
+            return Err(error(format_args!("no proof with hash {}", request.proof_hash)));
+        }
+        let request = ChallengeRequest {
+            proof_hash: request.proof_hash,
+            challenger: request.challenger,
+            reason: request.reason.unwrap_or_default(),
+        };
+        let verdict = engine
+            .adjudicator()?
+            .challenge(&engine.store, &request, current_unix_time())
+            .map_err(error)?;
+        serde_json::to_value(&verdict).map_err(error)
+    })
+    .await
+}
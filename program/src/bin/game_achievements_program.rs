@@ -0,0 +1,71 @@
+//! SP1 proof program proving the achievements a replayed game earned
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use game_lib::achievements::{
+    self, Achievement, AchievementPublicValues, AchievementTracker, ACHIEVEMENTS_ABI_VERSION,
+};
+use game_lib::replay;
+use game_lib::RULES_VERSION;
+
+pub fn main() {
+    // Read input data
+    println!("cycle-tracker-report-start: read_input");
+    let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Read player name as bytes
+    let chunk_count = sp1_zkvm::io::read::<u32>(); // Chunks of the encoded replay that follow
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: hash_inputs");
+    // Hash player name for privacy
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    println!("cycle-tracker-report-end: hash_inputs");
+
+    println!("cycle-tracker-report-start: simulate");
+    // Every achievement is computed from the simulation, none from the client.
+    // Chunks of the replay are decoded, hashed and simulated as they are read,
+    // so guest memory does not grow with the length of the game
+    let chunks = (0..chunk_count).map(|_| sp1_zkvm::io::read::<Vec<u8>>());
+    let mut tracker = AchievementTracker::new();
+    let (replay_hash, run) = replay::run_chunks(chunks, |sim, events| tracker.observe(sim, events));
+    let replay_valid = run.is_ok();
+    let (mode_id, earned) = match &run {
+        Ok((header, sim)) => {
+            println!("Ticks: {}", sim.tick());
+            println!("Score: {}", sim.score());
+            (header.mode.id(), tracker.earned())
+        }
+        Err(e) => {
+            println!("Replay Error: {}", e);
+            (0, 0)
+        }
+    };
+    println!("cycle-tracker-report-end: simulate");
+
+    let verified = replay_valid as u32;
+
+    println!("===== GAME ACHIEVEMENTS REPORT =====");
+    println!("Player: [HASHED]");
+    println!("Mode: {}", mode_id);
+    println!("Replay Valid: {}", replay_valid);
+    for achievement in Achievement::in_mask(earned) {
+        println!("Earned: {}", achievement.description());
+    }
+    println!("Verification Status: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
+    println!("====================================");
+
+    let public_values = AchievementPublicValues {
+        abi_version: ACHIEVEMENTS_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: mode_id,
+        player_name_hash,
+        replay_hash,
+        achievements: earned,
+        verified,
+    };
+
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = achievements::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
+    sp1_zkvm::io::commit_slice(&encoded);
+}
@@ -0,0 +1,88 @@
+//! SP1 proof program verifying a two-player co-op game from its replay
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use game_lib::coop::{self, CoopPublicValues, COOP_ABI_VERSION};
+use game_lib::RULES_VERSION;
+
+pub fn main() {
+    // Read input data
+    println!("cycle-tracker-report-start: read_input");
+    let timestamp = sp1_zkvm::io::read::<u64>();
+    let first_player = sp1_zkvm::io::read::<Vec<u8>>(); // Player on the first input stream
+    let second_player = sp1_zkvm::io::read::<Vec<u8>>(); // Player on the second input stream
+    let current_time = sp1_zkvm::io::read::<u64>(); // Current timestamp
+    let chunk_count = sp1_zkvm::io::read::<u32>(); // Chunks of the encoded co-op replay that follow
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: hash_inputs");
+    // Hash player names for privacy
+    let player_name_hashes =
+        [game_lib::player_name_hash(&first_player), game_lib::player_name_hash(&second_player)];
+    println!("cycle-tracker-report-end: hash_inputs");
+
+    println!("cycle-tracker-report-start: simulate");
+    // Scores come from the simulation, so each kill is credited to the player who made it.
+    // The replay streams in chunks that are hashed and stepped through as they arrive
+    let chunks = (0..chunk_count).map(|_| sp1_zkvm::io::read::<Vec<u8>>());
+    let (replay_hash, run) = coop::run_chunks(chunks);
+    let replay_valid = run.is_ok();
+    let (mode, sim) = match run {
+        Ok((header, sim)) => (Some(header.mode), Some(sim)),
+        Err(e) => {
+            println!("Replay Error: {}", e);
+            (None, None)
+        }
+    };
+    let scores = sim.as_ref().map_or([0, 0], |sim| sim.scores());
+    let wave = sim.as_ref().map_or(0, |sim| sim.wave());
+    println!("cycle-tracker-report-end: simulate");
+
+    println!("cycle-tracker-report-start: validate");
+    // Limits shared with the host
+    use game_lib::{MAX_SCORE, MAX_TIMESTAMP_SKEW_SECS};
+
+    // Verify timestamp (not more than 1 hour difference)
+    let timestamp_valid = current_time.abs_diff(timestamp) <= MAX_TIMESTAMP_SKEW_SECS;
+
+    // Each player's score must be within the limits of the mode
+    let scores_valid = mode.is_some_and(|mode| {
+        scores.iter().all(|&score| score <= MAX_SCORE && mode.rules().score_valid(score))
+    });
+
+    // A player cannot team up with themselves
+    let players_valid = player_name_hashes[0] != player_name_hashes[1];
+
+    let verified = (replay_valid && timestamp_valid && scores_valid && players_valid) as u32;
+    println!("cycle-tracker-report-end: validate");
+
+    println!("===== CO-OP GAME REPORT =====");
+    println!("Timestamp: {}", timestamp);
+    println!("Timestamp Valid: {}", timestamp_valid);
+    println!("Players: [HASHED]");
+    println!("Players Valid: {}", players_valid);
+    println!("Replay Valid: {}", replay_valid);
+    println!("Scores: {:?}", scores);
+    println!("Scores Valid: {}", scores_valid);
+    println!("Wave: {}", wave);
+    println!("Verification Status: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
+    println!("=============================");
+
+    let public_values = CoopPublicValues {
+        abi_version: COOP_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: mode.map_or(0, |mode| mode.id()),
+        timestamp,
+        replay_hash,
+        player_name_hashes,
+        scores,
+        wave,
+        verified,
+    };
+
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = coop::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
+    sp1_zkvm::io::commit_slice(&encoded);
+}
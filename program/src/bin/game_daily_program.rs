@@ -0,0 +1,92 @@
+//! SP1 proof program verifying a daily challenge game from its replay
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use game_lib::daily::{self, DailyPublicValues, DAILY_ABI_VERSION, SECS_PER_DAY};
+use game_lib::replay;
+use game_lib::RULES_VERSION;
+
+pub fn main() {
+    // Read input data
+    println!("cycle-tracker-report-start: read_input");
+    let day = sp1_zkvm::io::read::<u32>(); // Day of the challenge, counted from 1970-01-01
+    let timestamp = sp1_zkvm::io::read::<u64>();
+    let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Read player name as bytes
+    let current_time = sp1_zkvm::io::read::<u64>(); // Current timestamp
+    let chunk_count = sp1_zkvm::io::read::<u32>(); // Chunks of the encoded replay that follow
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: hash_inputs");
+    // Hash player name for privacy and the date to recompute the day's seed
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    let daily_seed = daily::daily_seed(day);
+    println!("cycle-tracker-report-end: hash_inputs");
+
+    println!("cycle-tracker-report-start: simulate");
+    // The score comes from the simulation rather than the client. The replay
+    // is read, hashed and simulated a chunk at a time, so memory stays
+    // bounded however long the game ran, and its hash binds the score to it
+    let chunks = (0..chunk_count).map(|_| sp1_zkvm::io::read::<Vec<u8>>());
+    let (replay_hash, run) = replay::run_chunks(chunks, |_, _| {});
+    let replay_valid = run.is_ok();
+    let (mode, seed, score) = match &run {
+        Ok((header, sim)) => (Some(header.mode), header.seed, sim.score()),
+        Err(e) => {
+            println!("Replay Error: {}", e);
+            (None, 0, 0)
+        }
+    };
+    println!("cycle-tracker-report-end: simulate");
+
+    println!("cycle-tracker-report-start: validate");
+    // Limits shared with the host
+    use game_lib::{MAX_SCORE, MAX_TIMESTAMP_SKEW_SECS};
+
+    // Verify timestamp (not more than 1 hour difference)
+    let timestamp_valid = current_time.abs_diff(timestamp) <= MAX_TIMESTAMP_SKEW_SECS;
+
+    // The game must be played on the day's seed, during that day
+    let seed_valid = replay_valid && seed == daily_seed;
+    let start = daily::day_start(day);
+    let day_valid = start <= timestamp && timestamp < start + SECS_PER_DAY;
+
+    // Verify score follows the rules of the mode
+    let score_valid =
+        score <= MAX_SCORE && mode.is_some_and(|mode| mode.rules().score_valid(score));
+
+    let verified =
+        (replay_valid && timestamp_valid && seed_valid && day_valid && score_valid) as u32;
+    println!("cycle-tracker-report-end: validate");
+
+    println!("===== DAILY CHALLENGE REPORT =====");
+    println!("Date: {}", daily::date(day));
+    println!("Timestamp: {}", timestamp);
+    println!("Timestamp Valid: {}", timestamp_valid);
+    println!("Player: [HASHED]");
+    println!("Replay Valid: {}", replay_valid);
+    println!("Seed Valid: {}", seed_valid);
+    println!("Day Valid: {}", day_valid);
+    println!("Score: {}", score);
+    println!("Score Valid: {}", score_valid);
+    println!("Verification Status: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
+    println!("==================================");
+
+    let public_values = DailyPublicValues {
+        abi_version: DAILY_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        day,
+        seed: daily_seed,
+        mode: mode.map_or(0, |mode| mode.id()),
+        timestamp,
+        player_name_hash,
+        score,
+        replay_hash,
+        verified,
+    };
+
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = daily::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
+    sp1_zkvm::io::commit_slice(&encoded);
+}
@@ -0,0 +1,64 @@
+//! SP1 proof program proving a player is in the top K of a committed leaderboard
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use game_lib::checkpoint::InclusionProof;
+use game_lib::rank::{self, RankLeaf, RankPublicValues, RANK_ABI_VERSION};
+
+pub fn main() {
+    // Read input data; only the root and k are public
+    println!("cycle-tracker-report-start: read_input");
+    let board_root = sp1_zkvm::io::read::<[u8; 32]>(); // Committed leaderboard root
+    let k = sp1_zkvm::io::read::<u32>(); // Rank the player must be at or above
+    let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Read player name as bytes
+    let leaf = sp1_zkvm::io::read::<RankLeaf>(); // The player's row, kept private
+    let proof = sp1_zkvm::io::read::<InclusionProof>(); // Path from the row to the root
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: hash_player_name");
+    // Hash player name for privacy
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    println!("cycle-tracker-report-end: hash_player_name");
+
+    println!("cycle-tracker-report-start: validate");
+    // The row must be on the board at the position of its own rank
+    let included = rank::verify_rank(&leaf, &proof, &board_root);
+
+    // The row must belong to the player
+    let player_valid = leaf.player_name_hash == player_name_hash;
+
+    // The rank must be within the top k
+    let rank_valid = leaf.rank <= k;
+
+    let member = (included && player_valid && rank_valid) as u32;
+    println!("cycle-tracker-report-end: validate");
+
+    // The row is private, so only the checks are printed
+    println!("===== LEADERBOARD RANK REPORT =====");
+    println!("Player: [HASHED]");
+    println!("Rank: [PRIVATE]");
+    println!("Top K: {}", k);
+    println!("Board Size: {}", proof.leaf_count);
+    println!("Included: {}", included);
+    println!("Player Valid: {}", player_valid);
+    println!("Rank Valid: {}", rank_valid);
+    println!("Membership: {}", if member == 1 { "SUCCESS" } else { "FAILED" });
+    println!("===================================");
+
+    // Create public values; the row and its path stay private
+    let public_values = RankPublicValues {
+        abi_version: RANK_ABI_VERSION,
+        mode: leaf.mode,
+        board_root,
+        leaf_count: proof.leaf_count as u32, // Bound by the root when included
+        k,
+        player_name_hash,
+        member,
+    };
+
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = rank::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
+    sp1_zkvm::io::commit_slice(&encoded);
+}
@@ -3,63 +3,27 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use alloy_sol_types::private::FixedBytes;
-
-/// Struktur untuk data publik verifikasi skor game
-#[derive(Debug, Clone)]
-pub struct GameScorePublicValues {
-    pub timestamp: u64,
-    pub player_name_hash: [u8; 32],
-    pub score: u32,
-    pub game_hash: [u8; 32],
-    pub verified: u32,
-}
-
-/// Implementasi encoding ABI untuk GameScorePublicValues
-pub mod abi {
-    use super::*;
-    use alloy_sol_types::{sol, SolType};
-
-    sol! {
-        struct GameScoreData {
-            uint64 timestamp;
-            bytes32 playerNameHash;
-            uint32 score;
-            bytes32 gameHash;
-            uint32 verified;
-        }
-    }
-
-    impl From<GameScorePublicValues> for GameScoreData {
-        fn from(value: GameScorePublicValues) -> Self {
-            Self {
-                timestamp: value.timestamp,
-                playerNameHash: FixedBytes(value.player_name_hash),
-                score: value.score,
-                gameHash: FixedBytes(value.game_hash),
-                verified: value.verified,
-            }
-        }
-    }
-
-    pub fn encode(values: GameScorePublicValues) -> Vec<u8> {
-        GameScoreData::abi_encode(&GameScoreData::from(values))
-    }
-}
+use game_lib::checkpoint::{self, Checkpoint};
+use game_lib::session::Session;
+use game_lib::{GameMode, GameScorePublicValues, ABI_VERSION, RULES_VERSION};
 
 pub fn main() {
     // Baca input data dari SP1 VM
+    println!("cycle-tracker-report-start: read_input");
     let timestamp = sp1_zkvm::io::read::<u64>();
     let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Membaca nama pemain sebagai bytes
     let score = sp1_zkvm::io::read::<u32>();
     let game_hash_input = sp1_zkvm::io::read::<Vec<u8>>(); // Membaca hash game sebagai bytes
+    let current_time = sp1_zkvm::io::read::<u64>(); // Timestamp saat ini
+    let mode_id = sp1_zkvm::io::read::<u32>(); // Id mode permainan
+    let checkpoints = sp1_zkvm::io::read::<Vec<Checkpoint>>(); // Checkpoint tiap wave
+    let session = sp1_zkvm::io::read::<Session>(); // Waktu mulai, selesai dan jumlah tick
+    println!("cycle-tracker-report-end: read_input");
 
-    // Hash nama pemain untuk privasi menggunakan hash SHA-256 manual
-    // karena sp1_zkvm::hash::sha256 tidak tersedia
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    hasher.update(&player_name);
-    let player_name_hash: [u8; 32] = hasher.finalize().into();
+    println!("cycle-tracker-report-start: hash_player_name");
+    // Hash nama pemain untuk privasi
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    println!("cycle-tracker-report-end: hash_player_name");
     
     // Konversi game_hash ke [u8; 32]
     let mut game_hash = [0u8; 32];
@@ -67,12 +31,10 @@ pub fn main() {
         game_hash[i] = byte;
     }
 
-    // Konstanta
-    const MAX_SCORE: u32 = 10000; 
+    println!("cycle-tracker-report-start: validate");
+    // Batas yang sama dengan host
+    use game_lib::{MAX_SCORE, MAX_SCORE_PER_MINUTE, MAX_TIMESTAMP_SKEW_SECS};
 
-    // Verifikasi skor
-    let current_time = sp1_zkvm::io::read::<u64>(); // Timestamp saat ini
-    
     // Verifikasi timestamp (tidak lebih dari 1 jam perbedaan)
     let time_diff = if current_time > timestamp {
         current_time - timestamp
@@ -80,37 +42,79 @@ pub fn main() {
         timestamp - current_time
     };
     
-    let timestamp_valid = time_diff <= 3600;
+    let timestamp_valid = time_diff <= MAX_TIMESTAMP_SKEW_SECS;
     
-    // Verifikasi skor tidak melebihi batas
-    let score_valid = score <= MAX_SCORE;
+    // Verifikasi mode dikenal dan skor sesuai aturan mode
+    let mode = GameMode::from_id(mode_id);
+    let mode_valid = mode.is_some();
+    let score_valid = score <= MAX_SCORE && mode.is_some_and(|mode| mode.rules().score_valid(score));
+
+    // Checkpoint harus berurutan dan konsisten dengan skor akhir
+    let checkpoints_valid = mode.is_some_and(|mode| {
+        checkpoint::sequence_valid(&checkpoints, score, &mode.rules())
+    });
+    
+    // Game harus selesai sebelum dikirim, berlangsung selama tick-nya dan
+    // berhenti pada batas waktu mode
+    let session_valid = session.ordered(timestamp)
+        && session.duration_matches_ticks()
+        && mode.is_some_and(|mode| session.within_time_limit(&mode.rules()));
+
+    // Skor tidak boleh diraih lebih cepat dari yang diizinkan mode
+    let rate_valid = mode.is_some_and(|mode| {
+        let max_rate = MAX_SCORE_PER_MINUTE.min(mode.rules().max_score_per_minute);
+        session.score_rate_valid(score, max_rate)
+    });
     
     // Verifikasi game hash valid (harus 32 bytes)
     let hash_valid = game_hash_input.len() == 32;
     
     // Hasil verifikasi keseluruhan
-    let verified = (timestamp_valid && score_valid && hash_valid) as u32;
+    let verified = (timestamp_valid
+        && mode_valid
+        && score_valid
+        && checkpoints_valid
+        && session_valid
+        && rate_valid
+        && hash_valid) as u32;
+    println!("cycle-tracker-report-end: validate");
 
     // Debug output
     println!("Game Score Verification:");
     println!("Timestamp: {}", timestamp);
     println!("Player: [HASHED]");
     println!("Score: {}", score);
+    println!("Mode: {}", mode_id);
     println!("Timestamp Valid: {}", timestamp_valid);
+    println!("Mode Valid: {}", mode_valid);
     println!("Score Valid: {}", score_valid);
+    println!("Checkpoints: {}", checkpoints.len());
+    println!("Checkpoints Valid: {}", checkpoints_valid);
+    println!("Duration: {}s ({} ticks)", session.duration_secs(), session.ticks);
+    println!("Session Valid: {}", session_valid);
+    println!("Score Rate Valid: {}", rate_valid);
     println!("Hash Valid: {}", hash_valid);
     println!("Verification Result: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
     
     // Buat public values
     let public_values = GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: mode_id,
         timestamp,
         player_name_hash,
         score,
         game_hash,
         verified,
+        checkpoint_root: checkpoint::merkle_root(&checkpoints),
+        checkpoint_count: checkpoints.len() as u32,
+        duration_secs: u32::try_from(session.duration_secs()).unwrap_or(u32::MAX),
+        ticks: session.ticks,
     };
     
-    // Encode hasil untuk output - perhatikan perubahan di sini
-    let encoded = abi::encode(public_values);
+    // Encode hasil untuk output dengan layout ABI terbaru
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = game_lib::abi::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
     sp1_zkvm::io::commit_slice(&encoded);
 } 
\ No newline at end of file
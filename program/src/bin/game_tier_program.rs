@@ -0,0 +1,112 @@
+//! SP1 proof program proving a score reached a tier without revealing it
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use game_lib::checkpoint::{self, Checkpoint};
+use game_lib::session::Session;
+use game_lib::tier::{self, TierPublicValues, TIER_ABI_VERSION};
+use game_lib::{GameMode, RULES_VERSION};
+
+pub fn main() {
+    // Read input data, laid out as for game_score_program with the thresholds last
+    println!("cycle-tracker-report-start: read_input");
+    let timestamp = sp1_zkvm::io::read::<u64>();
+    let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Read player name as bytes
+    let score = sp1_zkvm::io::read::<u32>();
+    let game_hash_input = sp1_zkvm::io::read::<Vec<u8>>(); // Read game hash as bytes
+    let current_time = sp1_zkvm::io::read::<u64>(); // Current timestamp
+    let mode_id = sp1_zkvm::io::read::<u32>(); // Game mode id
+    let checkpoints = sp1_zkvm::io::read::<Vec<Checkpoint>>(); // Per-wave checkpoints
+    let session = sp1_zkvm::io::read::<Session>(); // Start, end and tick count of the game
+    let thresholds = sp1_zkvm::io::read::<Vec<u32>>(); // Public tier thresholds
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: hash_player_name");
+    // Hash player name for privacy
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    println!("cycle-tracker-report-end: hash_player_name");
+
+    println!("cycle-tracker-report-start: validate");
+    // Limits shared with the host
+    use game_lib::{MAX_SCORE, MAX_SCORE_PER_MINUTE, MAX_TIMESTAMP_SKEW_SECS};
+
+    // Verify timestamp (not more than 1 hour difference)
+    let timestamp_valid = current_time.abs_diff(timestamp) <= MAX_TIMESTAMP_SKEW_SECS;
+
+    // Verify mode is known and score follows its rules
+    let mode = GameMode::from_id(mode_id);
+    let mode_valid = mode.is_some();
+    let score_valid = score <= MAX_SCORE && mode.is_some_and(|mode| mode.rules().score_valid(score));
+
+    // Verify checkpoints are ordered and consistent with the final score
+    let checkpoints_valid = mode.is_some_and(|mode| {
+        checkpoint::sequence_valid(&checkpoints, score, &mode.rules())
+    });
+
+    // Verify the game ended before it was submitted, lasted as long as its
+    // ticks take and stopped at the mode's time limit
+    let session_valid = session.ordered(timestamp)
+        && session.duration_matches_ticks()
+        && mode.is_some_and(|mode| session.within_time_limit(&mode.rules()));
+
+    // Verify the score was not earned faster than the mode allows
+    let rate_valid = mode.is_some_and(|mode| {
+        let max_rate = MAX_SCORE_PER_MINUTE.min(mode.rules().max_score_per_minute);
+        session.score_rate_valid(score, max_rate)
+    });
+
+    // Verify game hash is valid (must be 32 bytes)
+    let hash_valid = game_hash_input.len() == 32;
+
+    // Thresholds must be strictly ascending for the tier to mean anything
+    let thresholds_valid = tier::check_thresholds(&thresholds).is_ok();
+
+    // Overall verification result
+    let verified = (timestamp_valid
+        && mode_valid
+        && score_valid
+        && checkpoints_valid
+        && session_valid
+        && rate_valid
+        && hash_valid
+        && thresholds_valid) as u32;
+    let tier = tier::tier_for(score, &thresholds);
+    println!("cycle-tracker-report-end: validate");
+
+    // The score is private, so only the checks and the tier are printed
+    println!("===== GAME SCORE TIER REPORT =====");
+    println!("Timestamp: {}", timestamp);
+    println!("Timestamp Valid: {}", timestamp_valid);
+    println!("Player: [HASHED]");
+    println!("Mode: {}", mode_id);
+    println!("Mode Valid: {}", mode_valid);
+    println!("Score: [PRIVATE]");
+    println!("Score Valid: {}", score_valid);
+    println!("Checkpoints Valid: {}", checkpoints_valid);
+    println!("Session Valid: {}", session_valid);
+    println!("Score Rate Valid: {}", rate_valid);
+    println!("Hash Valid: {}", hash_valid);
+    println!("Thresholds: {:?}", thresholds);
+    println!("Thresholds Valid: {}", thresholds_valid);
+    println!("Tier: {}", tier);
+    println!("Verification Status: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
+    println!("==================================");
+
+    // Create public values; score, game hash and checkpoints stay private
+    let public_values = TierPublicValues {
+        abi_version: TIER_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: mode_id,
+        timestamp,
+        player_name_hash,
+        thresholds,
+        tier,
+        verified,
+    };
+
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = tier::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
+    sp1_zkvm::io::commit_slice(&encoded);
+}
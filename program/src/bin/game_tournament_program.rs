@@ -0,0 +1,96 @@
+//! SP1 proof program verifying a tournament entry from its replay
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use game_lib::replay;
+use game_lib::tournament::{self, TournamentPublicValues, TournamentRules, TOURNAMENT_ABI_VERSION};
+use game_lib::RULES_VERSION;
+
+pub fn main() {
+    // Read input data
+    println!("cycle-tracker-report-start: read_input");
+    let rules = sp1_zkvm::io::read::<TournamentRules>(); // Tournament being entered
+    let timestamp = sp1_zkvm::io::read::<u64>();
+    let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Read player name as bytes
+    let current_time = sp1_zkvm::io::read::<u64>(); // Current timestamp
+    let chunk_count = sp1_zkvm::io::read::<u32>(); // Chunks of the encoded replay that follow
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: hash_inputs");
+    // Hash player name for privacy and the rules to identify the tournament
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    let tournament_id = rules.commitment();
+    println!("cycle-tracker-report-end: hash_inputs");
+
+    println!("cycle-tracker-report-start: simulate");
+    // The score comes from the simulation rather than the client. Each chunk
+    // of the replay is hashed and simulated as it is read, so a long game is
+    // never held in memory whole
+    let chunks = (0..chunk_count).map(|_| sp1_zkvm::io::read::<Vec<u8>>());
+    let (replay_hash, run) = replay::run_chunks(chunks, |_, _| {});
+    let replay_valid = run.is_ok();
+    let (mode, seed, score) = match &run {
+        Ok((header, sim)) => (Some(header.mode), header.seed, sim.score()),
+        Err(e) => {
+            println!("Replay Error: {}", e);
+            (None, 0, 0)
+        }
+    };
+    println!("cycle-tracker-report-end: simulate");
+
+    println!("cycle-tracker-report-start: validate");
+    // Limits shared with the host
+    use game_lib::{MAX_SCORE, MAX_TIMESTAMP_SKEW_SECS};
+
+    // Verify timestamp (not more than 1 hour difference)
+    let timestamp_valid = current_time.abs_diff(timestamp) <= MAX_TIMESTAMP_SKEW_SECS;
+
+    // The game must be played on the tournament's seed and mode, while it is running
+    let seed_valid = replay_valid && seed == rules.seed;
+    let mode_valid = mode == Some(rules.mode);
+    let window_valid = rules.in_window(timestamp);
+
+    // Verify score follows the rules of the mode
+    let score_valid = score <= MAX_SCORE && rules.mode.rules().score_valid(score);
+
+    let verified = (replay_valid
+        && timestamp_valid
+        && seed_valid
+        && mode_valid
+        && window_valid
+        && score_valid) as u32;
+    println!("cycle-tracker-report-end: validate");
+
+    println!("===== TOURNAMENT ENTRY REPORT =====");
+    println!("Tournament: {}", rules.id);
+    println!("Timestamp: {}", timestamp);
+    println!("Timestamp Valid: {}", timestamp_valid);
+    println!("Player: [HASHED]");
+    println!("Replay Valid: {}", replay_valid);
+    println!("Seed Valid: {}", seed_valid);
+    println!("Mode Valid: {}", mode_valid);
+    println!("Window Valid: {}", window_valid);
+    println!("Score: {}", score);
+    println!("Score Valid: {}", score_valid);
+    println!("Verification Status: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
+    println!("===================================");
+
+    let public_values = TournamentPublicValues {
+        abi_version: TOURNAMENT_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        tournament_id,
+        mode: mode.map_or(0, |mode| mode.id()),
+        seed,
+        timestamp,
+        player_name_hash,
+        score,
+        replay_hash,
+        verified,
+    };
+
+    println!("cycle-tracker-report-start: abi_encode");
+    let encoded = tournament::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
+    sp1_zkvm::io::commit_slice(&encoded);
+}
@@ -3,21 +3,27 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use game_lib::GameScorePublicValues;
+use game_lib::checkpoint::{self, Checkpoint};
+use game_lib::session::Session;
+use game_lib::{GameMode, GameScorePublicValues, ABI_VERSION, RULES_VERSION};
 
 pub fn main() {
     // Read input data
+    println!("cycle-tracker-report-start: read_input");
     let timestamp = sp1_zkvm::io::read::<u64>();
     let player_name = sp1_zkvm::io::read::<Vec<u8>>(); // Read player name as bytes
     let score = sp1_zkvm::io::read::<u32>();
     let game_hash_input = sp1_zkvm::io::read::<Vec<u8>>(); // Read game hash as bytes
+    let current_time = sp1_zkvm::io::read::<u64>(); // Current timestamp
+    let mode_id = sp1_zkvm::io::read::<u32>(); // Game mode id
+    let checkpoints = sp1_zkvm::io::read::<Vec<Checkpoint>>(); // Per-wave checkpoints
+    let session = sp1_zkvm::io::read::<Session>(); // Start, end and tick count of the game
+    println!("cycle-tracker-report-end: read_input");
 
-    // Hash player name for privacy using manual SHA-256 hash
-    // since sp1_zkvm::hash::sha256 is not available
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    hasher.update(&player_name);
-    let player_name_hash: [u8; 32] = hasher.finalize().into();
+    println!("cycle-tracker-report-start: hash_player_name");
+    // Hash player name for privacy
+    let player_name_hash = game_lib::player_name_hash(&player_name);
+    println!("cycle-tracker-report-end: hash_player_name");
     
     // Convert game_hash to [u8; 32]
     let mut game_hash = [0u8; 32];
@@ -25,11 +31,9 @@ pub fn main() {
         game_hash[i] = byte;
     }
     
-    // Constants
-    const MAX_SCORE: u32 = 10000; 
-    
-    // Verify score
-    let current_time = sp1_zkvm::io::read::<u64>(); // Current timestamp
+    println!("cycle-tracker-report-start: validate");
+    // Limits shared with the host
+    use game_lib::{MAX_SCORE, MAX_SCORE_PER_MINUTE, MAX_TIMESTAMP_SKEW_SECS};
     
     // Verify timestamp (not more than 1 hour difference)
     let time_diff = if current_time > timestamp {
@@ -38,16 +42,42 @@ pub fn main() {
         timestamp - current_time
     };
     
-    let timestamp_valid = time_diff <= 3600;
+    let timestamp_valid = time_diff <= MAX_TIMESTAMP_SKEW_SECS;
+    
+    // Verify mode is known and score follows its rules
+    let mode = GameMode::from_id(mode_id);
+    let mode_valid = mode.is_some();
+    let score_valid = score <= MAX_SCORE && mode.is_some_and(|mode| mode.rules().score_valid(score));
+    
+    // Verify checkpoints are ordered and consistent with the final score
+    let checkpoints_valid = mode.is_some_and(|mode| {
+        checkpoint::sequence_valid(&checkpoints, score, &mode.rules())
+    });
+    
+    // Verify the game ended before it was submitted, lasted as long as its
+    // ticks take and stopped at the mode's time limit
+    let session_valid = session.ordered(timestamp)
+        && session.duration_matches_ticks()
+        && mode.is_some_and(|mode| session.within_time_limit(&mode.rules()));
     
-    // Verify score doesn't exceed limit
-    let score_valid = score <= MAX_SCORE;
+    // Verify the score was not earned faster than the mode allows
+    let rate_valid = mode.is_some_and(|mode| {
+        let max_rate = MAX_SCORE_PER_MINUTE.min(mode.rules().max_score_per_minute);
+        session.score_rate_valid(score, max_rate)
+    });
     
     // Verify game hash is valid (must be 32 bytes)
     let hash_valid = game_hash_input.len() == 32;
     
     // Overall verification result
-    let verified = (timestamp_valid && score_valid && hash_valid) as u32;
+    let verified = (timestamp_valid
+        && mode_valid
+        && score_valid
+        && checkpoints_valid
+        && session_valid
+        && rate_valid
+        && hash_valid) as u32;
+    println!("cycle-tracker-report-end: validate");
     
     // Debug output with consistent formatting
     // (all output in one block to prevent stdout/stderr separation)
@@ -55,22 +85,38 @@ pub fn main() {
     println!("Timestamp: {}", timestamp);
     println!("Timestamp Valid: {}", timestamp_valid);
     println!("Player: [HASHED]");
+    println!("Mode: {}", mode_id);
+    println!("Mode Valid: {}", mode_valid);
     println!("Score: {}", score);
     println!("Score Valid: {}", score_valid);
+    println!("Checkpoints: {}", checkpoints.len());
+    println!("Checkpoints Valid: {}", checkpoints_valid);
+    println!("Duration: {}s ({} ticks)", session.duration_secs(), session.ticks);
+    println!("Session Valid: {}", session_valid);
+    println!("Score Rate Valid: {}", rate_valid);
     println!("Hash Valid: {}", hash_valid);
     println!("Verification Status: {}", if verified == 1 { "SUCCESS" } else { "FAILED" });
     println!("=========================================");
     
     // Create public values
     let public_values = GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: mode_id,
         timestamp,
         player_name_hash,
         score,
         game_hash,
         verified,
+        checkpoint_root: checkpoint::merkle_root(&checkpoints),
+        checkpoint_count: checkpoints.len() as u32,
+        duration_secs: u32::try_from(session.duration_secs()).unwrap_or(u32::MAX),
+        ticks: session.ticks,
     };
     
     // Encode results for output
+    println!("cycle-tracker-report-start: abi_encode");
     let encoded = game_lib::abi::encode(public_values);
+    println!("cycle-tracker-report-end: abi_encode");
     sp1_zkvm::io::commit_slice(&encoded);
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! Cycles of hashing a replay in the guest, before and after the precompiles.
+//!
+//! The hash benchmark guest hashes each replay with a plain Rust SHA-256,
+//! as the guests did before, and with the patched `sha2` that calls SP1's
+//! syscall. Hashing cost depends only on the replay's
+//! length, so the replays here are idle games of typical lengths.
+//!
+//! Run with `cargo bench --bench guest_hashing`, optionally followed by
+//! replay lengths in minutes (default 1 5 15 30).
+
+use game_lib::replay::Replay;
+use game_lib::sim::{Input, TICK_RATE};
+use game_lib::GameMode;
+use game_verification_script::report::ExecutionSummary;
+use game_verification_script::GAME_HASH_BENCH_ELF;
+use sp1_sdk::{ProverClient, SP1Stdin};
+
+const DEFAULT_MINUTES: [u64; 4] = [1, 5, 15, 30];
+
+fn main() {
+    let minutes: Vec<u64> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| arg.parse().expect("replay length in minutes"))
+        .collect();
+    let minutes = if minutes.is_empty() { DEFAULT_MINUTES.to_vec() } else { minutes };
+
+    let client = ProverClient::from_env();
+    println!(
+        "{:>8} {:>12} {:>16} {:>16} {:>8}",
+        "minutes", "replay bytes", "sha256 software", "sha256 syscall", "speedup"
+    );
+    for minutes in minutes {
+        let ticks = minutes * 60 * u64::from(TICK_RATE);
+        let mut replay = Replay::new(GameMode::Endless, 1);
+        replay.inputs = vec![Input::new(0, 0, 0); ticks as usize];
+        let replay = replay.encode();
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&replay);
+        let (_, report) =
+            client.execute(GAME_HASH_BENCH_ELF, &stdin).run().expect("guest executes");
+        let summary = ExecutionSummary::from(&report);
+        let section = |name: &str| summary.sections.get(name).copied().unwrap_or(0);
+        let software = section("sha256_software");
+        let precompile = section("sha256_precompile");
+        println!(
+            "{:>8} {:>12} {:>16} {:>16} {:>7.1}x",
+            minutes,
+            replay.len(),
+            software,
+            precompile,
+            software as f64 / precompile.max(1) as f64
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Cycles and memory of a replay guest as the replay grows.
+//!
+//! Each replay is an endless game played by a bot that aims at the nearest
+//! enemy, so it survives and the simulation runs for the whole replay. The
+//! replays are executed in the achievements guest, not proved, and the
+//! memory column counts the words the guest touched. With the replay
+//! streamed in chunks it should stay flat while cycles grow with the ticks.
+//!
+//! Run with `cargo bench --bench replay_stream`, optionally followed by
+//! replay lengths in minutes (default 1 5 15 30).
+
+use game_lib::replay::Replay;
+use game_lib::sim::{Input, Simulation, TICK_RATE};
+use game_lib::GameMode;
+use game_verification_script::achievements::achievements_stdin;
+use game_verification_script::GAME_ACHIEVEMENTS_ELF;
+use sp1_sdk::ProverClient;
+
+const DEFAULT_MINUTES: [u64; 4] = [1, 5, 15, 30];
+const SEED: u64 = 7;
+
+/// Replay of `ticks` ticks of the nearest-enemy bot
+fn bot_replay(ticks: u64) -> Replay {
+    let mut sim = Simulation::new(GameMode::Endless, SEED);
+    let mut replay = Replay::new(GameMode::Endless, SEED);
+    for _ in 0..ticks {
+        let player = *sim.player();
+        let distance = |x: f64, y: f64| (x - player.x).hypot(y - player.y);
+        let target = sim
+            .enemies()
+            .iter()
+            .min_by(|a, b| distance(a.x, a.y).total_cmp(&distance(b.x, b.y)))
+            .map_or((player.x, player.y), |enemy| (enemy.x, enemy.y));
+        let input = Input::new(0, target.0 as i16, target.1 as i16);
+        if !sim.is_over() {
+            sim.step(input);
+        }
+        replay.inputs.push(input);
+    }
+    replay
+}
+
+fn main() {
+    let minutes: Vec<u64> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| arg.parse().expect("replay length in minutes"))
+        .collect();
+    let minutes = if minutes.is_empty() { DEFAULT_MINUTES.to_vec() } else { minutes };
+
+    let client = ProverClient::from_env();
+    println!(
+        "{:>8} {:>8} {:>12} {:>14} {:>12} {:>14}",
+        "minutes", "ticks", "replay bytes", "cycles", "cycles/tick", "memory (KiB)"
+    );
+    for minutes in minutes {
+        let ticks = minutes * 60 * u64::from(TICK_RATE);
+        let replay = bot_replay(ticks).encode();
+        let stdin = achievements_stdin("bench", &replay);
+        let (_, report) =
+            client.execute(GAME_ACHIEVEMENTS_ELF, &stdin).run().expect("guest executes");
+        let cycles = report.total_instruction_count();
+        println!(
+            "{:>8} {:>8} {:>12} {:>14} {:>12} {:>14}",
+            minutes,
+            ticks,
+            replay.len(),
+            cycles,
+            cycles / ticks,
+            report.touched_memory_addresses * 4 / 1024
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Host support for achievement proofs, which replay a game in the guest and
+//! commit the achievements it earned.
+//!
+//! Like tier and rank proofs, achievement proofs commit their own layout and
+//! are written to standalone files instead of the proof store.
+
+use std::convert::Infallible;
+
+use game_lib::achievements::{self, AchievementPublicValues};
+use game_lib::replay::Replay;
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::progress::{run_stage, ProgressObserver, Stage};
+use crate::{write_replay, VerifyError, GAME_ACHIEVEMENTS_ELF};
+
+/// Decode a replay as the guest will, so a bad file is rejected before proving
+pub fn check_replay(replay: &[u8]) -> Result<Replay, VerifyError> {
+    Replay::decode(replay).map_err(|e| VerifyError::Replay(e.to_string()))
+}
+
+/// Guest input for an achievement proof of `player`'s encoded replay
+pub fn achievements_stdin(player: &str, replay: &[u8]) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&player.as_bytes().to_vec());
+    write_replay(&mut stdin, replay);
+    stdin
+}
+
+/// Decode the public values of an achievement proof
+pub fn decode_achievement_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<AchievementPublicValues, VerifyError> {
+    achievements::decode(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::Verification(format!("invalid achievement public values: {}", e)))
+}
+
+/// A generated achievement proof with its decoded public values
+pub struct AchievementProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub values: AchievementPublicValues,
+}
+
+/// Prover with the achievements program keys set up, reusable across replays
+pub struct AchievementProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+}
+
+impl AchievementProver {
+    /// Create the SP1 client and generate the achievements program keys
+    pub fn new(observer: &dyn ProgressObserver) -> Self {
+        let client = ProverClient::from_env();
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_ACHIEVEMENTS_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Verify a proof against the achievements program verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client.verify(proof, &self.vk).map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Run the achievements guest without proving, returning its public values
+    pub fn execute(
+        &self,
+        player: &str,
+        replay: &[u8],
+    ) -> Result<AchievementPublicValues, VerifyError> {
+        let stdin = achievements_stdin(player, replay);
+        let (public_values, _) = self
+            .client
+            .execute(GAME_ACHIEVEMENTS_ELF, &stdin)
+            .run()
+            .map_err(|e| VerifyError::Prover(e.to_string()))?;
+        achievements::decode(public_values.as_slice())
+            .map_err(|e| VerifyError::Prover(format!("invalid achievement public values: {}", e)))
+    }
+
+    /// Check, prove and verify the achievements earned in one replay
+    pub fn prove(
+        &self,
+        player: &str,
+        replay: &[u8],
+        observer: &dyn ProgressObserver,
+    ) -> Result<AchievementProof, VerifyError> {
+        check_replay(replay)?;
+        let stdin = achievements_stdin(player, replay);
+
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (_, report) = self
+                .client
+                .execute(GAME_ACHIEVEMENTS_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            let cycles = report.total_instruction_count();
+            Ok((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        let values = decode_achievement_values(&proof)?;
+        Ok(AchievementProof { proof, values })
+    }
+}
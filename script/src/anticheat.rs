@@ -0,0 +1,335 @@
+//! Heuristic anti-cheat signals computed from the game client's event log.
+//!
+//! The web game records events through `addGameEvent` as
+//! `{"type": ..., "timestamp": <ms>, ...}` objects. None of the signals here
+//! proves cheating on its own; each is scored by how far it is past its
+//! threshold and the scores are combined into a single suspicion value kept
+//! alongside the proof.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Layout version of [`AnticheatReport`]
+pub const REPORT_VERSION: u32 = 1;
+
+/// Position on the game canvas, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    fn distance(&self, other: &Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// One entry of the client event log; timestamps are Unix milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    #[serde(rename = "enemy_killed")]
+    EnemyKilled {
+        timestamp: u64,
+        /// Where the enemy was
+        position: Point,
+        /// Score after the kill
+        score: u32,
+        /// Where the player was; missing from logs of older clients
+        #[serde(default)]
+        player: Option<Point>,
+    },
+    #[serde(rename = "player_hit")]
+    PlayerHit { timestamp: u64, lives: u32 },
+    #[serde(rename = "shoot")]
+    Shoot { timestamp: u64, position: Point, angle: f64 },
+    #[serde(rename = "newWave")]
+    NewWave { timestamp: u64, wave: u32 },
+    /// Any event type the analyzer does not look at
+    #[serde(other)]
+    Other,
+}
+
+impl GameEvent {
+    pub fn timestamp(&self) -> Option<u64> {
+        match self {
+            GameEvent::EnemyKilled { timestamp, .. }
+            | GameEvent::PlayerHit { timestamp, .. }
+            | GameEvent::Shoot { timestamp, .. }
+            | GameEvent::NewWave { timestamp, .. } => Some(*timestamp),
+            GameEvent::Other => None,
+        }
+    }
+}
+
+/// Event log as a bare array or as the client's `gameData` object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EventLog {
+    Events(Vec<GameEvent>),
+    GameData { events: Vec<GameEvent> },
+}
+
+/// Parse an event log exported by the web client
+pub fn parse_events(json: &str) -> Result<Vec<GameEvent>, serde_json::Error> {
+    Ok(match serde_json::from_str(json)? {
+        EventLog::Events(events) | EventLog::GameData { events } => events,
+    })
+}
+
+/// Limits past which a signal counts against the player
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Thresholds {
+    /// Average kills per second over the whole game
+    pub max_kill_rate: f64,
+    /// Kills inside any one-second window
+    pub max_kills_per_window: usize,
+    /// Aim turns at least this large, in radians, count as flicks...
+    pub flick_angle: f64,
+    /// ...when made within this many milliseconds of the previous shot
+    pub flick_window_ms: u64,
+    /// Fraction of consecutive shots that may be flicks
+    pub max_flick_ratio: f64,
+    /// Shot intervals with a lower coefficient of variation look scripted
+    pub min_shot_interval_cv: f64,
+    /// Fewest shot intervals needed to judge their regularity
+    pub min_shot_intervals: usize,
+    /// Furthest an enemy can be from the player when the blade kills it
+    pub max_kill_distance: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            max_kill_rate: 2.0,
+            max_kills_per_window: 6,
+            flick_angle: PI / 2.0,
+            flick_window_ms: 50,
+            max_flick_ratio: 0.1,
+            min_shot_interval_cv: 0.05,
+            min_shot_intervals: 10,
+            // Blade length 40 plus enemy radius 20 and blade width 6, with some slack
+            // for the kill being logged a frame late
+            max_kill_distance: 80.0,
+        }
+    }
+}
+
+/// One computed signal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Signal {
+    pub name: String,
+    /// Measured value, or `None` if the log had too little data
+    pub value: Option<f64>,
+    pub threshold: f64,
+    /// 0 within the threshold, rising to 1 as the value gets further past it
+    pub severity: f64,
+    pub weight: f64,
+}
+
+impl Signal {
+    pub fn triggered(&self) -> bool {
+        self.severity > 0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Clean,
+    Suspicious,
+    LikelyCheating,
+}
+
+impl Verdict {
+    fn from_suspicion(suspicion: f64) -> Self {
+        if suspicion < 0.2 {
+            Verdict::Clean
+        } else if suspicion < 0.5 {
+            Verdict::Suspicious
+        } else {
+            Verdict::LikelyCheating
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Clean => "clean",
+            Verdict::Suspicious => "suspicious",
+            Verdict::LikelyCheating => "likely_cheating",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Scored anti-cheat report for one submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnticheatReport {
+    pub version: u32,
+    pub event_count: usize,
+    pub duration_secs: f64,
+    pub signals: Vec<Signal>,
+    /// Weighted mean severity of the signals that could be computed, from 0 to 1
+    pub suspicion: f64,
+    pub verdict: Verdict,
+}
+
+/// Severity of `value` exceeding `max`, reaching 1 at twice the limit
+fn above(value: f64, max: f64) -> f64 {
+    if value <= max {
+        0.0
+    } else if max <= 0.0 {
+        1.0
+    } else {
+        ((value - max) / max).min(1.0)
+    }
+}
+
+/// Severity of `value` falling below `min`, reaching 1 at zero
+fn below(value: f64, min: f64) -> f64 {
+    if value >= min {
+        0.0
+    } else {
+        ((min - value) / min).min(1.0)
+    }
+}
+
+/// Smallest difference between two angles, in `[0, PI]`
+fn angle_delta(a: f64, b: f64) -> f64 {
+    let delta = (a - b).rem_euclid(2.0 * PI);
+    delta.min(2.0 * PI - delta)
+}
+
+/// Compute every signal for `events`
+pub fn analyze(events: &[GameEvent], thresholds: &Thresholds) -> AnticheatReport {
+    let timestamps: Vec<u64> = events.iter().filter_map(GameEvent::timestamp).collect();
+    let duration_ms = match (timestamps.iter().min(), timestamps.iter().max()) {
+        (Some(first), Some(last)) => last - first,
+        _ => 0,
+    };
+    // Very short logs are measured as lasting one second
+    let duration_secs = (duration_ms as f64 / 1000.0).max(1.0);
+
+    let mut kill_times = Vec::new();
+    let mut kill_distances = Vec::new();
+    let mut shots = Vec::new();
+    for event in events {
+        match event {
+            GameEvent::EnemyKilled { timestamp, position, player, .. } => {
+                kill_times.push(*timestamp);
+                if let Some(player) = player {
+                    kill_distances.push(position.distance(player));
+                }
+            }
+            GameEvent::Shoot { timestamp, angle, .. } => shots.push((*timestamp, *angle)),
+            _ => {}
+        }
+    }
+    kill_times.sort_unstable();
+    shots.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut signals = Vec::new();
+
+    let kill_rate = kill_times.len() as f64 / duration_secs;
+    signals.push(Signal {
+        name: "kill_rate".to_string(),
+        value: Some(kill_rate),
+        threshold: thresholds.max_kill_rate,
+        severity: above(kill_rate, thresholds.max_kill_rate),
+        weight: 1.0,
+    });
+
+    // Kills in each one-second window opening at a kill; the window's end
+    // only moves forward, and measuring from its opening kill cannot overflow
+    let mut peak_kills = 0;
+    let mut end = 0;
+    for (start, first) in kill_times.iter().enumerate() {
+        while end < kill_times.len() && kill_times[end] - first < 1000 {
+            end += 1;
+        }
+        peak_kills = peak_kills.max(end - start);
+    }
+    let max_window = thresholds.max_kills_per_window as f64;
+    signals.push(Signal {
+        name: "peak_kills_per_second".to_string(),
+        value: Some(peak_kills as f64),
+        threshold: max_window,
+        severity: above(peak_kills as f64, max_window),
+        weight: 1.0,
+    });
+
+    let flick_ratio = (shots.len() >= 2).then(|| {
+        let flicks = shots
+            .windows(2)
+            .filter(|pair| {
+                let ((t0, a0), (t1, a1)) = (pair[0], pair[1]);
+                t1 - t0 <= thresholds.flick_window_ms
+                    && angle_delta(a0, a1) >= thresholds.flick_angle
+            })
+            .count();
+        flicks as f64 / (shots.len() - 1) as f64
+    });
+    signals.push(Signal {
+        name: "aim_flick_ratio".to_string(),
+        value: flick_ratio,
+        threshold: thresholds.max_flick_ratio,
+        severity: flick_ratio.map_or(0.0, |ratio| above(ratio, thresholds.max_flick_ratio)),
+        weight: 1.0,
+    });
+
+    let intervals: Vec<f64> = shots.windows(2).map(|pair| (pair[1].0 - pair[0].0) as f64).collect();
+    let interval_cv = (intervals.len() >= thresholds.min_shot_intervals).then(|| {
+        let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+        let variance =
+            intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / intervals.len() as f64;
+        if mean > 0.0 {
+            variance.sqrt() / mean
+        } else {
+            0.0
+        }
+    });
+    signals.push(Signal {
+        name: "shot_interval_cv".to_string(),
+        value: interval_cv,
+        threshold: thresholds.min_shot_interval_cv,
+        severity: interval_cv.map_or(0.0, |cv| below(cv, thresholds.min_shot_interval_cv)),
+        weight: 1.0,
+    });
+
+    // A single impossible kill is enough to be worth a look
+    let far_kills = kill_distances.iter().filter(|d| **d > thresholds.max_kill_distance).count();
+    let far_ratio = (!kill_distances.is_empty())
+        .then(|| far_kills as f64 / kill_distances.len() as f64);
+    signals.push(Signal {
+        name: "far_kill_ratio".to_string(),
+        value: far_ratio,
+        threshold: 0.0,
+        severity: far_ratio.map_or(0.0, |ratio| if ratio > 0.0 { ratio.max(0.5) } else { 0.0 }),
+        weight: 2.0,
+    });
+
+    let measured = signals.iter().filter(|signal| signal.value.is_some());
+    let total_weight: f64 = measured.clone().map(|signal| signal.weight).sum();
+    let suspicion = if total_weight > 0.0 {
+        measured.map(|signal| signal.weight * signal.severity).sum::<f64>() / total_weight
+    } else {
+        0.0
+    };
+
+    AnticheatReport {
+        version: REPORT_VERSION,
+        event_count: events.len(),
+        duration_secs,
+        signals,
+        suspicion,
+        verdict: Verdict::from_suspicion(suspicion),
+    }
+}
@@ -0,0 +1,208 @@
+//! Proving a file of submissions with a single key setup.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::progress::ProgressObserver;
+use crate::store::ProofStore;
+use crate::{current_unix_time, ScoreProver, Submission};
+
+/// Failure reason for lines that are not valid submissions
+pub const INVALID_SUBMISSION: &str = "invalid_submission";
+
+/// One non-empty line of a JSONL submission file
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    /// 1-based line number in the input file
+    pub line: usize,
+    pub submission: Result<Submission, String>,
+    /// Time this line's timestamp is checked against, if the line gives one
+    pub reference_time: Option<u64>,
+}
+
+/// A submission line, optionally with its own reference time
+#[derive(Deserialize)]
+struct BatchLine {
+    #[serde(default)]
+    reference_time: Option<u64>,
+    #[serde(flatten)]
+    submission: Submission,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Number of submissions proved concurrently
+    pub parallelism: usize,
+    /// Time to check timestamps against for lines without their own
+    /// `reference_time`; defaults to the time each proof starts
+    pub reference_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineStatus {
+    Ok,
+    Failed,
+}
+
+/// Outcome of proving one line, written as one line of the result file
+#[derive(Debug, Clone, Serialize)]
+pub struct LineResult {
+    pub line: usize,
+    pub status: LineStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_path: Option<String>,
+    /// Anti-cheat suspicion, for submissions with an event log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspicion: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl LineResult {
+    fn failed(line: usize, reason: &str, error: String) -> Self {
+        Self {
+            line,
+            status: LineStatus::Failed,
+            proof_hash: None,
+            proof_path: None,
+            suspicion: None,
+            reason: Some(reason.to_string()),
+            error: Some(error),
+        }
+    }
+}
+
+/// Totals over a whole batch
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub failures_by_reason: BTreeMap<String, usize>,
+}
+
+impl BatchSummary {
+    pub fn from_results(results: &[LineResult]) -> Self {
+        let mut summary = Self {
+            total: results.len(),
+            ..Self::default()
+        };
+        for result in results {
+            match result.status {
+                LineStatus::Ok => summary.succeeded += 1,
+                LineStatus::Failed => {
+                    summary.failed += 1;
+                    let reason = result.reason.clone().unwrap_or_default();
+                    *summary.failures_by_reason.entry(reason).or_default() += 1;
+                }
+            }
+        }
+        summary
+    }
+}
+
+/// Read a JSONL submission file, skipping blank lines
+pub fn read_submissions(path: &Path) -> io::Result<Vec<BatchEntry>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let parsed = serde_json::from_str::<BatchLine>(line).map_err(|e| e.to_string());
+            BatchEntry {
+                line: index + 1,
+                reference_time: parsed
+                    .as_ref()
+                    .ok()
+                    .and_then(|parsed| parsed.reference_time),
+                submission: parsed.map(|parsed| parsed.submission),
+            }
+        })
+        .collect())
+}
+
+/// Prove every entry, returning results in input order
+pub fn run_batch(
+    prover: &ScoreProver,
+    store: &ProofStore,
+    entries: &[BatchEntry],
+    options: &BatchOptions,
+    observer: &(dyn ProgressObserver + Sync),
+) -> Vec<LineResult> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; entries.len()]);
+    let workers = options.parallelism.clamp(1, entries.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(entry) = entries.get(index) else {
+                    break;
+                };
+                let result = prove_entry(prover, store, entry, options, observer);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every entry is proved"))
+        .collect()
+}
+
+fn prove_entry(
+    prover: &ScoreProver,
+    store: &ProofStore,
+    entry: &BatchEntry,
+    options: &BatchOptions,
+    observer: &dyn ProgressObserver,
+) -> LineResult {
+    let submission = match &entry.submission {
+        Ok(submission) => submission,
+        Err(e) => return LineResult::failed(entry.line, INVALID_SUBMISSION, e.clone()),
+    };
+
+    let current_time = entry
+        .reference_time
+        .or(options.reference_time)
+        .unwrap_or_else(current_unix_time);
+
+    match prover.prove(submission, current_time, store, observer) {
+        Ok(result) => LineResult {
+            line: entry.line,
+            status: LineStatus::Ok,
+            proof_hash: Some(result.proof_hash),
+            proof_path: Some(result.proof_path),
+            suspicion: result.anticheat.map(|report| report.suspicion),
+            reason: None,
+            error: None,
+        },
+        Err(e) => LineResult::failed(entry.line, e.kind(), e.to_string()),
+    }
+}
+
+/// Write results as JSONL, one line per input line
+pub fn write_results(path: &Path, results: &[LineResult]) -> io::Result<()> {
+    let mut out = String::new();
+    for result in results {
+        out.push_str(&serde_json::to_string(result)?);
+        out.push('\n');
+    }
+    fs::write(path, out)
+}
@@ -0,0 +1,330 @@
+//! Challenges against accepted scores.
+//!
+//! A challenger names a stored proof. The [`Adjudicator`] verifies the proof
+//! again, re-runs the game's stored replay through the simulation, and
+//! upholds the score only if the replay ends in the committed score, tick
+//! count and checkpoint root. A proof made under older rules that the current
+//! simulation no longer reproduces, or stored without a replay, is left
+//! inconclusive rather than overturned. Every verdict is signed with the
+//! adjudicator's secp256k1 key so it can be published and checked by anyone
+//! holding the public key.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use game_lib::checkpoint::merkle_root;
+use game_lib::replay::Replay;
+use game_lib::{GameMode, GameScorePublicValues, RULES_VERSION};
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::store::{ProofStore, StoreError};
+use crate::{check_submission, ScoreProver};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeOutcome {
+    /// The score stands
+    Upheld,
+    /// The score is removed from the leaderboards
+    Overturned,
+    /// The proof holds but the game cannot be re-run: it was made under
+    /// older rules or stored without a replay; the score stands
+    Inconclusive,
+}
+
+impl ChallengeOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChallengeOutcome::Upheld => "upheld",
+            ChallengeOutcome::Overturned => "overturned",
+            ChallengeOutcome::Inconclusive => "inconclusive",
+        }
+    }
+}
+
+impl fmt::Display for ChallengeOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request to re-examine a stored proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeRequest {
+    pub proof_hash: String,
+    pub challenger: String,
+    /// Why the challenger thinks the score is wrong
+    pub reason: String,
+}
+
+/// The signed part of a verdict
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerdictBody {
+    pub proof_hash: String,
+    pub challenger: String,
+    pub reason: String,
+    pub outcome: ChallengeOutcome,
+    /// Problems found while re-examining the proof; empty if upheld
+    pub findings: Vec<String>,
+    /// Rules the game was re-run under
+    pub rules_version: u32,
+    /// Unix time the verdict was reached
+    pub decided_at: u64,
+}
+
+impl VerdictBody {
+    /// Bytes the signature is made over
+    fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("verdict body serializes")
+    }
+}
+
+/// A verdict with the adjudicator's signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedVerdict {
+    #[serde(flatten)]
+    pub body: VerdictBody,
+    /// Hex SEC1-compressed public key of the adjudicator
+    pub signer: String,
+    /// Hex ECDSA signature over the JSON encoding of the body
+    pub signature: String,
+}
+
+impl SignedVerdict {
+    fn sign(body: VerdictBody, key: &SigningKey) -> Self {
+        let signature: Signature = key.sign(&body.signing_bytes());
+        Self { signer: public_key_hex(key), signature: hex::encode(signature.to_bytes()), body }
+    }
+
+    /// Check the signature against the embedded signer key
+    ///
+    /// Callers should also check `signer` is a key they trust.
+    pub fn verify(&self) -> Result<(), ChallengeError> {
+        let invalid = |e: &dyn fmt::Display| ChallengeError::Signature(e.to_string());
+        let signer = hex::decode(&self.signer).map_err(|e| invalid(&e))?;
+        let signer = VerifyingKey::from_sec1_bytes(&signer).map_err(|e| invalid(&e))?;
+        let signature = hex::decode(&self.signature).map_err(|e| invalid(&e))?;
+        let signature = Signature::from_slice(&signature).map_err(|e| invalid(&e))?;
+        signer.verify(&self.body.signing_bytes(), &signature).map_err(|e| invalid(&e))
+    }
+}
+
+#[derive(Debug)]
+pub enum ChallengeError {
+    Store(StoreError),
+    /// The proof was stored without its submission, so it cannot be replayed
+    NoSubmission(String),
+    /// Signing key could not be read or created
+    Key(String),
+    /// Verdict signature is malformed or does not match
+    Signature(String),
+}
+
+impl fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChallengeError::Store(e) => write!(f, "{}", e),
+            ChallengeError::NoSubmission(hash) => {
+                write!(f, "no submission stored for proof {}; it cannot be replayed", hash)
+            }
+            ChallengeError::Key(reason) => write!(f, "signing key unavailable: {}", reason),
+            ChallengeError::Signature(reason) => write!(f, "invalid verdict signature: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ChallengeError {}
+
+impl From<StoreError> for ChallengeError {
+    fn from(e: StoreError) -> Self {
+        ChallengeError::Store(e)
+    }
+}
+
+/// Hex SEC1-compressed public key of `key`
+pub fn public_key_hex(key: &SigningKey) -> String {
+    hex::encode(VerifyingKey::from(key).to_encoded_point(true).as_bytes())
+}
+
+/// Read the hex signing key at `path`, generating and saving one if it does not exist
+///
+/// A new key is written to a temporary file readable only by its owner and
+/// then linked into place, so no reader sees a partial key and a key another
+/// process saved first is loaded rather than replaced.
+pub fn load_or_create_signing_key(path: &Path) -> Result<SigningKey, ChallengeError> {
+    let key_error =
+        |e: &dyn fmt::Display| ChallengeError::Key(format!("{}: {}", path.display(), e));
+    match fs::read_to_string(path) {
+        Ok(contents) => {
+            let bytes = hex::decode(contents.trim()).map_err(|e| key_error(&e))?;
+            SigningKey::from_slice(&bytes).map_err(|e| key_error(&e))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let key = SigningKey::random(&mut rand::rngs::OsRng);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| key_error(&e))?;
+            }
+            let mut temp = path.as_os_str().to_owned();
+            temp.push(format!(".{:016x}.tmp", rand::random::<u64>()));
+            let temp = PathBuf::from(temp);
+            write_private(&temp, hex::encode(key.to_bytes()).as_bytes())
+                .map_err(|e| key_error(&e))?;
+            let linked = fs::hard_link(&temp, path);
+            let _ = fs::remove_file(&temp);
+            match linked {
+                Ok(()) => Ok(key),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    load_or_create_signing_key(path)
+                }
+                Err(e) => Err(key_error(&e)),
+            }
+        }
+        Err(e) => Err(key_error(&e)),
+    }
+}
+
+/// Create `path`, which must not exist, with permissions for its owner only
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Ways the replay fails to reproduce the committed game
+fn rerun_findings(replay: &[u8], committed: &GameScorePublicValues) -> Vec<String> {
+    let replay = match Replay::decode(replay) {
+        Ok(replay) => replay,
+        Err(e) => return vec![format!("stored replay is invalid: {}", e)],
+    };
+    if GameMode::from_id(committed.mode) != Some(replay.mode) {
+        return vec![format!(
+            "replay is of {}, not the committed mode {}",
+            replay.mode, committed.mode
+        )];
+    }
+
+    let sim = replay.run();
+    let mut findings = Vec::new();
+    if sim.score() != committed.score {
+        findings.push(format!(
+            "replay scores {}, not the committed {}",
+            sim.score(),
+            committed.score
+        ));
+    }
+    if sim.tick() != committed.ticks {
+        findings.push(format!(
+            "replay runs {} ticks, not the committed {}",
+            sim.tick(),
+            committed.ticks
+        ));
+    }
+    let checkpoints = sim.checkpoints();
+    if merkle_root(checkpoints) != committed.checkpoint_root
+        || checkpoints.len() != committed.checkpoint_count as usize
+    {
+        findings.push("replay checkpoints do not match the committed root".to_string());
+    }
+    findings
+}
+
+/// Re-examines challenged proofs and signs the verdicts
+///
+/// Borrows its prover, so a caller that already set up the proving keys
+/// shares them instead of running key generation again.
+///
+/// Submissions are judged by the prover's own policy.
+pub struct Adjudicator<'a> {
+    prover: &'a ScoreProver,
+    key: SigningKey,
+}
+
+impl<'a> Adjudicator<'a> {
+    pub fn new(prover: &'a ScoreProver, key: SigningKey) -> Self {
+        Self { prover, key }
+    }
+
+    /// Hex public key verdicts are signed with
+    pub fn public_key(&self) -> String {
+        public_key_hex(&self.key)
+    }
+
+    /// Decide a challenge and record the verdict in `store`
+    pub fn challenge(
+        &self,
+        store: &ProofStore,
+        request: &ChallengeRequest,
+        now: u64,
+    ) -> Result<SignedVerdict, ChallengeError> {
+        let hash = request.proof_hash.as_str();
+        let proof = store.load(hash)?;
+        let stored = store
+            .submission(hash)?
+            .ok_or_else(|| ChallengeError::NoSubmission(hash.to_string()))?;
+
+        // Problems with the proof itself overturn it under any rules
+        let mut findings = Vec::new();
+        if let Err(e) = self.prover.verify(&proof) {
+            findings.push(e.to_string());
+        }
+
+        let committed = game_lib::abi::decode(proof.public_values.as_slice())
+            .map_err(|e| StoreError::PublicValues(e.to_string()))?;
+        if committed.verified != 1 {
+            findings.push("guest rejected the score when it was proved".to_string());
+        }
+
+        // The rest judges the game by the current rules
+        let policy = self.prover.policy();
+        let mut replay_findings = Vec::new();
+        if let Err(e) = check_submission(&stored.submission, stored.reference_time, policy) {
+            replay_findings.push(format!("submission fails current rules: {}", e));
+        }
+        let replay = store.replay(hash)?;
+        if let Some(replay) = &replay {
+            replay_findings.extend(rerun_findings(replay, &committed));
+        }
+
+        let outcome = if !findings.is_empty() {
+            ChallengeOutcome::Overturned
+        } else if replay.is_none() {
+            replay_findings.push(
+                "no replay was stored with the proof, so the game cannot be re-run".to_string(),
+            );
+            ChallengeOutcome::Inconclusive
+        } else if replay_findings.is_empty() {
+            ChallengeOutcome::Upheld
+        } else if committed.rules_version < RULES_VERSION {
+            // The simulation may have changed since, so a mismatch proves nothing
+            replay_findings.push(format!(
+                "proved under rules version {}, which the current simulation cannot reproduce",
+                committed.rules_version
+            ));
+            ChallengeOutcome::Inconclusive
+        } else {
+            ChallengeOutcome::Overturned
+        };
+        findings.append(&mut replay_findings);
+
+        let body = VerdictBody {
+            proof_hash: request.proof_hash.clone(),
+            challenger: request.challenger.clone(),
+            reason: request.reason.clone(),
+            outcome,
+            findings,
+            rules_version: RULES_VERSION,
+            decided_at: now,
+        };
+        let verdict = SignedVerdict::sign(body, &self.key);
+        store.add_verdict(&verdict)?;
+        Ok(verdict)
+    }
+}
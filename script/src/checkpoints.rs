@@ -0,0 +1,84 @@
+//! Inclusion proofs for single checkpoints of a proved game.
+//!
+//! A [`CheckpointClaim`] shows that a game reached a given wave with a given
+//! score and lives, checked against the checkpoint root committed in the
+//! proof's public values rather than by re-running the game.
+
+use std::fmt;
+
+use game_lib::checkpoint::{merkle_root, Checkpoint, InclusionProof};
+use game_lib::GameScorePublicValues;
+use serde::{Deserialize, Serialize};
+
+/// A checkpoint together with the path proving it is under a committed root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointClaim {
+    pub checkpoint: Checkpoint,
+    pub inclusion: InclusionProof,
+    /// Hex checkpoint root the claim was made against
+    pub checkpoint_root: String,
+}
+
+#[derive(Debug)]
+pub enum ClaimError {
+    /// No checkpoint was recorded for the wave
+    NoCheckpoint { wave: u32 },
+    /// The checkpoints given do not hash to the committed root
+    RootMismatch { committed: String, computed: String },
+    /// The inclusion path does not lead from the checkpoint to the committed root
+    InvalidInclusion,
+}
+
+impl fmt::Display for ClaimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClaimError::NoCheckpoint { wave } => write!(f, "no checkpoint for wave {}", wave),
+            ClaimError::RootMismatch { committed, computed } => {
+                write!(f, "checkpoints hash to {} but the proof commits to {}", computed, committed)
+            }
+            ClaimError::InvalidInclusion => {
+                write!(f, "checkpoint is not included under the committed root")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClaimError {}
+
+/// Build the claim for `wave` from the full checkpoint list of a proved game
+pub fn claim(
+    checkpoints: &[Checkpoint],
+    values: &GameScorePublicValues,
+    wave: u32,
+) -> Result<CheckpointClaim, ClaimError> {
+    let committed = hex::encode(values.checkpoint_root);
+    let computed = hex::encode(merkle_root(checkpoints));
+    if computed != committed || checkpoints.len() != values.checkpoint_count as usize {
+        return Err(ClaimError::RootMismatch { committed, computed });
+    }
+
+    let index = checkpoints
+        .iter()
+        .position(|checkpoint| checkpoint.wave == wave)
+        .ok_or(ClaimError::NoCheckpoint { wave })?;
+    let inclusion =
+        InclusionProof::new(checkpoints, index).ok_or(ClaimError::NoCheckpoint { wave })?;
+
+    Ok(CheckpointClaim { checkpoint: checkpoints[index], inclusion, checkpoint_root: committed })
+}
+
+/// Check a claim against the public values of the proof it refers to
+///
+/// The proof itself must be verified separately.
+pub fn verify_claim(
+    claim: &CheckpointClaim,
+    values: &GameScorePublicValues,
+) -> Result<(), ClaimError> {
+    let included = claim.inclusion.leaf_count == values.checkpoint_count as usize
+        && claim.inclusion.verify(&claim.checkpoint, &values.checkpoint_root);
+    if included {
+        Ok(())
+    } else {
+        Err(ClaimError::InvalidInclusion)
+    }
+}
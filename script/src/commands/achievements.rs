@@ -0,0 +1,113 @@
+//! `achievements`: prove the achievements a replayed game earned.
+
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use game_lib::achievements::{Achievement, AchievementPublicValues};
+use game_lib::GameMode;
+use game_verification_script::achievements::{
+    check_replay, decode_achievement_values, AchievementProver,
+};
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::store::{read_proof, write_proof};
+
+use super::{exit_code, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum AchievementsCommand {
+    /// Replay a game and prove the achievements it earned
+    Prove {
+        /// Player name
+        #[arg(long)]
+        player: String,
+
+        /// Encoded replay recorded by the client
+        #[arg(long)]
+        replay: PathBuf,
+
+        /// File to write the achievement proof to
+        #[arg(long, default_value = "achievements_proof.bin")]
+        out: PathBuf,
+    },
+    /// Verify an achievement proof file and print the achievements it shows
+    Verify {
+        /// Achievement proof file written by `achievements prove`
+        proof: PathBuf,
+
+        /// Require the proof to be for this player
+        #[arg(long)]
+        player: Option<String>,
+
+        /// Achievements the proof must show, comma separated (flawless, frenzy, survivor)
+        #[arg(long, value_delimiter = ',')]
+        require: Vec<Achievement>,
+    },
+}
+
+fn print_achievement_values(values: &AchievementPublicValues) {
+    println!("ABI Version: {}", values.abi_version);
+    println!("Rules Version: {}", values.rules_version);
+    match GameMode::from_id(values.mode) {
+        Some(mode) => println!("Mode: {}", mode),
+        None => println!("Mode: unknown ({})", values.mode),
+    }
+    println!("Player Hash: {}", hex::encode(values.player_name_hash));
+    println!("Replay Hash: {}", hex::encode(values.replay_hash));
+    let earned = values.earned();
+    if earned.is_empty() {
+        println!("Achievements: none");
+    }
+    for achievement in earned {
+        println!("Achievement: {} ({})", achievement, achievement.description());
+    }
+    println!("Verified: {}", values.verified == 1);
+}
+
+pub fn run(command: AchievementsCommand) -> Result<(), Failure> {
+    match command {
+        AchievementsCommand::Prove { player, replay, out } => {
+            let replay = std::fs::read(&replay).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", replay.display(), e))
+            })?;
+
+            println!("=== GAME ACHIEVEMENTS PROOF ===");
+            // Reject undecodable replays before the expensive key setup
+            check_replay(&replay)?;
+
+            let prover = AchievementProver::new(&ConsoleObserver);
+            let proof = prover.prove(&player, &replay, &ConsoleObserver)?;
+            write_proof(&out, &proof.proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+
+            print_achievement_values(&proof.values);
+            println!("Achievement proof saved to: {}", out.display());
+        }
+        AchievementsCommand::Verify { proof, player, require } => {
+            let proof = read_proof(&proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+            let values = decode_achievement_values(&proof)?;
+
+            let prover = AchievementProver::new(&ConsoleObserver);
+            prover.verify(&proof)?;
+            print_achievement_values(&values);
+
+            let missing = Achievement::mask(&require) & !values.achievements;
+            let problem = if values.verified != 1 {
+                Some("proof is valid but the guest rejected the replay".to_string())
+            } else if player.is_some_and(|player| {
+                game_lib::player_name_hash(player.as_bytes()) != values.player_name_hash
+            }) {
+                Some("proof is for a different player".to_string())
+            } else if missing != 0 {
+                let missing: Vec<String> =
+                    Achievement::in_mask(missing).iter().map(ToString::to_string).collect();
+                Some(format!("proof does not show {}", missing.join(", ")))
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                return Err(Failure::new(exit_code::REJECTED, problem));
+            }
+            println!("ACHIEVEMENTS_VERIFIED=true");
+        }
+    }
+    Ok(())
+}
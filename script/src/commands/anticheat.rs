@@ -0,0 +1,75 @@
+//! `anticheat`: analyse client event logs for signs of cheating.
+
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use game_verification_script::anticheat::{self, AnticheatReport, Thresholds};
+
+use super::{exit_code, open_store, read_events, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum AnticheatCommand {
+    /// Compute the anti-cheat report for an event log
+    Analyze {
+        /// JSON event log exported by the web client
+        events: PathBuf,
+
+        /// Write the report as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+    /// Show the report stored with a proof
+    Show { proof_hash: String },
+}
+
+fn print_anticheat_report(report: &AnticheatReport) {
+    println!("===== ANTI-CHEAT REPORT =====");
+    println!("Events: {}", report.event_count);
+    println!("Duration: {:.1}s", report.duration_secs);
+    for signal in &report.signals {
+        let value = match signal.value {
+            Some(value) => format!("{:.3}", value),
+            None => "n/a".to_string(),
+        };
+        let flag = if signal.triggered() { "  <- triggered" } else { "" };
+        println!("{:<24} {:>10} (threshold {}){}", signal.name, value, signal.threshold, flag);
+    }
+    println!("Suspicion: {:.2}", report.suspicion);
+    println!("Verdict: {}", report.verdict);
+    println!("=============================");
+}
+
+pub fn run(store_path: &Path, command: AnticheatCommand) -> Result<(), Failure> {
+    match command {
+        AnticheatCommand::Analyze { events, json } => {
+            let report = anticheat::analyze(&read_events(&events)?, &Thresholds::default());
+            print_anticheat_report(&report);
+
+            if let Some(path) = json {
+                let json = serde_json::to_string_pretty(&report)
+                    .expect("Failed to serialize anti-cheat report");
+                std::fs::write(&path, json).map_err(|e| {
+                    Failure::new(exit_code::IO, format!("failed to write report: {}", e))
+                })?;
+                println!("Anti-cheat report saved to: {}", path.display());
+            }
+        }
+        AnticheatCommand::Show { proof_hash } => {
+            let store = open_store(store_path)?;
+            if store.get(&proof_hash).is_none() {
+                return Err(Failure::new(
+                    exit_code::USAGE,
+                    format!("no proof with hash {}", proof_hash),
+                ));
+            }
+            let report = store
+                .anticheat(&proof_hash)
+                .map_err(|e| Failure::new(exit_code::IO, e))?
+                .ok_or_else(|| {
+                    Failure::new(exit_code::USAGE, "no anti-cheat report stored for this proof")
+                })?;
+            print_anticheat_report(&report);
+        }
+    }
+    Ok(())
+}
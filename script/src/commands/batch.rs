@@ -0,0 +1,59 @@
+//! `batch`: prove every submission in a JSONL file.
+
+use std::path::Path;
+
+use game_verification_script::batch::{
+    read_submissions, run_batch, write_results, BatchOptions, BatchSummary,
+};
+use game_verification_script::config::Config;
+use game_verification_script::progress::NoopObserver;
+use game_verification_script::ScoreProver;
+
+use super::{exit_code, open_store, Failure};
+
+pub fn run(
+    config: &Config,
+    input: &Path,
+    options: &BatchOptions,
+    results_path: &Path,
+    summary_path: &Path,
+) -> Result<(), Failure> {
+    let entries = read_submissions(input).map_err(|e| {
+        Failure::new(exit_code::IO, format!("failed to read {}: {}", input.display(), e))
+    })?;
+    println!("Loaded {} submissions from {}", entries.len(), input.display());
+
+    let store = open_store(&config.store_dir.value)?;
+    println!("Setting up SP1 program...");
+    let prover = ScoreProver::new(config.policy(), &NoopObserver);
+
+    println!("Proving with parallelism {}...", options.parallelism);
+    let results = run_batch(&prover, &store, &entries, options, &NoopObserver);
+
+    write_results(results_path, &results)
+        .map_err(|e| Failure::new(exit_code::IO, format!("failed to write results: {}", e)))?;
+
+    let summary = BatchSummary::from_results(&results);
+    let summary_json = serde_json::to_string_pretty(&summary).expect("Failed to serialize summary");
+    std::fs::write(summary_path, summary_json)
+        .map_err(|e| Failure::new(exit_code::IO, format!("failed to write summary: {}", e)))?;
+
+    println!("===== BATCH SUMMARY =====");
+    println!("Total: {}", summary.total);
+    println!("Succeeded: {}", summary.succeeded);
+    println!("Failed: {}", summary.failed);
+    for (reason, count) in &summary.failures_by_reason {
+        println!("  {}: {}", reason, count);
+    }
+    println!("Results saved to: {}", results_path.display());
+    println!("Summary saved to: {}", summary_path.display());
+    println!("=========================");
+
+    if summary.failed > 0 {
+        return Err(Failure::new(
+            exit_code::REJECTED,
+            format!("{} of {} submissions failed", summary.failed, summary.total),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! `challenge`: challenge stored scores and check verdicts.
+
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use game_verification_script::challenge::{
+    load_or_create_signing_key, public_key_hex, Adjudicator, ChallengeRequest, SignedVerdict,
+};
+use game_verification_script::config::Config;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::{current_unix_time, ScoreProver};
+
+use super::{exit_code, open_store, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum ChallengeCommand {
+    /// Re-run a stored game against its proof and record a signed verdict
+    Open {
+        proof_hash: String,
+
+        /// Who is challenging the score
+        #[arg(long)]
+        challenger: String,
+
+        /// Why the score is being challenged
+        #[arg(long, default_value = "")]
+        reason: String,
+
+        /// Hex secp256k1 key to sign verdicts with, created if missing
+        /// (defaults to <cache-dir>/verdict_signing.key)
+        #[arg(long)]
+        signing_key: Option<PathBuf>,
+    },
+    /// Show the verdicts recorded for a proof
+    Show { proof_hash: String },
+    /// Check the signature of a verdict file
+    Verify {
+        /// Verdict JSON as printed by `challenge open`
+        verdict: PathBuf,
+
+        /// Require the verdict to be signed by this hex public key
+        #[arg(long)]
+        signer: Option<String>,
+    },
+    /// Print the public key verdicts are signed with
+    Key {
+        #[arg(long)]
+        signing_key: Option<PathBuf>,
+    },
+}
+
+fn print_verdict(verdict: &SignedVerdict) {
+    let body = &verdict.body;
+    println!("Proof: {}", body.proof_hash);
+    println!("Challenger: {}", body.challenger);
+    if !body.reason.is_empty() {
+        println!("Reason: {}", body.reason);
+    }
+    println!("Decided At: {}", body.decided_at);
+    println!("Rules Version: {}", body.rules_version);
+    for finding in &body.findings {
+        println!("Finding: {}", finding);
+    }
+    println!("Signer: {}", verdict.signer);
+    println!("CHALLENGE_OUTCOME={}", body.outcome);
+}
+
+pub fn run(config: &Config, command: ChallengeCommand) -> Result<(), Failure> {
+    let store_path = config.store_dir.value.as_path();
+    let key_path = |path: Option<PathBuf>| {
+        path.unwrap_or_else(|| config.cache_dir.value.join("verdict_signing.key"))
+    };
+
+    match command {
+        ChallengeCommand::Open { proof_hash, challenger, reason, signing_key } => {
+            let store = open_store(store_path)?;
+            if store.get(&proof_hash).is_none() {
+                return Err(Failure::new(
+                    exit_code::USAGE,
+                    format!("no proof with hash {}", proof_hash),
+                ));
+            }
+            let key = load_or_create_signing_key(&key_path(signing_key))?;
+
+            let prover = ScoreProver::new(config.policy(), &ConsoleObserver);
+            let adjudicator = Adjudicator::new(&prover, key);
+            let request = ChallengeRequest { proof_hash, challenger, reason };
+            let verdict = adjudicator.challenge(&store, &request, current_unix_time())?;
+
+            println!("{}", serde_json::to_string(&verdict).expect("Failed to serialize verdict"));
+            print_verdict(&verdict);
+        }
+        ChallengeCommand::Show { proof_hash } => {
+            let store = open_store(store_path)?;
+            let verdicts =
+                store.verdicts(&proof_hash).map_err(|e| Failure::new(exit_code::IO, e))?;
+            if verdicts.is_empty() {
+                println!("No challenges recorded for {}", proof_hash);
+            }
+            for verdict in &verdicts {
+                print_verdict(verdict);
+                println!();
+            }
+        }
+        ChallengeCommand::Verify { verdict, signer } => {
+            let contents = std::fs::read_to_string(&verdict).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", verdict.display(), e))
+            })?;
+            let verdict: SignedVerdict = serde_json::from_str(contents.trim())
+                .map_err(|e| Failure::new(exit_code::USAGE, format!("invalid verdict: {}", e)))?;
+            verdict.verify()?;
+            if signer.is_some_and(|signer| !signer.eq_ignore_ascii_case(&verdict.signer)) {
+                return Err(Failure::new(
+                    exit_code::REJECTED,
+                    "verdict is not signed by the expected key",
+                ));
+            }
+            print_verdict(&verdict);
+            println!("SIGNATURE_VALID=true");
+        }
+        ChallengeCommand::Key { signing_key } => {
+            let key = load_or_create_signing_key(&key_path(signing_key))?;
+            println!("{}", public_key_hex(&key));
+        }
+    }
+    Ok(())
+}
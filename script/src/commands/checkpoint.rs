@@ -0,0 +1,114 @@
+//! `checkpoint`: prove or check that a game passed through a checkpoint.
+
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use game_verification_script::checkpoints::{self, CheckpointClaim};
+use game_verification_script::config::Config;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::ScoreProver;
+
+use super::{decode_public_values, exit_code, load_proof, open_store, read_checkpoints, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum CheckpointCommand {
+    /// Write an inclusion proof for the checkpoint of one wave
+    Prove {
+        /// Proof hash in the store, or path to a proof file
+        proof: String,
+
+        /// Wave whose checkpoint to prove
+        #[arg(long)]
+        wave: u32,
+
+        /// JSON file with the game's checkpoints (defaults to those kept in the store)
+        #[arg(long)]
+        checkpoints: Option<PathBuf>,
+
+        /// Write the claim to this file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Verify a proof and check a checkpoint claim against its public values
+    Verify {
+        /// Proof hash in the store, or path to a proof file
+        proof: String,
+
+        /// Claim file written by `checkpoint prove`
+        claim: PathBuf,
+    },
+}
+
+pub fn run(config: &Config, command: CheckpointCommand) -> Result<(), Failure> {
+    let store_path = config.store_dir.value.as_path();
+
+    match command {
+        CheckpointCommand::Prove { proof, wave, checkpoints, out } => {
+            let (proof, entry) = load_proof(store_path, &proof)?;
+            let values = decode_public_values(&proof)?;
+
+            let checkpoints = match (checkpoints, entry) {
+                (Some(path), _) => read_checkpoints(&path)?,
+                (None, Some(entry)) => open_store(store_path)?
+                    .checkpoints(&entry.proof_hash)
+                    .map_err(|e| Failure::new(exit_code::IO, e))?
+                    .ok_or_else(|| {
+                        Failure::new(
+                            exit_code::USAGE,
+                            "no checkpoints stored for this proof; pass --checkpoints",
+                        )
+                    })?,
+                (None, None) => {
+                    return Err(Failure::new(
+                        exit_code::USAGE,
+                        "--checkpoints is required for proof files",
+                    ))
+                }
+            };
+
+            let claim = checkpoints::claim(&checkpoints, &values, wave)
+                .map_err(|e| Failure::new(exit_code::REJECTED, e))?;
+            let json =
+                serde_json::to_string_pretty(&claim).expect("Failed to serialize checkpoint claim");
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, json).map_err(|e| {
+                        Failure::new(exit_code::IO, format!("failed to write claim: {}", e))
+                    })?;
+                    println!("Checkpoint claim saved to: {}", path.display());
+                }
+                None => println!("{}", json),
+            }
+        }
+        CheckpointCommand::Verify { proof, claim } => {
+            let (proof, _) = load_proof(store_path, &proof)?;
+            let values = decode_public_values(&proof)?;
+
+            let contents = std::fs::read_to_string(&claim).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", claim.display(), e))
+            })?;
+            let claim: CheckpointClaim = serde_json::from_str(&contents).map_err(|e| {
+                Failure::new(exit_code::USAGE, format!("invalid checkpoint claim: {}", e))
+            })?;
+
+            let prover = ScoreProver::new(config.policy(), &ConsoleObserver);
+            prover.verify(&proof)?;
+            if values.verified != 1 {
+                return Err(Failure::new(
+                    exit_code::REJECTED,
+                    "proof is valid but the guest rejected the score",
+                ));
+            }
+            checkpoints::verify_claim(&claim, &values)
+                .map_err(|e| Failure::new(exit_code::REJECTED, e))?;
+
+            let checkpoint = &claim.checkpoint;
+            println!(
+                "Wave {} reached at tick {} with score {} and {} lives",
+                checkpoint.wave, checkpoint.tick, checkpoint.score, checkpoint.lives
+            );
+            println!("CHECKPOINT_VERIFIED=true");
+        }
+    }
+    Ok(())
+}
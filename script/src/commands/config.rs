@@ -0,0 +1,20 @@
+//! `config`: show the effective configuration.
+
+use clap::Subcommand;
+use game_verification_script::config::Config;
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print every effective setting and where it came from
+    Show,
+}
+
+pub fn run(config: &Config) {
+    match &config.file {
+        Some(path) => println!("# config file: {}", path.display()),
+        None => println!("# config file: none"),
+    }
+    for (key, value, source) in config.rows() {
+        println!("{} = {}  # {}", key, value, source);
+    }
+}
@@ -0,0 +1,110 @@
+//! `coop`: prove or verify a two-player co-op game.
+
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use game_lib::coop::CoopPublicValues;
+use game_lib::GameMode;
+use game_verification_script::config::Config;
+use game_verification_script::coop::{
+    check_coop_submission, decode_coop_values, CoopProver, CoopSubmission,
+};
+use game_verification_script::current_unix_time;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::store::{read_proof, write_proof};
+
+use super::{exit_code, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum CoopCommand {
+    /// Replay a co-op game and prove both players' scores
+    Prove {
+        /// Player on the first input stream of the replay
+        #[arg(long)]
+        player: String,
+
+        /// Player on the second input stream of the replay
+        #[arg(long)]
+        partner: String,
+
+        /// Encoded co-op replay recorded by the client
+        #[arg(long)]
+        replay: PathBuf,
+
+        /// Game timestamp (0 uses the current time)
+        #[arg(long, default_value = "0")]
+        timestamp: u64,
+
+        /// Unix time to check the timestamp against instead of the current time
+        #[arg(long)]
+        reference_time: Option<u64>,
+
+        /// File to write the co-op proof to
+        #[arg(long, default_value = "coop_proof.bin")]
+        out: PathBuf,
+    },
+    /// Verify a co-op proof file and print both players' scores
+    Verify {
+        /// Co-op proof file written by `coop prove`
+        proof: PathBuf,
+    },
+}
+
+fn print_coop_values(values: &CoopPublicValues) {
+    println!("ABI Version: {}", values.abi_version);
+    println!("Rules Version: {}", values.rules_version);
+    match GameMode::from_id(values.mode) {
+        Some(mode) => println!("Mode: {}", mode),
+        None => println!("Mode: unknown ({})", values.mode),
+    }
+    println!("Timestamp: {}", values.timestamp);
+    println!("Replay Hash: {}", hex::encode(values.replay_hash));
+    for (index, (hash, score)) in values.player_name_hashes.iter().zip(values.scores).enumerate() {
+        println!("Player {} Hash: {}", index + 1, hex::encode(hash));
+        println!("Player {} Score: {}", index + 1, score);
+    }
+    println!("Team Score: {}", values.team_score());
+    println!("Wave: {}", values.wave);
+    println!("Verified: {}", values.verified == 1);
+}
+
+pub fn run(config: &Config, command: CoopCommand) -> Result<(), Failure> {
+    match command {
+        CoopCommand::Prove { player, partner, replay, timestamp, reference_time, out } => {
+            let replay = std::fs::read(&replay).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", replay.display(), e))
+            })?;
+            let current_time = reference_time.unwrap_or_else(current_unix_time);
+            let timestamp = if timestamp == 0 { current_unix_time() } else { timestamp };
+            let submission = CoopSubmission { timestamp, players: [player, partner], replay };
+            let policy = config.policy();
+
+            println!("=== CO-OP GAME PROOF ===");
+            // Reject obviously invalid games before the expensive key setup
+            check_coop_submission(&submission, current_time, &policy)?;
+
+            let prover = CoopProver::new(policy, &ConsoleObserver);
+            let proof = prover.prove(&submission, current_time, &ConsoleObserver)?;
+            write_proof(&out, &proof.proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+
+            print_coop_values(&proof.values);
+            println!("Co-op proof saved to: {}", out.display());
+        }
+        CoopCommand::Verify { proof } => {
+            let proof = read_proof(&proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+            let values = decode_coop_values(&proof)?;
+
+            let prover = CoopProver::new(config.policy(), &ConsoleObserver);
+            prover.verify(&proof)?;
+            print_coop_values(&values);
+            if values.verified != 1 {
+                return Err(Failure::new(
+                    exit_code::REJECTED,
+                    "proof is valid but the guest rejected the game",
+                ));
+            }
+            println!("COOP_VERIFIED=true");
+        }
+    }
+    Ok(())
+}
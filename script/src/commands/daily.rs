@@ -0,0 +1,140 @@
+//! `daily`: play the daily challenge, whose seed is derived from the UTC date.
+
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use game_lib::GameMode;
+use game_verification_script::config::Config;
+use game_verification_script::current_unix_time;
+use game_verification_script::daily::{
+    check_daily_entry, DailyBoard, DailyChallenge, DailyEntry, DailyProver, DAILY_DIR,
+};
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::store::write_proof;
+
+use super::{exit_code, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum DailyCommand {
+    /// Print the seed and time window of a day's challenge
+    Seed {
+        /// UTC date as YYYY-MM-DD (default: today)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Print the challenge as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Prove a daily challenge game and record it on that day's board
+    Submit {
+        /// Player name
+        #[arg(long)]
+        player: String,
+
+        /// Encoded replay recorded by the client
+        #[arg(long)]
+        replay: PathBuf,
+
+        /// Game timestamp, which picks the day (0 uses the current time)
+        #[arg(long, default_value = "0")]
+        timestamp: u64,
+
+        /// Unix time to check the timestamp against instead of the current time
+        #[arg(long)]
+        reference_time: Option<u64>,
+    },
+    /// Print a day's board, best first
+    Board {
+        /// UTC date as YYYY-MM-DD (default: today)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Game mode (endless, time-attack or hardcore)
+        #[arg(long, default_value = "endless")]
+        mode: GameMode,
+
+        /// Number of rows to show
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// Print the board as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run(config: &Config, store_path: &Path, command: DailyCommand) -> Result<(), Failure> {
+    let dir = store_path.join(DAILY_DIR);
+    let challenge_of = |date: Option<String>| match date {
+        Some(date) => DailyChallenge::for_date(&date),
+        None => Ok(DailyChallenge::today()),
+    };
+    match command {
+        DailyCommand::Seed { date, json } => {
+            let challenge = challenge_of(date)?;
+            if json {
+                let json = serde_json::to_string_pretty(&challenge)
+                    .expect("Failed to serialize daily challenge");
+                println!("{}", json);
+                return Ok(());
+            }
+            println!("Date: {}", challenge.date);
+            println!("Seed: {}", challenge.seed);
+            println!("Domain: {}", challenge.domain);
+            println!("Window: {}..{}", challenge.starts_at, challenge.ends_at);
+        }
+        DailyCommand::Submit { player, replay, timestamp, reference_time } => {
+            let replay = std::fs::read(&replay).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", replay.display(), e))
+            })?;
+            let current_time = reference_time.unwrap_or_else(current_unix_time);
+            let timestamp = if timestamp == 0 { current_unix_time() } else { timestamp };
+            let challenge = DailyChallenge::at(timestamp);
+            let entry = DailyEntry { timestamp, player, replay };
+            let policy = config.policy();
+
+            println!("=== DAILY CHALLENGE PROOF ===");
+            println!("Date: {}", challenge.date);
+            // Reject games on another day's seed before the expensive key setup
+            check_daily_entry(&challenge, &entry, current_time, &policy)?;
+
+            let prover = DailyProver::new(policy, &ConsoleObserver);
+            let proof = prover.prove(&challenge, &entry, current_time, &ConsoleObserver)?;
+            let mut board = DailyBoard::load(&dir, challenge)?;
+            let proof_dir = board.proof_dir(&dir);
+            let out = proof_dir.join(format!("{}.bin", hex::encode(proof.values.replay_hash)));
+            std::fs::create_dir_all(&proof_dir)
+                .map_err(|e| Failure::new(exit_code::IO, e))
+                .and_then(|_| {
+                    write_proof(&out, &proof.proof).map_err(|e| Failure::new(exit_code::IO, e))
+                })?;
+
+            let result = board.record(&entry.player, &proof.values, &out)?;
+            println!("Player: {}", result.player);
+            println!("Mode: {}", result.mode);
+            println!("Score: {}", result.score);
+            println!("Proof saved to: {}", result.proof_path);
+            board.save(&dir)?;
+        }
+        DailyCommand::Board { date, mode, limit, json } => {
+            let board = DailyBoard::load(&dir, challenge_of(date)?)?;
+            let standings = board.standings(mode, Some(limit));
+            if json {
+                let json = serde_json::to_string_pretty(&standings)
+                    .expect("Failed to serialize daily board");
+                println!("{}", json);
+                return Ok(());
+            }
+            let challenge = &board.challenge;
+            println!("{} daily board ({}, seed {})", challenge.date, mode, challenge.seed);
+            for row in &standings {
+                println!(
+                    "{:>3}. score={:<6} timestamp={} player={} attempts={}",
+                    row.rank, row.score, row.timestamp, row.player, row.attempts
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! `execute`: run the score guest without proving and check cycle budgets.
+
+use std::path::Path;
+
+use game_verification_script::report::{CycleBudget, ExecutionOutcome, ExecutionSummary};
+use game_verification_script::{guest_stdin, GAME_SCORE_ELF};
+use sp1_sdk::ProverClient;
+
+use super::{exit_code, Failure, SubmissionArgs};
+
+pub fn run(
+    args: &SubmissionArgs,
+    json: Option<&Path>,
+    budget: &CycleBudget,
+) -> Result<(), Failure> {
+    let stdin = guest_stdin(&args.submission()?, args.reference_time())?;
+
+    let client = ProverClient::from_env();
+    let (public_values, report) = client.execute(GAME_SCORE_ELF, &stdin).run().map_err(|e| {
+        Failure::new(exit_code::PROVER, format!("failed to execute program: {}", e))
+    })?;
+    println!("Program executed successfully.");
+
+    let outcome = ExecutionOutcome::new(ExecutionSummary::from(&report), budget)
+        .map_err(|e| Failure::new(exit_code::USAGE, e))?;
+
+    println!("===== GAME SCORE VERIFICATION REPORT =====");
+    println!("Public Values: {:?}", public_values);
+    println!("Total Cycles: {}", outcome.summary.total_cycles);
+    println!("Total Syscalls: {}", outcome.summary.total_syscalls);
+    for (syscall, count) in &outcome.summary.syscalls {
+        println!("  {}: {}", syscall, count);
+    }
+    println!("Sections:");
+    for (section, cycles) in &outcome.summary.sections {
+        println!("  {}: {} cycles", section, cycles);
+    }
+    println!("=========================================");
+
+    if let Some(path) = json {
+        let json =
+            serde_json::to_string_pretty(&outcome).expect("Failed to serialize execution report");
+        std::fs::write(path, json).map_err(|e| {
+            Failure::new(exit_code::IO, format!("failed to write execution report: {}", e))
+        })?;
+        println!("Execution report saved to: {}", path.display());
+    }
+
+    if !outcome.within_budget {
+        let violations: Vec<String> = outcome.violations.iter().map(|v| v.to_string()).collect();
+        return Err(Failure::new(
+            exit_code::BUDGET,
+            format!("cycle budget exceeded: {}", violations.join("; ")),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//! `golden`: write the golden test vectors.
+
+use std::path::Path;
+
+use game_verification_script::golden;
+
+use super::{exit_code, Failure};
+
+pub fn run(out: &Path) -> Result<(), Failure> {
+    let vectors = golden::generate();
+    let json = serde_json::to_string_pretty(&vectors).expect("Failed to serialize golden vectors");
+    std::fs::write(out, json + "\n").map_err(|e| {
+        Failure::new(exit_code::IO, format!("failed to write {}: {}", out.display(), e))
+    })?;
+    println!(
+        "Wrote {} game hash, {} player hash, {} ABI, {} checkpoint, {} daily seed, {} fixed-point \
+         and {} replay vectors to {}",
+        vectors.game_hash.len(),
+        vectors.player_name_hash.len(),
+        vectors.abi.len(),
+        vectors.checkpoint_root.len(),
+        vectors.daily_seed.len(),
+        vectors.fixed.len(),
+        vectors.replay.len(),
+        out.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! `inspect`: decode the public values of a stored proof or proof file.
+
+use std::path::Path;
+
+use super::{decode_public_values, load_proof, print_public_values, Failure};
+
+pub fn run(store_path: &Path, reference: &str) -> Result<(), Failure> {
+    let (proof, entry) = load_proof(store_path, reference)?;
+    let values = decode_public_values(&proof)?;
+
+    print_public_values(&values);
+    if let Some(entry) = entry {
+        println!("Proof Hash: {}", entry.proof_hash);
+        println!("Stored At: {}", entry.stored_at);
+        println!("Verification Key: {}", entry.vkey);
+    }
+    println!("SP1 Version: {}", proof.sp1_version);
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! `leaderboard`: show the verified leaderboard for a game mode.
+
+use std::path::Path;
+
+use game_lib::GameMode;
+use game_verification_script::leaderboard;
+
+use super::{open_store, Failure};
+
+pub fn run(store_path: &Path, mode: GameMode, limit: usize, json: bool) -> Result<(), Failure> {
+    let store = open_store(store_path)?;
+    let rows = leaderboard::board(&store.list(), mode, Some(limit));
+
+    if json {
+        let json = serde_json::to_string_pretty(&rows).expect("Failed to serialize leaderboard");
+        println!("{}", json);
+        return Ok(());
+    }
+    println!("{} leaderboard", mode);
+    for row in &rows {
+        let challenge = match row.challenge {
+            Some(outcome) => format!(" ({})", outcome),
+            None => String::new(),
+        };
+        println!(
+            "{:>3}. score={:<6} timestamp={} player={} proof={}{}",
+            row.rank, row.score, row.timestamp, row.player_name_hash, row.proof_hash, challenge
+        );
+    }
+    Ok(())
+}
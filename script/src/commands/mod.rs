@@ -0,0 +1,315 @@
+//! Handlers for each `bladewarrior` subcommand, with the pieces they share.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use game_lib::checkpoint::Checkpoint;
+use game_lib::session::Session;
+use game_lib::GameMode;
+use game_verification_script::anticheat::{parse_events, GameEvent};
+use game_verification_script::challenge::ChallengeError;
+use game_verification_script::daily::DailyError;
+use game_verification_script::rank::RankError;
+use game_verification_script::store::{read_proof, ProofStore, StoreEntry};
+use game_verification_script::tournament::TournamentError;
+use game_verification_script::{current_unix_time, Submission, VerifyError};
+use sp1_sdk::SP1ProofWithPublicValues;
+
+pub mod achievements;
+pub mod anticheat;
+pub mod batch;
+pub mod challenge;
+pub mod checkpoint;
+pub mod config;
+pub mod coop;
+pub mod daily;
+pub mod execute;
+pub mod golden;
+pub mod inspect;
+pub mod leaderboard;
+pub mod prove;
+pub mod rank;
+pub mod replay;
+pub mod store;
+pub mod tier;
+pub mod tournament;
+pub mod verify;
+pub mod vkey;
+
+/// Process exit codes shared by every subcommand
+pub mod exit_code {
+    /// The score or proof was rejected
+    pub const REJECTED: i32 = 1;
+    /// Invalid arguments or input files (clap also uses 2 for usage errors)
+    pub const USAGE: i32 = 2;
+    /// Reading or writing files or the proof store failed
+    pub const IO: i32 = 3;
+    /// Execution went over a cycle budget
+    pub const BUDGET: i32 = 4;
+    /// SP1 failed to execute or prove the guest
+    pub const PROVER: i32 = 5;
+}
+
+/// A score submission given on the command line
+#[derive(Args, Debug)]
+pub struct SubmissionArgs {
+    /// Game timestamp (0 uses the current time)
+    #[arg(long, default_value = "0")]
+    timestamp: u64,
+
+    /// Player name
+    #[arg(long, default_value = "TestPlayer")]
+    player: String,
+
+    /// Game score
+    #[arg(long, default_value = "0")]
+    score: u32,
+
+    /// Game data hash
+    #[arg(
+        long,
+        default_value = "0000000000000000000000000000000000000000000000000000000000000000"
+    )]
+    game_hash: String,
+
+    /// Game mode (endless, time-attack or hardcore)
+    #[arg(long, default_value = "endless")]
+    mode: GameMode,
+
+    /// JSON file with the game's per-wave checkpoints
+    #[arg(long)]
+    checkpoints: Option<PathBuf>,
+
+    /// JSON event log exported by the web client, for anti-cheat analysis
+    #[arg(long)]
+    events: Option<PathBuf>,
+
+    /// Encoded replay recorded by the client, kept for challenges
+    #[arg(long)]
+    replay: Option<PathBuf>,
+
+    /// Unix time the game started (defaults to when it ended)
+    #[arg(long)]
+    started_at: Option<u64>,
+
+    /// Unix time the game ended (defaults to the timestamp)
+    #[arg(long)]
+    ended_at: Option<u64>,
+
+    /// Ticks the game's simulation ran
+    #[arg(long, default_value = "0")]
+    ticks: u64,
+
+    /// Unix time to check the timestamp against instead of the current time
+    #[arg(long)]
+    reference_time: Option<u64>,
+}
+
+impl SubmissionArgs {
+    pub fn submission(&self) -> Result<Submission, Failure> {
+        let timestamp = if self.timestamp == 0 { current_unix_time() } else { self.timestamp };
+        let checkpoints = match &self.checkpoints {
+            Some(path) => read_checkpoints(path)?,
+            None => Vec::new(),
+        };
+        let events = match &self.events {
+            Some(path) => read_events(path)?,
+            None => Vec::new(),
+        };
+        let replay = match &self.replay {
+            Some(path) => std::fs::read(path).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", path.display(), e))
+            })?,
+            None => Vec::new(),
+        };
+        let ended_at = self.ended_at.unwrap_or(timestamp);
+        let session = Session {
+            started_at: self.started_at.unwrap_or(ended_at),
+            ended_at,
+            ticks: self.ticks,
+        };
+        Ok(Submission {
+            timestamp,
+            player: self.player.clone(),
+            score: self.score,
+            game_hash: self.game_hash.clone(),
+            mode: self.mode,
+            checkpoints,
+            session,
+            events,
+            replay,
+        })
+    }
+
+    pub fn reference_time(&self) -> u64 {
+        self.reference_time.unwrap_or_else(current_unix_time)
+    }
+}
+
+/// Error carrying the exit code it should end the process with
+#[derive(Debug)]
+pub struct Failure {
+    pub code: i32,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(code: i32, message: impl fmt::Display) -> Self {
+        Self { code, message: message.to_string() }
+    }
+}
+
+impl From<ChallengeError> for Failure {
+    fn from(e: ChallengeError) -> Self {
+        let code = match e {
+            ChallengeError::Store(_) | ChallengeError::Key(_) => exit_code::IO,
+            ChallengeError::NoSubmission(_) => exit_code::USAGE,
+            ChallengeError::Signature(_) => exit_code::REJECTED,
+        };
+        Failure::new(code, e)
+    }
+}
+
+impl From<RankError> for Failure {
+    fn from(e: RankError) -> Self {
+        match e {
+            RankError::Verify(e) => e.into(),
+            RankError::NotOnBoard | RankError::BelowTopK { .. } => {
+                Failure::new(exit_code::REJECTED, e)
+            }
+            RankError::Board(_) | RankError::RootMismatch { .. } | RankError::Entry(_) => {
+                Failure::new(exit_code::USAGE, e)
+            }
+        }
+    }
+}
+
+impl From<TournamentError> for Failure {
+    fn from(e: TournamentError) -> Self {
+        let code = match e {
+            TournamentError::Verify(e) => return e.into(),
+            TournamentError::Io(_) => exit_code::IO,
+            TournamentError::NotAllowed(_)
+            | TournamentError::NotRegistered(_)
+            | TournamentError::Closed
+            | TournamentError::WrongTournament
+            | TournamentError::WrongPlayer
+            | TournamentError::AlreadyRecorded(_)
+            | TournamentError::Rejected => exit_code::REJECTED,
+            TournamentError::InvalidId(_)
+            | TournamentError::InvalidWindow { .. }
+            | TournamentError::Exists(_)
+            | TournamentError::NotFound(_)
+            | TournamentError::AlreadyRegistered(_) => exit_code::USAGE,
+        };
+        Failure::new(code, e)
+    }
+}
+
+impl From<DailyError> for Failure {
+    fn from(e: DailyError) -> Self {
+        let code = match e {
+            DailyError::Verify(e) => return e.into(),
+            DailyError::Io(_) => exit_code::IO,
+            DailyError::WrongDay { .. }
+            | DailyError::WrongPlayer
+            | DailyError::AlreadyRecorded(_)
+            | DailyError::Rejected => exit_code::REJECTED,
+            DailyError::InvalidDate(_) => exit_code::USAGE,
+        };
+        Failure::new(code, e)
+    }
+}
+
+impl From<VerifyError> for Failure {
+    fn from(e: VerifyError) -> Self {
+        let code = match e {
+            VerifyError::TimestampSkew { .. }
+            | VerifyError::ScoreCap { .. }
+            | VerifyError::ScoreStep { .. }
+            | VerifyError::CheckpointSequence
+            | VerifyError::Session(_)
+            | VerifyError::ScoreRate { .. }
+            | VerifyError::Tournament(_)
+            | VerifyError::Daily(_)
+            | VerifyError::GuestRejected
+            | VerifyError::Verification(_) => exit_code::REJECTED,
+            VerifyError::HashEncoding(_)
+            | VerifyError::TierThresholds(_)
+            | VerifyError::Replay(_)
+            | VerifyError::DuplicatePlayer(_) => exit_code::USAGE,
+            VerifyError::Prover(_) => exit_code::PROVER,
+            VerifyError::Save { .. } => exit_code::IO,
+        };
+        Failure::new(code, e)
+    }
+}
+
+pub fn open_store(path: &Path) -> Result<ProofStore, Failure> {
+    ProofStore::open(path).map_err(|e| Failure::new(exit_code::IO, e))
+}
+
+/// Load a proof by store hash, falling back to treating `reference` as a file path
+pub fn load_proof(
+    store_path: &Path,
+    reference: &str,
+) -> Result<(SP1ProofWithPublicValues, Option<StoreEntry>), Failure> {
+    let store = open_store(store_path)?;
+    if let Some(entry) = store.get(reference) {
+        let proof = store.load(reference).map_err(|e| Failure::new(exit_code::IO, e))?;
+        return Ok((proof, Some(entry)));
+    }
+
+    let path = Path::new(reference);
+    if !path.exists() {
+        return Err(Failure::new(
+            exit_code::USAGE,
+            format!("{} is neither a stored proof hash nor a file", reference),
+        ));
+    }
+    let proof = read_proof(path).map_err(|e| Failure::new(exit_code::IO, e))?;
+    Ok((proof, None))
+}
+
+pub fn decode_public_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<game_lib::GameScorePublicValues, Failure> {
+    game_lib::abi::decode(proof.public_values.as_slice())
+        .map_err(|e| Failure::new(exit_code::REJECTED, format!("invalid public values: {}", e)))
+}
+
+pub fn print_public_values(values: &game_lib::GameScorePublicValues) {
+    println!("ABI Version: {}", values.abi_version);
+    println!("Rules Version: {}", values.rules_version);
+    match GameMode::from_id(values.mode) {
+        Some(mode) => println!("Mode: {}", mode),
+        None => println!("Mode: unknown ({})", values.mode),
+    }
+    println!("Timestamp: {}", values.timestamp);
+    println!("Player Hash: {}", hex::encode(values.player_name_hash));
+    println!("Score: {}", values.score);
+    println!("Game Hash: {}", hex::encode(values.game_hash));
+    println!("Verified: {}", values.verified == 1);
+    println!("Checkpoints: {}", values.checkpoint_count);
+    println!("Checkpoint Root: {}", hex::encode(values.checkpoint_root));
+    println!("Duration: {}s ({} ticks)", values.duration_secs, values.ticks);
+}
+
+pub fn read_checkpoints(path: &Path) -> Result<Vec<Checkpoint>, Failure> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        Failure::new(exit_code::IO, format!("failed to read {}: {}", path.display(), e))
+    })?;
+    serde_json::from_str(&contents).map_err(|e| {
+        Failure::new(exit_code::USAGE, format!("invalid checkpoints in {}: {}", path.display(), e))
+    })
+}
+
+pub fn read_events(path: &Path) -> Result<Vec<GameEvent>, Failure> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        Failure::new(exit_code::IO, format!("failed to read {}: {}", path.display(), e))
+    })?;
+    parse_events(&contents).map_err(|e| {
+        Failure::new(exit_code::USAGE, format!("invalid event log in {}: {}", path.display(), e))
+    })
+}
@@ -0,0 +1,30 @@
+//! `prove`: prove a score and add it to the proof store.
+
+use game_verification_script::config::Config;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::{check_submission, ScoreProver};
+
+use super::{open_store, Failure, SubmissionArgs};
+
+pub fn run(config: &Config, args: &SubmissionArgs) -> Result<(), Failure> {
+    let submission = args.submission()?;
+    let current_time = args.reference_time();
+    let policy = config.policy();
+
+    println!("=== GAME SCORE VERIFICATION ===");
+    // Reject obviously invalid submissions before the expensive key setup
+    check_submission(&submission, current_time, &policy)?;
+
+    let store = open_store(&config.store_dir.value)?;
+    let prover = ScoreProver::new(policy, &ConsoleObserver);
+    let result = prover.prove(&submission, current_time, &store, &ConsoleObserver)?;
+
+    println!("Proof saved to: {}", result.proof_path);
+    println!("Proof hash: {}", result.proof_hash);
+    if let Some(report) = &result.anticheat {
+        println!("Anti-cheat suspicion: {:.2} ({})", report.suspicion, report.verdict);
+    }
+    println!("VERIFICATION_SUCCESS=true");
+    println!("=== VERIFICATION COMPLETED SUCCESSFULLY ===");
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! `rank`: prove a player is in the top K of a leaderboard.
+
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use game_lib::rank::RankPublicValues;
+use game_lib::GameMode;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::rank::{decode_rank_values, RankProver, RankedBoard};
+use game_verification_script::store::{read_proof, write_proof};
+
+use super::{exit_code, open_store, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum RankCommand {
+    /// Snapshot the verified board for a mode and print the root to publish
+    Publish {
+        /// Game mode (endless, time-attack or hardcore)
+        #[arg(long, default_value = "endless")]
+        mode: GameMode,
+
+        /// File to write the board snapshot to; keep it private
+        #[arg(long, default_value = "board.json")]
+        out: PathBuf,
+    },
+    /// Prove a player is in the top K of a board snapshot
+    Prove {
+        /// Board snapshot written by `rank publish`
+        #[arg(long, default_value = "board.json")]
+        board: PathBuf,
+
+        /// Player name
+        #[arg(long)]
+        player: String,
+
+        /// Rank the player must be at or above
+        #[arg(long)]
+        k: u32,
+
+        /// File to write the rank proof to
+        #[arg(long, default_value = "rank_proof.bin")]
+        out: PathBuf,
+    },
+    /// Verify a rank proof against a published root
+    Verify {
+        /// Rank proof file written by `rank prove`
+        proof: PathBuf,
+
+        /// Hex board root the proof must be against
+        #[arg(long)]
+        root: String,
+
+        /// Require the proof to be for this player
+        #[arg(long)]
+        player: Option<String>,
+
+        /// Require the proof to show at least this rank
+        #[arg(long)]
+        k: Option<u32>,
+    },
+}
+
+fn print_rank_values(values: &RankPublicValues) {
+    println!("ABI Version: {}", values.abi_version);
+    match GameMode::from_id(values.mode) {
+        Some(mode) => println!("Mode: {}", mode),
+        None => println!("Mode: unknown ({})", values.mode),
+    }
+    println!("Board Root: {}", hex::encode(values.board_root));
+    println!("Board Size: {}", values.leaf_count);
+    println!("Top K: {}", values.k);
+    println!("Player Hash: {}", hex::encode(values.player_name_hash));
+    println!("Member: {}", values.member == 1);
+}
+
+pub fn run(store_path: &Path, command: RankCommand) -> Result<(), Failure> {
+    match command {
+        RankCommand::Publish { mode, out } => {
+            let board = RankedBoard::from_store(&open_store(store_path)?, mode)?;
+            let json = serde_json::to_string_pretty(&board).expect("Failed to serialize board");
+            std::fs::write(&out, json).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to write board: {}", e))
+            })?;
+            println!("Board Root: {}", board.root);
+            println!("Board Size: {}", board.leaves.len());
+            println!("Board snapshot saved to: {}", out.display());
+        }
+        RankCommand::Prove { board, player, k, out } => {
+            let contents = std::fs::read_to_string(&board).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", board.display(), e))
+            })?;
+            let board: RankedBoard = serde_json::from_str(&contents).map_err(|e| {
+                Failure::new(exit_code::USAGE, format!("invalid board snapshot: {}", e))
+            })?;
+            // Reject players outside the top k before the expensive key setup
+            board.check()?;
+            board.member(&player, k)?;
+
+            let prover = RankProver::new(&ConsoleObserver);
+            let proof = prover.prove(&board, &player, k, &ConsoleObserver)?;
+            write_proof(&out, &proof.proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+
+            print_rank_values(&proof.values);
+            println!("Rank proof saved to: {}", out.display());
+        }
+        RankCommand::Verify { proof, root, player, k } => {
+            let proof = read_proof(&proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+            let values = decode_rank_values(&proof)?;
+
+            let prover = RankProver::new(&ConsoleObserver);
+            prover.verify(&proof)?;
+            print_rank_values(&values);
+
+            let problem = if values.member != 1 {
+                Some("proof is valid but the guest rejected the membership".to_string())
+            } else if !hex::encode(values.board_root).eq_ignore_ascii_case(&root) {
+                Some(format!("proof is against root {}", hex::encode(values.board_root)))
+            } else if player.is_some_and(|player| {
+                game_lib::player_name_hash(player.as_bytes()) != values.player_name_hash
+            }) {
+                Some("proof is for a different player".to_string())
+            } else if k.is_some_and(|k| values.k > k) {
+                Some(format!("proof only shows a place in the top {}", values.k))
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                return Err(Failure::new(exit_code::REJECTED, problem));
+            }
+            println!("RANK_VERIFIED=true");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! `replay`: re-execute a submission against a proof's public values.
+
+use std::path::Path;
+
+use game_verification_script::{current_unix_time, guest_stdin, GAME_SCORE_ELF};
+use sp1_sdk::ProverClient;
+
+use super::{
+    decode_public_values, exit_code, load_proof, open_store, print_public_values, Failure,
+    SubmissionArgs,
+};
+
+pub fn run(store_path: &Path, reference: &str, args: &SubmissionArgs) -> Result<(), Failure> {
+    let (proof, entry) = load_proof(store_path, reference)?;
+
+    // The guest checked the timestamp against the time recorded with the
+    // submission; proofs stored without one were proved around when stored
+    let current_time = match (args.reference_time, &entry) {
+        (Some(time), _) => time,
+        (None, Some(entry)) => open_store(store_path)?
+            .submission(&entry.proof_hash)
+            .map_err(|e| Failure::new(exit_code::IO, e))?
+            .map_or(entry.stored_at, |stored| stored.reference_time),
+        (None, None) => current_unix_time(),
+    };
+
+    let stdin = guest_stdin(&args.submission()?, current_time)?;
+    let client = ProverClient::from_env();
+    let (public_values, _) = client.execute(GAME_SCORE_ELF, &stdin).run().map_err(|e| {
+        Failure::new(exit_code::PROVER, format!("failed to execute program: {}", e))
+    })?;
+
+    if public_values.as_slice() == proof.public_values.as_slice() {
+        println!("Replay matches the proof's public values");
+        Ok(())
+    } else {
+        println!("Expected:");
+        print_public_values(&decode_public_values(&proof)?);
+        if let Ok(replayed) = game_lib::abi::decode(public_values.as_slice()) {
+            println!("Replayed:");
+            print_public_values(&replayed);
+        }
+        Err(Failure::new(exit_code::REJECTED, "replay does not match the proof's public values"))
+    }
+}
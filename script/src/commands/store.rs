@@ -0,0 +1,86 @@
+//! `store`: list, look up and prune stored proofs.
+
+use std::path::Path;
+
+use clap::Subcommand;
+use game_verification_script::current_unix_time;
+use game_verification_script::store::{RetentionPolicy, StoreEntry};
+
+use super::{exit_code, open_store, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum StoreCommand {
+    /// List stored proofs, oldest first
+    List,
+    /// Show the index entry for a proof hash
+    Lookup { proof_hash: String },
+    /// Remove proofs according to a retention policy
+    Prune {
+        /// Remove proofs stored more than this many seconds ago
+        #[arg(long)]
+        max_age_secs: Option<u64>,
+
+        /// Keep only this many most recently stored proofs
+        #[arg(long)]
+        max_entries: Option<usize>,
+
+        /// Remove proofs whose guest verification failed
+        #[arg(long)]
+        drop_unverified: bool,
+
+        /// Never remove a player's best verified score
+        #[arg(long)]
+        keep_best_per_player: bool,
+    },
+}
+
+fn print_entry(entry: &StoreEntry) {
+    println!(
+        "{}  score={:<6} verified={:<5} timestamp={} player={}",
+        entry.proof_hash, entry.score, entry.verified, entry.timestamp, entry.player_name_hash
+    );
+}
+
+pub fn run(store_path: &Path, command: StoreCommand) -> Result<(), Failure> {
+    let store = open_store(store_path)?;
+
+    match command {
+        StoreCommand::List => {
+            let entries = store.list();
+            for entry in &entries {
+                print_entry(entry);
+            }
+            println!("{} proofs in {}", entries.len(), store.root().display());
+        }
+        StoreCommand::Lookup { proof_hash } => {
+            let entry = store.get(&proof_hash).ok_or_else(|| {
+                Failure::new(exit_code::USAGE, format!("no proof with hash {}", proof_hash))
+            })?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&entry).expect("Failed to serialize entry")
+            );
+        }
+        StoreCommand::Prune {
+            max_age_secs,
+            max_entries,
+            drop_unverified,
+            keep_best_per_player,
+        } => {
+            let policy = RetentionPolicy {
+                max_age_secs,
+                max_entries,
+                drop_unverified,
+                keep_best_per_player,
+            };
+            let removed = store
+                .prune(&policy, current_unix_time())
+                .map_err(|e| Failure::new(exit_code::IO, e))?;
+            for entry in &removed {
+                print_entry(entry);
+            }
+            println!("Removed {} proofs", removed.len());
+        }
+    }
+    Ok(())
+}
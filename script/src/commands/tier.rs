@@ -0,0 +1,101 @@
+//! `tier`: prove a score reached a tier without revealing it.
+
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use game_lib::tier::TierPublicValues;
+use game_lib::GameMode;
+use game_verification_script::config::Config;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::store::{read_proof, write_proof};
+use game_verification_script::tier::{check_tier_submission, decode_tier_values, TierProver};
+
+use super::{exit_code, Failure, SubmissionArgs};
+
+#[derive(Subcommand, Debug)]
+pub enum TierCommand {
+    /// Prove which of the given thresholds a score reached
+    Prove {
+        #[command(flatten)]
+        submission: Box<SubmissionArgs>,
+
+        /// Strictly ascending score thresholds, comma separated (e.g. 1000,5000,9000)
+        #[arg(long, value_delimiter = ',', required = true)]
+        thresholds: Vec<u32>,
+
+        /// File to write the tier proof to
+        #[arg(long, default_value = "tier_proof.bin")]
+        out: PathBuf,
+    },
+    /// Verify a tier proof file and print the tier it shows
+    Verify {
+        /// Tier proof file written by `tier prove`
+        proof: PathBuf,
+
+        /// Fail unless the proof shows the score was at least this
+        #[arg(long)]
+        min_score: Option<u32>,
+    },
+}
+
+fn print_tier_values(values: &TierPublicValues) {
+    println!("ABI Version: {}", values.abi_version);
+    println!("Rules Version: {}", values.rules_version);
+    match GameMode::from_id(values.mode) {
+        Some(mode) => println!("Mode: {}", mode),
+        None => println!("Mode: unknown ({})", values.mode),
+    }
+    println!("Timestamp: {}", values.timestamp);
+    println!("Player Hash: {}", hex::encode(values.player_name_hash));
+    println!("Thresholds: {:?}", values.thresholds);
+    match values.min_score() {
+        Some(min_score) => println!("Tier: {} (score >= {})", values.tier, min_score),
+        None => println!("Tier: 0 (below every threshold)"),
+    }
+    println!("Verified: {}", values.verified == 1);
+}
+
+pub fn run(config: &Config, command: TierCommand) -> Result<(), Failure> {
+    match command {
+        TierCommand::Prove { submission, thresholds, out } => {
+            let current_time = submission.reference_time();
+            let submission = submission.submission()?;
+            let policy = config.policy();
+
+            println!("=== GAME SCORE TIER PROOF ===");
+            // Reject obviously invalid submissions before the expensive key setup
+            check_tier_submission(&submission, &thresholds, current_time, &policy)?;
+
+            let prover = TierProver::new(policy, &ConsoleObserver);
+            let proof = prover.prove(&submission, &thresholds, current_time, &ConsoleObserver)?;
+            write_proof(&out, &proof.proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+
+            print_tier_values(&proof.values);
+            println!("Tier proof saved to: {}", out.display());
+        }
+        TierCommand::Verify { proof, min_score } => {
+            let proof = read_proof(&proof).map_err(|e| Failure::new(exit_code::IO, e))?;
+            let values = decode_tier_values(&proof)?;
+
+            let prover = TierProver::new(config.policy(), &ConsoleObserver);
+            prover.verify(&proof)?;
+            print_tier_values(&values);
+            if values.verified != 1 {
+                return Err(Failure::new(
+                    exit_code::REJECTED,
+                    "proof is valid but the guest rejected the score",
+                ));
+            }
+            if let Some(min_score) = min_score {
+                if values.min_score() < Some(min_score) {
+                    return Err(Failure::new(
+                        exit_code::REJECTED,
+                        format!("proof does not show a score of at least {}", min_score),
+                    ));
+                }
+            }
+            println!("TIER_VERIFIED=true");
+        }
+    }
+    Ok(())
+}
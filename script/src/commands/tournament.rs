@@ -0,0 +1,153 @@
+//! `tournament`: run tournaments played on a fixed seed.
+
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use game_lib::tournament::TournamentRules;
+use game_lib::GameMode;
+use game_verification_script::config::Config;
+use game_verification_script::current_unix_time;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::store::write_proof;
+use game_verification_script::tournament::{
+    check_entry, Tournament, TournamentEntry, TournamentError, TournamentProver, TOURNAMENTS_DIR,
+};
+
+use super::{exit_code, Failure};
+
+#[derive(Subcommand, Debug)]
+pub enum TournamentCommand {
+    /// Define a tournament and print the id its proofs commit to
+    Create {
+        /// Name of the tournament (letters, digits, '-' and '_')
+        #[arg(long)]
+        id: String,
+
+        /// Game mode (endless, time-attack or hardcore)
+        #[arg(long, default_value = "endless")]
+        mode: GameMode,
+
+        /// Seed every entrant plays
+        #[arg(long)]
+        seed: u64,
+
+        /// Unix time games may be played from
+        #[arg(long)]
+        starts_at: u64,
+
+        /// Unix time games must be played before
+        #[arg(long)]
+        ends_at: u64,
+
+        /// Players who may register, comma separated (default: anyone)
+        #[arg(long, value_delimiter = ',')]
+        allow: Option<Vec<String>>,
+    },
+    /// Register a player for a tournament
+    Register {
+        id: String,
+
+        /// Player name
+        #[arg(long)]
+        player: String,
+    },
+    /// Prove a registered player's game and record the result
+    Submit {
+        id: String,
+
+        /// Player name
+        #[arg(long)]
+        player: String,
+
+        /// Encoded replay recorded by the client
+        #[arg(long)]
+        replay: PathBuf,
+
+        /// Game timestamp (0 uses the current time)
+        #[arg(long, default_value = "0")]
+        timestamp: u64,
+
+        /// Unix time to check the timestamp against instead of the current time
+        #[arg(long)]
+        reference_time: Option<u64>,
+    },
+    /// Print each player's best result, best first
+    Standings {
+        id: String,
+
+        /// Print the standings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run(config: &Config, store_path: &Path, command: TournamentCommand) -> Result<(), Failure> {
+    let dir = store_path.join(TOURNAMENTS_DIR);
+    match command {
+        TournamentCommand::Create { id, mode, seed, starts_at, ends_at, allow } => {
+            let rules = TournamentRules { id, mode, seed, starts_at, ends_at };
+            let tournament = Tournament::new(rules, allow)?;
+            tournament.create(&dir)?;
+            println!("Tournament: {}", tournament.rules.id);
+            println!("Tournament Id: {}", tournament.commitment());
+        }
+        TournamentCommand::Register { id, player } => {
+            let mut tournament = Tournament::load(&dir, &id)?;
+            tournament.register(&player, current_unix_time())?;
+            tournament.save(&dir)?;
+            println!("Registered {} for {}", player, id);
+        }
+        TournamentCommand::Submit { id, player, replay, timestamp, reference_time } => {
+            let mut tournament = Tournament::load(&dir, &id)?;
+            if !tournament.is_registered(&player) {
+                return Err(TournamentError::NotRegistered(player).into());
+            }
+            let replay = std::fs::read(&replay).map_err(|e| {
+                Failure::new(exit_code::IO, format!("failed to read {}: {}", replay.display(), e))
+            })?;
+            let current_time = reference_time.unwrap_or_else(current_unix_time);
+            let timestamp = if timestamp == 0 { current_unix_time() } else { timestamp };
+            let entry = TournamentEntry { timestamp, player, replay };
+            let policy = config.policy();
+
+            println!("=== TOURNAMENT ENTRY PROOF ===");
+            // Reject entries on the wrong seed before the expensive key setup
+            check_entry(&tournament.rules, &entry, current_time, &policy)?;
+
+            let prover = TournamentProver::new(policy, &ConsoleObserver);
+            let proof = prover.prove(&tournament.rules, &entry, current_time, &ConsoleObserver)?;
+            let proof_dir = tournament.proof_dir(&dir);
+            let out = proof_dir.join(format!("{}.bin", hex::encode(proof.values.replay_hash)));
+            std::fs::create_dir_all(&proof_dir)
+                .map_err(|e| Failure::new(exit_code::IO, e))
+                .and_then(|_| {
+                    write_proof(&out, &proof.proof).map_err(|e| Failure::new(exit_code::IO, e))
+                })?;
+
+            let result = tournament.record(&entry.player, &proof.values, &out)?;
+            println!("Player: {}", result.player);
+            println!("Score: {}", result.score);
+            println!("Proof saved to: {}", result.proof_path);
+            tournament.save(&dir)?;
+        }
+        TournamentCommand::Standings { id, json } => {
+            let tournament = Tournament::load(&dir, &id)?;
+            let standings = tournament.standings();
+            if json {
+                let json = serde_json::to_string_pretty(&standings)
+                    .expect("Failed to serialize standings");
+                println!("{}", json);
+                return Ok(());
+            }
+            let rules = &tournament.rules;
+            println!("{} standings ({}, seed {})", id, rules.mode, rules.seed);
+            for row in &standings {
+                println!(
+                    "{:>3}. score={:<6} timestamp={} player={} entries={}",
+                    row.rank, row.score, row.timestamp, row.player, row.entries
+                );
+            }
+        }
+    }
+    Ok(())
+}
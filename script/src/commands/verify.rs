@@ -0,0 +1,34 @@
+//! `verify`: verify a stored proof or proof file.
+
+use game_verification_script::config::Config;
+use game_verification_script::progress::ConsoleObserver;
+use game_verification_script::ScoreProver;
+
+use super::{decode_public_values, exit_code, load_proof, print_public_values, Failure};
+
+pub fn run(config: &Config, reference: &str) -> Result<(), Failure> {
+    let (proof, _) = load_proof(&config.store_dir.value, reference)?;
+    let values = decode_public_values(&proof)?;
+
+    let prover = ScoreProver::new(config.policy(), &ConsoleObserver);
+    let result = prover.verify(&proof);
+
+    print_public_values(&values);
+    match result {
+        Ok(()) if values.verified == 1 => {
+            println!("VERIFICATION_SUCCESS=true");
+            Ok(())
+        }
+        Ok(()) => {
+            println!("VERIFICATION_SUCCESS=false");
+            Err(Failure::new(
+                exit_code::REJECTED,
+                "proof is valid but the guest rejected the score",
+            ))
+        }
+        Err(e) => {
+            println!("VERIFICATION_SUCCESS=false");
+            Err(e.into())
+        }
+    }
+}
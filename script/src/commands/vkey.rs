@@ -0,0 +1,62 @@
+//! `vkey`: print or save the verification key of a guest program.
+
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use game_verification_script::config::Config;
+use game_verification_script::{
+    GAME_ACHIEVEMENTS_ELF, GAME_COOP_ELF, GAME_DAILY_ELF, GAME_RANK_ELF, GAME_SCORE_ELF,
+    GAME_TIER_ELF, GAME_TOURNAMENT_ELF, GAME_VERIFICATION_ELF,
+};
+use sp1_sdk::{HashableKey, ProverClient};
+
+use super::{exit_code, Failure};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Program {
+    GameScore,
+    Verification,
+    GameTier,
+    GameRank,
+    GameAchievements,
+    GameCoop,
+    GameTournament,
+    GameDaily,
+}
+
+pub fn run(config: &Config, program: Program, out: Option<PathBuf>) -> Result<(), Failure> {
+    let (elf, name) = match program {
+        Program::GameScore => (GAME_SCORE_ELF, "game_score"),
+        Program::Verification => (GAME_VERIFICATION_ELF, "verification"),
+        Program::GameTier => (GAME_TIER_ELF, "game_tier"),
+        Program::GameRank => (GAME_RANK_ELF, "game_rank"),
+        Program::GameAchievements => (GAME_ACHIEVEMENTS_ELF, "game_achievements"),
+        Program::GameCoop => (GAME_COOP_ELF, "game_coop"),
+        Program::GameTournament => (GAME_TOURNAMENT_ELF, "game_tournament"),
+        Program::GameDaily => (GAME_DAILY_ELF, "game_daily"),
+    };
+
+    let client = ProverClient::from_env();
+    let (_, vk) = client.setup(elf);
+    println!("{}", vk.bytes32());
+
+    let path = match out {
+        Some(path) => path,
+        None => {
+            let cache_dir = &config.cache_dir.value;
+            std::fs::create_dir_all(cache_dir).map_err(|e| {
+                Failure::new(
+                    exit_code::IO,
+                    format!("failed to create {}: {}", cache_dir.display(), e),
+                )
+            })?;
+            cache_dir.join(format!("{}_vkey.json", name))
+        }
+    };
+    let vk_json = serde_json::to_string_pretty(&vk).expect("Failed to serialize verification key");
+    std::fs::write(&path, vk_json).map_err(|e| {
+        Failure::new(exit_code::IO, format!("failed to save verification key: {}", e))
+    })?;
+    println!("Verification key saved to: {}", path.display());
+    Ok(())
+}
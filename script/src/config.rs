@@ -0,0 +1,399 @@
+//! Layered configuration for the command line tool.
+//!
+//! Each setting is resolved from, in increasing priority: built-in defaults,
+//! a TOML file, `BLADEWARRIOR_*` environment variables (including those loaded
+//! from `.env`) and command line flags. The layer a value came from is kept so
+//! `bladewarrior config show` can report it.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use game_lib::{MAX_SCORE, MAX_SCORE_PER_MINUTE, MAX_TIMESTAMP_SKEW_SECS};
+
+use crate::store::DEFAULT_STORE_DIR;
+use crate::VerificationPolicy;
+
+/// Config file read from the working directory when no other is given
+pub const DEFAULT_CONFIG_FILE: &str = "bladewarrior.toml";
+
+/// Environment variable naming the config file
+pub const CONFIG_ENV: &str = "BLADEWARRIOR_CONFIG";
+
+/// SP1 prover backend, passed to the SDK as `SP1_PROVER`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProverMode {
+    Cpu,
+    Cuda,
+    Network,
+    Mock,
+}
+
+impl ProverMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProverMode::Cpu => "cpu",
+            ProverMode::Cuda => "cuda",
+            ProverMode::Network => "network",
+            ProverMode::Mock => "mock",
+        }
+    }
+}
+
+impl fmt::Display for ProverMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProverMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cpu" => Ok(ProverMode::Cpu),
+            "cuda" => Ok(ProverMode::Cuda),
+            "network" => Ok(ProverMode::Network),
+            "mock" => Ok(ProverMode::Mock),
+            _ => Err(format!("unknown prover mode {} (expected cpu, cuda, network or mock)", s)),
+        }
+    }
+}
+
+/// Where a setting's effective value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => f.write_str("default"),
+            Source::File(path) => write!(f, "file {}", path.display()),
+            Source::Env(var) => write!(f, "env {}", var),
+            Source::Cli => f.write_str("command line"),
+        }
+    }
+}
+
+/// A resolved value and the layer that set it
+#[derive(Debug, Clone)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Setting<T> {
+    fn default(value: T) -> Self {
+        Self { value, source: Source::Default }
+    }
+
+    fn set(&mut self, value: Option<T>, source: Source) {
+        if let Some(value) = value {
+            self.value = value;
+            self.source = source;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    File { path: PathBuf, reason: String },
+    Env { var: &'static str, reason: String },
+    /// A value parsed but is not allowed, such as a policy looser than the guest's
+    Value { key: &'static str, source: Source, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, reason } => {
+                write!(f, "invalid config file {}: {}", path.display(), reason)
+            }
+            ConfigError::Env { var, reason } => write!(f, "invalid value for {}: {}", var, reason),
+            ConfigError::Value { key, source, reason } => {
+                write!(f, "invalid {} from {}: {}", key, source, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Layout of the TOML config file; every key is optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    #[serde(default)]
+    prover: FileProver,
+    #[serde(default)]
+    paths: FilePaths,
+    #[serde(default)]
+    policy: FilePolicy,
+    #[serde(default)]
+    concurrency: FileConcurrency,
+    #[serde(default)]
+    server: FileServer,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileProver {
+    mode: Option<ProverMode>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilePaths {
+    store_dir: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilePolicy {
+    max_timestamp_skew_secs: Option<u64>,
+    max_score: Option<u32>,
+    max_score_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConcurrency {
+    parallelism: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileServer {
+    bind: Option<String>,
+}
+
+/// Values given as command line flags
+#[derive(Debug, Default, Clone)]
+pub struct CliOverrides {
+    pub config_file: Option<PathBuf>,
+    pub prover_mode: Option<ProverMode>,
+    pub store_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub parallelism: Option<usize>,
+}
+
+/// Effective configuration
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Config file that was read, if any
+    pub file: Option<PathBuf>,
+    pub prover_mode: Setting<ProverMode>,
+    /// Proof store directory
+    pub store_dir: Setting<PathBuf>,
+    /// Directory for reusable artifacts such as verification keys
+    pub cache_dir: Setting<PathBuf>,
+    pub max_timestamp_skew_secs: Setting<u64>,
+    pub max_score: Setting<u32>,
+    pub max_score_per_minute: Setting<u32>,
+    /// Number of proofs generated concurrently in batch mode
+    pub parallelism: Setting<usize>,
+    /// Address the verification server listens on
+    pub server_bind: Setting<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let policy = VerificationPolicy::default();
+        Self {
+            file: None,
+            prover_mode: Setting::default(ProverMode::Cpu),
+            store_dir: Setting::default(PathBuf::from(DEFAULT_STORE_DIR)),
+            cache_dir: Setting::default(PathBuf::from(".bladewarrior")),
+            max_timestamp_skew_secs: Setting::default(policy.max_timestamp_skew_secs),
+            max_score: Setting::default(policy.max_score),
+            max_score_per_minute: Setting::default(policy.max_score_per_minute),
+            parallelism: Setting::default(1),
+            server_bind: Setting::default("0.0.0.0:3000".to_string()),
+        }
+    }
+}
+
+impl Config {
+    /// Resolve every layer, reading the config file and process environment
+    pub fn load(cli: &CliOverrides) -> Result<Self, ConfigError> {
+        Self::resolve(cli, |var| std::env::var(var).ok())
+    }
+
+    /// Resolve every layer with `env` standing in for the process environment
+    pub fn resolve(
+        cli: &CliOverrides,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+
+        // A file named explicitly must exist; the default one is optional
+        let explicit = cli
+            .config_file
+            .clone()
+            .or_else(|| env(CONFIG_ENV).map(PathBuf::from));
+        let path = match explicit {
+            Some(path) => Some(path),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.exists()),
+        };
+        if let Some(path) = path {
+            config.apply_file(&path)?;
+        }
+
+        config.apply_env(&env)?;
+        config.apply_cli(cli);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject a policy looser than the guest's own limits, which the guest
+    /// would refuse only after proving, and a server address without a port
+    fn validate(&self) -> Result<(), ConfigError> {
+        fn at_most<T: PartialOrd + fmt::Display>(
+            key: &'static str,
+            setting: &Setting<T>,
+            limit: T,
+        ) -> Result<(), ConfigError> {
+            if setting.value <= limit {
+                return Ok(());
+            }
+            Err(ConfigError::Value {
+                key,
+                source: setting.source.clone(),
+                reason: format!("{} is above the guest's limit of {}", setting.value, limit),
+            })
+        }
+
+        at_most(
+            "policy.max_timestamp_skew_secs",
+            &self.max_timestamp_skew_secs,
+            MAX_TIMESTAMP_SKEW_SECS,
+        )?;
+        at_most("policy.max_score", &self.max_score, MAX_SCORE)?;
+        at_most("policy.max_score_per_minute", &self.max_score_per_minute, MAX_SCORE_PER_MINUTE)?;
+
+        let bind = &self.server_bind.value;
+        let valid = matches!(bind.rsplit_once(':'),
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok());
+        if !valid {
+            return Err(ConfigError::Value {
+                key: "server.bind",
+                source: self.server_bind.source.clone(),
+                reason: format!("{} is not a host:port address", bind),
+            });
+        }
+        Ok(())
+    }
+
+    fn apply_file(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let error = |reason: String| ConfigError::File { path: path.to_path_buf(), reason };
+        let contents = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        let file: FileConfig = toml::from_str(&contents).map_err(|e| error(e.to_string()))?;
+
+        let source = Source::File(path.to_path_buf());
+        self.prover_mode.set(file.prover.mode, source.clone());
+        self.store_dir.set(file.paths.store_dir, source.clone());
+        self.cache_dir.set(file.paths.cache_dir, source.clone());
+        self.max_timestamp_skew_secs
+            .set(file.policy.max_timestamp_skew_secs, source.clone());
+        self.max_score.set(file.policy.max_score, source.clone());
+        self.max_score_per_minute.set(file.policy.max_score_per_minute, source.clone());
+        self.parallelism.set(file.concurrency.parallelism, source.clone());
+        self.server_bind.set(file.server.bind, source);
+        self.file = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    fn apply_env(&mut self, env: &impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        fn parse<T: FromStr>(
+            env: &impl Fn(&str) -> Option<String>,
+            var: &'static str,
+        ) -> Result<Option<T>, ConfigError>
+        where
+            T::Err: fmt::Display,
+        {
+            env(var)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|e: T::Err| ConfigError::Env { var, reason: e.to_string() })
+                })
+                .transpose()
+        }
+
+        // SP1's own variable is honoured, but ours takes precedence
+        for var in ["SP1_PROVER", "BLADEWARRIOR_PROVER_MODE"] {
+            self.prover_mode.set(parse(env, var)?, Source::Env(var));
+        }
+        const STORE_DIR: &str = "BLADEWARRIOR_STORE_DIR";
+        const CACHE_DIR: &str = "BLADEWARRIOR_CACHE_DIR";
+        const MAX_SKEW: &str = "BLADEWARRIOR_MAX_TIMESTAMP_SKEW_SECS";
+        const MAX_SCORE: &str = "BLADEWARRIOR_MAX_SCORE";
+        const MAX_RATE: &str = "BLADEWARRIOR_MAX_SCORE_PER_MINUTE";
+        const PARALLELISM: &str = "BLADEWARRIOR_PARALLELISM";
+        const SERVER_BIND: &str = "BLADEWARRIOR_SERVER_BIND";
+        self.store_dir.set(parse(env, STORE_DIR)?, Source::Env(STORE_DIR));
+        self.cache_dir.set(parse(env, CACHE_DIR)?, Source::Env(CACHE_DIR));
+        self.max_timestamp_skew_secs.set(parse(env, MAX_SKEW)?, Source::Env(MAX_SKEW));
+        self.max_score.set(parse(env, MAX_SCORE)?, Source::Env(MAX_SCORE));
+        self.max_score_per_minute.set(parse(env, MAX_RATE)?, Source::Env(MAX_RATE));
+        self.parallelism.set(parse(env, PARALLELISM)?, Source::Env(PARALLELISM));
+        self.server_bind.set(env(SERVER_BIND), Source::Env(SERVER_BIND));
+        Ok(())
+    }
+
+    fn apply_cli(&mut self, cli: &CliOverrides) {
+        self.prover_mode.set(cli.prover_mode, Source::Cli);
+        self.store_dir.set(cli.store_dir.clone(), Source::Cli);
+        self.cache_dir.set(cli.cache_dir.clone(), Source::Cli);
+        self.parallelism.set(cli.parallelism, Source::Cli);
+    }
+
+    pub fn policy(&self) -> VerificationPolicy {
+        VerificationPolicy {
+            max_timestamp_skew_secs: self.max_timestamp_skew_secs.value,
+            max_score: self.max_score.value,
+            max_score_per_minute: self.max_score_per_minute.value,
+        }
+    }
+
+    /// Point the SP1 SDK at the configured prover; call before creating a client
+    pub fn apply_prover_mode(&self) {
+        std::env::set_var("SP1_PROVER", self.prover_mode.value.as_str());
+    }
+
+    /// Effective values as `(key, value, source)` rows, in file order
+    pub fn rows(&self) -> Vec<(&'static str, String, &Source)> {
+        vec![
+            ("prover.mode", self.prover_mode.value.to_string(), &self.prover_mode.source),
+            ("paths.store_dir", self.store_dir.value.display().to_string(), &self.store_dir.source),
+            ("paths.cache_dir", self.cache_dir.value.display().to_string(), &self.cache_dir.source),
+            (
+                "policy.max_timestamp_skew_secs",
+                self.max_timestamp_skew_secs.value.to_string(),
+                &self.max_timestamp_skew_secs.source,
+            ),
+            ("policy.max_score", self.max_score.value.to_string(), &self.max_score.source),
+            (
+                "policy.max_score_per_minute",
+                self.max_score_per_minute.value.to_string(),
+                &self.max_score_per_minute.source,
+            ),
+            (
+                "concurrency.parallelism",
+                self.parallelism.value.to_string(),
+                &self.parallelism.source,
+            ),
+            ("server.bind", self.server_bind.value.clone(), &self.server_bind.source),
+        ]
+    }
+}
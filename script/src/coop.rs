@@ -0,0 +1,162 @@
+//! Host support for two-player co-op proofs.
+//!
+//! The co-op guest replays both players' input streams and commits their
+//! name hashes and scores together. Like tier proofs, co-op proofs commit
+//! their own layout and are written to standalone files.
+
+use std::convert::Infallible;
+
+use game_lib::coop::{self, CoopPublicValues, CoopReplay, CoopSimulation};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::progress::{run_stage, ProgressObserver, Stage};
+use crate::{write_replay, VerificationPolicy, VerifyError, GAME_COOP_ELF};
+
+/// A co-op game as submitted by the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoopSubmission {
+    pub timestamp: u64,
+    /// Players in the order of their input streams in the replay
+    pub players: [String; coop::PLAYERS],
+    /// Encoded [`CoopReplay`]
+    pub replay: Vec<u8>,
+}
+
+/// Guest input for a co-op submission checked against `current_time`
+pub fn coop_stdin(submission: &CoopSubmission, current_time: u64) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&submission.timestamp);
+    for player in &submission.players {
+        stdin.write(&player.as_bytes().to_vec());
+    }
+    stdin.write(&current_time);
+    write_replay(&mut stdin, &submission.replay);
+    stdin
+}
+
+/// Host-side checks mirroring the co-op guest, returning the replayed game
+pub fn check_coop_submission(
+    submission: &CoopSubmission,
+    current_time: u64,
+    policy: &VerificationPolicy,
+) -> Result<CoopSimulation, VerifyError> {
+    if current_time.abs_diff(submission.timestamp) > policy.max_timestamp_skew_secs {
+        return Err(VerifyError::TimestampSkew {
+            timestamp: submission.timestamp,
+            current_time,
+            max_skew: policy.max_timestamp_skew_secs,
+        });
+    }
+
+    let [first, second] = &submission.players;
+    if first == second {
+        return Err(VerifyError::DuplicatePlayer(first.clone()));
+    }
+
+    let replay =
+        CoopReplay::decode(&submission.replay).map_err(|e| VerifyError::Replay(e.to_string()))?;
+    let sim = replay.run();
+    let rules = replay.mode.rules();
+    let max_score = policy.max_score.min(rules.max_score);
+    if let Some(&score) = sim.scores().iter().find(|&&score| score > max_score) {
+        return Err(VerifyError::ScoreCap { score, max_score });
+    }
+    Ok(sim)
+}
+
+/// Decode the public values of a co-op proof
+pub fn decode_coop_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<CoopPublicValues, VerifyError> {
+    coop::decode(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::Verification(format!("invalid co-op public values: {}", e)))
+}
+
+/// A generated co-op proof with its decoded public values
+pub struct CoopProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub values: CoopPublicValues,
+}
+
+/// Prover with the co-op program keys set up, reusable across submissions
+pub struct CoopProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+    policy: VerificationPolicy,
+}
+
+impl CoopProver {
+    /// Create the SP1 client and generate the co-op program keys
+    pub fn new(policy: VerificationPolicy, observer: &dyn ProgressObserver) -> Self {
+        let client = ProverClient::from_env();
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_COOP_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk, policy }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Verify a proof against the co-op program verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client.verify(proof, &self.vk).map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Run the co-op guest without proving, returning its public values
+    pub fn execute(
+        &self,
+        submission: &CoopSubmission,
+        current_time: u64,
+    ) -> Result<CoopPublicValues, VerifyError> {
+        let stdin = coop_stdin(submission, current_time);
+        let (public_values, _) = self
+            .client
+            .execute(GAME_COOP_ELF, &stdin)
+            .run()
+            .map_err(|e| VerifyError::Prover(e.to_string()))?;
+        coop::decode(public_values.as_slice())
+            .map_err(|e| VerifyError::Prover(format!("invalid co-op public values: {}", e)))
+    }
+
+    /// Check, prove and verify one co-op game
+    pub fn prove(
+        &self,
+        submission: &CoopSubmission,
+        current_time: u64,
+        observer: &dyn ProgressObserver,
+    ) -> Result<CoopProof, VerifyError> {
+        check_coop_submission(submission, current_time, &self.policy)?;
+        let stdin = coop_stdin(submission, current_time);
+
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (_, report) = self
+                .client
+                .execute(GAME_COOP_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            let cycles = report.total_instruction_count();
+            Ok((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        let values = decode_coop_values(&proof)?;
+        Ok(CoopProof { proof, values })
+    }
+}
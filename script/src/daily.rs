@@ -0,0 +1,397 @@
+//! Daily challenges: the seed of each UTC day and its separate leaderboard.
+//!
+//! [`DailyChallenge`] is what the web client needs to start the day's game.
+//! Proved games are recorded on a board per day, a JSON file under
+//! `<store>/daily/<date>.json`, with their proofs kept next to it in
+//! `<store>/daily/<date>/`. Daily boards are kept apart from the main
+//! leaderboard, which ranks games played on any seed.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use game_lib::daily::{self, DailyPublicValues, DAILY_DOMAIN, SECS_PER_DAY};
+use game_lib::replay::Replay;
+use game_lib::sim::Simulation;
+use game_lib::GameMode;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::progress::{run_stage, ProgressObserver, Stage};
+use crate::{current_unix_time, write_replay, VerificationPolicy, VerifyError, GAME_DAILY_ELF};
+
+/// Directory under the proof store that daily boards are kept in
+pub const DAILY_DIR: &str = "daily";
+
+/// Everything a client needs to play one day's challenge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyChallenge {
+    /// `YYYY-MM-DD`, in UTC
+    pub date: String,
+    /// Days since 1970-01-01
+    pub day: u32,
+    pub seed: u64,
+    /// Domain string the seed is derived under
+    pub domain: String,
+    /// Unix time games may be played from
+    pub starts_at: u64,
+    /// Unix time games must be played before
+    pub ends_at: u64,
+}
+
+impl DailyChallenge {
+    pub fn for_day(day: u32) -> Self {
+        let starts_at = daily::day_start(day);
+        Self {
+            date: daily::date(day),
+            day,
+            seed: daily::daily_seed(day),
+            domain: DAILY_DOMAIN.to_string(),
+            starts_at,
+            ends_at: starts_at + SECS_PER_DAY,
+        }
+    }
+
+    /// Challenge of a `YYYY-MM-DD` date
+    pub fn for_date(date: &str) -> Result<Self, DailyError> {
+        daily::parse_date(date)
+            .map(Self::for_day)
+            .ok_or_else(|| DailyError::InvalidDate(date.to_string()))
+    }
+
+    /// Challenge of the day containing `timestamp`
+    pub fn at(timestamp: u64) -> Self {
+        Self::for_day(daily::day_of(timestamp))
+    }
+
+    /// Challenge of the current UTC day
+    pub fn today() -> Self {
+        Self::at(current_unix_time())
+    }
+}
+
+/// A daily game as submitted by the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyEntry {
+    pub timestamp: u64,
+    pub player: String,
+    /// Encoded [`Replay`]
+    pub replay: Vec<u8>,
+}
+
+/// One proved daily game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub player: String,
+    pub mode: GameMode,
+    pub score: u32,
+    pub timestamp: u64,
+    pub replay_hash: String,
+    /// Where the game's proof was saved
+    pub proof_path: String,
+}
+
+/// One ranked row of a daily board
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyStanding {
+    /// 1-based position
+    pub rank: usize,
+    pub player: String,
+    pub score: u32,
+    pub timestamp: u64,
+    /// Games the player had accepted in this mode
+    pub attempts: usize,
+}
+
+/// The results recorded for one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyBoard {
+    #[serde(flatten)]
+    pub challenge: DailyChallenge,
+    /// Accepted results in the order they were recorded
+    #[serde(default)]
+    pub results: Vec<DailyResult>,
+}
+
+#[derive(Debug)]
+pub enum DailyError {
+    /// Not a `YYYY-MM-DD` date from 1970-01-01 on
+    InvalidDate(String),
+    /// The proof was made for a different day
+    WrongDay {
+        expected: u32,
+        actual: u32,
+    },
+    /// The proof is for a different player than the result is recorded for
+    WrongPlayer,
+    /// A result for the same replay was already recorded
+    AlreadyRecorded(String),
+    /// The guest rejected the game
+    Rejected,
+    Io(String),
+    Verify(VerifyError),
+}
+
+impl fmt::Display for DailyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DailyError::InvalidDate(date) => {
+                write!(f, "invalid date {:?}: expected YYYY-MM-DD from 1970-01-01 on", date)
+            }
+            DailyError::WrongDay { expected, actual } => write!(
+                f,
+                "proof is for the challenge of {}, not {}",
+                daily::date(*actual),
+                daily::date(*expected)
+            ),
+            DailyError::WrongPlayer => write!(f, "proof is for a different player"),
+            DailyError::AlreadyRecorded(replay_hash) => {
+                write!(f, "replay {} was already recorded", replay_hash)
+            }
+            DailyError::Rejected => write!(f, "proof is valid but the guest rejected the game"),
+            DailyError::Io(reason) => write!(f, "daily board I/O error: {}", reason),
+            DailyError::Verify(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DailyError {}
+
+impl From<VerifyError> for DailyError {
+    fn from(e: VerifyError) -> Self {
+        DailyError::Verify(e)
+    }
+}
+
+impl DailyBoard {
+    pub fn new(challenge: DailyChallenge) -> Self {
+        Self { challenge, results: Vec::new() }
+    }
+
+    /// Accept a verified game played on this board's day
+    pub fn record(
+        &mut self,
+        player: &str,
+        values: &DailyPublicValues,
+        proof_path: &Path,
+    ) -> Result<&DailyResult, DailyError> {
+        if values.day != self.challenge.day {
+            return Err(DailyError::WrongDay { expected: self.challenge.day, actual: values.day });
+        }
+        if values.verified != 1 {
+            return Err(DailyError::Rejected);
+        }
+        if values.player_name_hash != game_lib::player_name_hash(player.as_bytes()) {
+            return Err(DailyError::WrongPlayer);
+        }
+        let mode = GameMode::from_id(values.mode)
+            .ok_or_else(|| VerifyError::Verification(format!("unknown mode {}", values.mode)))?;
+        let replay_hash = hex::encode(values.replay_hash);
+        if self.results.iter().any(|result| result.replay_hash == replay_hash) {
+            return Err(DailyError::AlreadyRecorded(replay_hash));
+        }
+        self.results.push(DailyResult {
+            player: player.to_string(),
+            mode,
+            score: values.score,
+            timestamp: values.timestamp,
+            replay_hash,
+            proof_path: proof_path.display().to_string(),
+        });
+        Ok(&self.results[self.results.len() - 1])
+    }
+
+    /// Each player's best result in `mode`, best first, truncated to `limit`
+    /// rows; ties go to whoever set the score first
+    pub fn standings(&self, mode: GameMode, limit: Option<usize>) -> Vec<DailyStanding> {
+        let mut best = BTreeMap::<&str, (&DailyResult, usize)>::new();
+        for result in self.results.iter().filter(|result| result.mode == mode) {
+            let (current, attempts) = best.entry(result.player.as_str()).or_insert((result, 0));
+            *attempts += 1;
+            let ranks_above = result.score > current.score
+                || (result.score == current.score && result.timestamp < current.timestamp);
+            if ranks_above {
+                *current = result;
+            }
+        }
+
+        let mut rows: Vec<(&DailyResult, usize)> = best.into_values().collect();
+        rows.sort_by(|(a, _), (b, _)| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.timestamp.cmp(&b.timestamp))
+                .then_with(|| a.player.cmp(&b.player))
+        });
+        rows.into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(position, (result, attempts))| DailyStanding {
+                rank: position + 1,
+                player: result.player.clone(),
+                score: result.score,
+                timestamp: result.timestamp,
+                attempts,
+            })
+            .collect()
+    }
+
+    fn path(dir: &Path, date: &str) -> PathBuf {
+        dir.join(format!("{}.json", date))
+    }
+
+    /// Directory the day's proofs are saved in
+    pub fn proof_dir(&self, dir: &Path) -> PathBuf {
+        dir.join(&self.challenge.date)
+    }
+
+    /// Load the board of `challenge`'s day, or an empty one if nothing was recorded yet
+    pub fn load(dir: &Path, challenge: DailyChallenge) -> Result<Self, DailyError> {
+        let path = Self::path(dir, &challenge.date);
+        if !path.exists() {
+            return Ok(Self::new(challenge));
+        }
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| DailyError::Io(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| DailyError::Io(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), DailyError> {
+        let path = Self::path(dir, &self.challenge.date);
+        let json = serde_json::to_string_pretty(self).expect("Failed to serialize daily board");
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&path, json))
+            .map_err(|e| DailyError::Io(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Guest input for a game played on the challenge of `day`
+pub fn daily_stdin(day: u32, entry: &DailyEntry, current_time: u64) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&day);
+    stdin.write(&entry.timestamp);
+    stdin.write(&entry.player.as_bytes().to_vec());
+    stdin.write(&current_time);
+    write_replay(&mut stdin, &entry.replay);
+    stdin
+}
+
+/// Host-side checks mirroring the daily guest, returning the replayed game
+pub fn check_daily_entry(
+    challenge: &DailyChallenge,
+    entry: &DailyEntry,
+    current_time: u64,
+    policy: &VerificationPolicy,
+) -> Result<Simulation, VerifyError> {
+    if current_time.abs_diff(entry.timestamp) > policy.max_timestamp_skew_secs {
+        return Err(VerifyError::TimestampSkew {
+            timestamp: entry.timestamp,
+            current_time,
+            max_skew: policy.max_timestamp_skew_secs,
+        });
+    }
+    if entry.timestamp < challenge.starts_at || entry.timestamp >= challenge.ends_at {
+        return Err(VerifyError::Daily(format!(
+            "played at {}, outside {} ({}..{})",
+            entry.timestamp, challenge.date, challenge.starts_at, challenge.ends_at
+        )));
+    }
+
+    let replay = Replay::decode(&entry.replay).map_err(|e| VerifyError::Replay(e.to_string()))?;
+    if replay.seed != challenge.seed {
+        return Err(VerifyError::Daily(format!(
+            "played on seed {}, not the seed {} of {}",
+            replay.seed, challenge.seed, challenge.date
+        )));
+    }
+
+    let sim = replay.run();
+    let max_score = policy.max_score.min(replay.mode.rules().max_score);
+    if sim.score() > max_score {
+        return Err(VerifyError::ScoreCap { score: sim.score(), max_score });
+    }
+    Ok(sim)
+}
+
+/// Decode the public values of a daily proof
+pub fn decode_daily_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<DailyPublicValues, VerifyError> {
+    daily::decode(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::Verification(format!("invalid daily public values: {}", e)))
+}
+
+/// A generated daily proof with its decoded public values
+pub struct DailyProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub values: DailyPublicValues,
+}
+
+/// Prover with the daily program keys set up, reusable across games
+pub struct DailyProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+    policy: VerificationPolicy,
+}
+
+impl DailyProver {
+    /// Create the SP1 client and generate the daily program keys
+    pub fn new(policy: VerificationPolicy, observer: &dyn ProgressObserver) -> Self {
+        let client = ProverClient::from_env();
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_DAILY_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk, policy }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Verify a proof against the daily program verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client.verify(proof, &self.vk).map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Check, prove and verify one game played on `challenge`
+    pub fn prove(
+        &self,
+        challenge: &DailyChallenge,
+        entry: &DailyEntry,
+        current_time: u64,
+        observer: &dyn ProgressObserver,
+    ) -> Result<DailyProof, VerifyError> {
+        check_daily_entry(challenge, entry, current_time, &self.policy)?;
+        let stdin = daily_stdin(challenge.day, entry, current_time);
+
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (_, report) = self
+                .client
+                .execute(GAME_DAILY_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            let cycles = report.total_instruction_count();
+            Ok((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        let values = decode_daily_values(&proof)?;
+        Ok(DailyProof { proof, values })
+    }
+}
@@ -0,0 +1,540 @@
+//! Golden test vectors for the byte layouts shared with other implementations.
+//!
+//! The web client and contracts must reproduce the game hash, the committed
+//! player name hash, the ABI encoding of the public values, the checkpoint
+//! Merkle root and the daily challenge seed bit for bit, and a replayed game
+//! must end with the same score and state hash, down to the raw bits of the
+//! fixed-point math underneath. [`generate`] computes a fixed set of
+//! vectors from the Rust definitions; the JSON file it writes is checked in
+//! so every implementation can test against the same bytes.
+
+use alloy_sol_types::private::FixedBytes;
+use alloy_sol_types::SolType;
+use game_lib::abi::{self, v0, v1, v2, v3};
+use game_lib::checkpoint::{self, Checkpoint};
+use game_lib::daily::{self, DAILY_DOMAIN};
+use game_lib::fixed::Fixed;
+use game_lib::replay::{self, Replay};
+use game_lib::sim::{Input, Rng, Simulation, TICK_RATE};
+use game_lib::{GameMode, GameScorePublicValues, ABI_VERSION, MAX_SCORE, RULES_VERSION};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Layout version of the vectors file; bump when its fields change
+pub const GOLDEN_VERSION: u32 = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenVectors {
+    pub version: u32,
+    /// `ABI_VERSION` and `RULES_VERSION` the vectors were generated with
+    pub abi_version: u32,
+    pub rules_version: u32,
+    pub game_hash: Vec<GameHashVector>,
+    pub player_name_hash: Vec<PlayerNameHashVector>,
+    pub abi: Vec<AbiVector>,
+    pub checkpoint_root: Vec<CheckpointRootVector>,
+    pub daily_seed: Vec<DailySeedVector>,
+    pub fixed: Vec<FixedVector>,
+    pub replay: Vec<ReplayVector>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameHashVector {
+    pub player: String,
+    /// UTF-8 bytes of `player`, so non-ASCII names are unambiguous
+    pub player_utf8: String,
+    pub score: u32,
+    pub timestamp: u64,
+    pub game_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerNameHashVector {
+    pub player: String,
+    pub player_utf8: String,
+    pub player_name_hash: String,
+}
+
+/// Public values and their encoding in one layout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiVector {
+    pub name: String,
+    /// ABI version of `encoded`; older layouts are only ever decoded
+    pub layout: u32,
+    /// What `encoded` decodes to
+    pub values: PublicValuesVector,
+    pub encoded: String,
+}
+
+/// [`GameScorePublicValues`] with byte fields as hex
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicValuesVector {
+    pub abi_version: u32,
+    pub rules_version: u32,
+    pub mode: u32,
+    pub timestamp: u64,
+    pub player_name_hash: String,
+    pub score: u32,
+    pub game_hash: String,
+    pub verified: u32,
+    pub checkpoint_root: String,
+    pub checkpoint_count: u32,
+    pub duration_secs: u32,
+    pub ticks: u64,
+}
+
+impl From<&GameScorePublicValues> for PublicValuesVector {
+    fn from(values: &GameScorePublicValues) -> Self {
+        Self {
+            abi_version: values.abi_version,
+            rules_version: values.rules_version,
+            mode: values.mode,
+            timestamp: values.timestamp,
+            player_name_hash: hex::encode(values.player_name_hash),
+            score: values.score,
+            game_hash: hex::encode(values.game_hash),
+            verified: values.verified,
+            checkpoint_root: hex::encode(values.checkpoint_root),
+            checkpoint_count: values.checkpoint_count,
+            duration_secs: values.duration_secs,
+            ticks: values.ticks,
+        }
+    }
+}
+
+impl PublicValuesVector {
+    /// Back to public values; panics on malformed hex, which only a corrupted
+    /// vectors file contains
+    pub fn to_values(&self) -> GameScorePublicValues {
+        GameScorePublicValues {
+            abi_version: self.abi_version,
+            rules_version: self.rules_version,
+            mode: self.mode,
+            timestamp: self.timestamp,
+            player_name_hash: hash32(&self.player_name_hash),
+            score: self.score,
+            game_hash: hash32(&self.game_hash),
+            verified: self.verified,
+            checkpoint_root: hash32(&self.checkpoint_root),
+            checkpoint_count: self.checkpoint_count,
+            duration_secs: self.duration_secs,
+            ticks: self.ticks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointRootVector {
+    pub name: String,
+    pub checkpoints: Vec<Checkpoint>,
+    /// Leaf hash of each checkpoint, in order
+    pub leaf_hashes: Vec<String>,
+    pub root: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailySeedVector {
+    pub date: String,
+    /// Days since 1970-01-01
+    pub day: u32,
+    /// String hashed for the seed
+    pub preimage: String,
+    /// Full SHA-256 of `preimage`; the seed is its first 8 bytes, little-endian
+    pub digest: String,
+    pub seed: u64,
+}
+
+/// One fixed-point operation, with arguments and results as raw 32.32 bits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixedVector {
+    /// `sqrt`, `hypot`, `atan2` or `sin_cos`, called as `args[0].op(args[1])`
+    pub op: String,
+    pub args: Vec<i64>,
+    /// `[sin, cos]` for `sin_cos`, otherwise the single result
+    pub result: Vec<i64>,
+}
+
+/// An encoded replay and the state the simulation ends in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayVector {
+    pub name: String,
+    pub replay: String,
+    pub replay_hash: String,
+    /// Ticks simulated, fewer than the inputs if the game ended early
+    pub ticks: u64,
+    pub score: u32,
+    pub wave: u32,
+    pub lives: u32,
+    pub state_hash: String,
+}
+
+fn hash32(hex_str: &str) -> [u8; 32] {
+    hex::decode(hex_str)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .expect("golden vector hashes are 32 hex-encoded bytes")
+}
+
+/// Names covering ASCII, combining and multi-byte characters, emoji and the empty name
+const PLAYERS: [&str; 8] = [
+    "",
+    "a",
+    "TestPlayer",
+    "Player With Spaces",
+    "Zoë Ünïcödé",
+    "玩家一号",
+    "⚔️ Blade 🗡️",
+    "a-very-long-player-name-that-is-longer-than-thirty-two-bytes-in-utf8",
+];
+
+const SCORES: [u32; 5] = [0, 10, MAX_SCORE - 10, MAX_SCORE, u32::MAX];
+
+const TIMESTAMPS: [u64; 3] = [0, 1_700_000_000, u64::MAX];
+
+fn game_hash_vectors() -> Vec<GameHashVector> {
+    let mut vectors = Vec::new();
+    for player in PLAYERS {
+        // Every score and timestamp for one name, then one combination for the rest
+        let combinations: Vec<(u32, u64)> = if player == "TestPlayer" {
+            SCORES.iter().flat_map(|s| TIMESTAMPS.iter().map(move |t| (*s, *t))).collect()
+        } else {
+            vec![(420, 1_700_000_000)]
+        };
+        for (score, timestamp) in combinations {
+            vectors.push(GameHashVector {
+                player: player.to_string(),
+                player_utf8: hex::encode(player),
+                score,
+                timestamp,
+                game_hash: hex::encode(game_lib::game_hash(player, score, timestamp)),
+            });
+        }
+    }
+    vectors
+}
+
+fn player_name_hash_vectors() -> Vec<PlayerNameHashVector> {
+    PLAYERS
+        .iter()
+        .map(|player| PlayerNameHashVector {
+            player: player.to_string(),
+            player_utf8: hex::encode(player),
+            player_name_hash: hex::encode(game_lib::player_name_hash(player.as_bytes())),
+        })
+        .collect()
+}
+
+fn checkpoints(count: u32) -> Vec<Checkpoint> {
+    (1..=count)
+        .map(|wave| Checkpoint {
+            wave,
+            tick: u64::from(wave - 1) * 1800,
+            score: (wave - 1) * 50,
+            lives: 3 - (wave / 4).min(2),
+            state_hash: [wave as u8; 32],
+        })
+        .collect()
+}
+
+fn public_values(
+    mode: GameMode,
+    timestamp: u64,
+    player: &str,
+    score: u32,
+    verified: u32,
+    checkpoints: &[Checkpoint],
+    ticks: u64,
+) -> GameScorePublicValues {
+    GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: mode.id(),
+        timestamp,
+        player_name_hash: game_lib::player_name_hash(player.as_bytes()),
+        score,
+        game_hash: game_lib::game_hash(player, score, timestamp),
+        verified,
+        checkpoint_root: checkpoint::merkle_root(checkpoints),
+        checkpoint_count: checkpoints.len() as u32,
+        duration_secs: (ticks / u64::from(TICK_RATE)) as u32,
+        ticks,
+    }
+}
+
+fn abi_vectors() -> Vec<AbiVector> {
+    let current = |name: &str, values: GameScorePublicValues| AbiVector {
+        name: name.to_string(),
+        layout: ABI_VERSION,
+        values: PublicValuesVector::from(&values),
+        encoded: hex::encode(abi::encode(values)),
+    };
+    let legacy = |name: &str, layout: u32, encoded: Vec<u8>| AbiVector {
+        name: name.to_string(),
+        layout,
+        values: PublicValuesVector::from(&abi::decode(&encoded).expect("legacy layouts decode")),
+        encoded: hex::encode(encoded),
+    };
+
+    let zero = GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: 0,
+        timestamp: 0,
+        player_name_hash: [0; 32],
+        score: 0,
+        game_hash: [0; 32],
+        verified: 0,
+        checkpoint_root: checkpoint::EMPTY_ROOT,
+        checkpoint_count: 0,
+        duration_secs: 0,
+        ticks: 0,
+    };
+    let max = GameScorePublicValues {
+        mode: u32::MAX,
+        timestamp: u64::MAX,
+        player_name_hash: [0xff; 32],
+        score: u32::MAX,
+        game_hash: [0xff; 32],
+        verified: 1,
+        checkpoint_root: [0xff; 32],
+        checkpoint_count: u32::MAX,
+        duration_secs: u32::MAX,
+        ticks: u64::MAX,
+        ..zero.clone()
+    };
+
+    let typical = public_values(
+        GameMode::Endless,
+        1_700_000_000,
+        "TestPlayer",
+        420,
+        1,
+        &checkpoints(5),
+        9_000,
+    );
+    let v0 = v0::GameScoreData {
+        timestamp: typical.timestamp,
+        playerNameHash: FixedBytes(typical.player_name_hash),
+        score: typical.score,
+        gameHash: FixedBytes(typical.game_hash),
+        verified: typical.verified,
+    };
+    let v1 = v1::GameScoreData {
+        abiVersion: 1,
+        rulesVersion: 1,
+        timestamp: typical.timestamp,
+        playerNameHash: FixedBytes(typical.player_name_hash),
+        score: typical.score,
+        gameHash: FixedBytes(typical.game_hash),
+        verified: typical.verified,
+    };
+    let v2 = v2::GameScoreData {
+        abiVersion: 2,
+        rulesVersion: 2,
+        mode: GameMode::Hardcore.id(),
+        timestamp: typical.timestamp,
+        playerNameHash: FixedBytes(typical.player_name_hash),
+        score: typical.score,
+        gameHash: FixedBytes(typical.game_hash),
+        verified: typical.verified,
+    };
+    let v3 = v3::GameScoreData {
+        abiVersion: 3,
+        rulesVersion: 3,
+        mode: typical.mode,
+        timestamp: typical.timestamp,
+        playerNameHash: FixedBytes(typical.player_name_hash),
+        score: typical.score,
+        gameHash: FixedBytes(typical.game_hash),
+        verified: typical.verified,
+        checkpointRoot: FixedBytes(typical.checkpoint_root),
+        checkpointCount: typical.checkpoint_count,
+    };
+
+    vec![
+        current("zero", zero),
+        current("typical_endless", typical),
+        current(
+            "time_attack_cap",
+            public_values(
+                GameMode::TimeAttack,
+                1_700_000_000,
+                "Zoë Ünïcödé",
+                5000,
+                1,
+                &checkpoints(9),
+                180 * u64::from(TICK_RATE),
+            ),
+        ),
+        current(
+            "hardcore_rejected",
+            public_values(GameMode::Hardcore, 1_700_000_000, "玩家一号", 30, 0, &[], 125),
+        ),
+        current("max", max),
+        legacy("legacy_v0", 0, v0::GameScoreData::abi_encode(&v0)),
+        legacy("legacy_v1", 1, v1::GameScoreData::abi_encode(&v1)),
+        legacy("legacy_v2", 2, v2::GameScoreData::abi_encode(&v2)),
+        legacy("legacy_v3", 3, v3::GameScoreData::abi_encode(&v3)),
+    ]
+}
+
+fn checkpoint_root_vectors() -> Vec<CheckpointRootVector> {
+    // Odd counts exercise nodes carried up without a sibling
+    [("empty", 0), ("single", 1), ("pair", 2), ("odd", 5), ("power_of_two", 8)]
+        .into_iter()
+        .map(|(name, count)| {
+            let checkpoints = checkpoints(count);
+            CheckpointRootVector {
+                name: name.to_string(),
+                leaf_hashes: checkpoints.iter().map(|c| hex::encode(c.leaf_hash())).collect(),
+                root: hex::encode(checkpoint::merkle_root(&checkpoints)),
+                checkpoints,
+            }
+        })
+        .collect()
+}
+
+fn daily_seed_vectors() -> Vec<DailySeedVector> {
+    // The epoch, a leap day, both ends of a year and the far future
+    ["1970-01-01", "2000-02-29", "2023-12-31", "2024-01-01", "2099-12-31"]
+        .into_iter()
+        .map(|date| {
+            let day = daily::parse_date(date).expect("golden daily dates are valid");
+            let preimage = format!("{}:{}", DAILY_DOMAIN, date);
+            DailySeedVector {
+                date: date.to_string(),
+                day,
+                digest: hex::encode(Sha256::digest(preimage.as_bytes())),
+                preimage,
+                seed: daily::daily_seed(day),
+            }
+        })
+        .collect()
+}
+
+fn fixed_vectors() -> Vec<FixedVector> {
+    let vector = |op: &str, args: &[Fixed], result: &[Fixed]| FixedVector {
+        op: op.to_string(),
+        args: args.iter().map(|value| value.raw()).collect(),
+        result: result.iter().map(|value| value.raw()).collect(),
+    };
+    let mut vectors = Vec::new();
+
+    // Exact squares, values between steps, the largest number and zero
+    for value in [0.0, 1.0, 2.0, 0.25, 1e-9, 640_000.0, f64::MAX] {
+        let value = Fixed::from_f64(value);
+        vectors.push(vector("sqrt", &[value], &[value.sqrt()]));
+    }
+
+    // Arena-sized vectors, negative sides, one step and the ends of the range
+    let lengths = [(3.0, -4.0), (800.0, 600.0), (-0.5, 0.125), (1e-9, 0.0), (f64::MAX, f64::MAX)];
+    for (x, y) in lengths {
+        let (x, y) = (Fixed::from_f64(x), Fixed::from_f64(y));
+        vectors.push(vector("hypot", &[x, y], &[x.hypot(y)]));
+    }
+
+    // Every axis and diagonal, the negative x axis either side of zero,
+    // tiny and huge vectors and the zero vector
+    let directions = [
+        (0.0, 1.0),
+        (1.0, 1.0),
+        (1.0, 0.0),
+        (1.0, -1.0),
+        (0.0, -1.0),
+        (-1.0, -1.0),
+        (-1.0, 0.0),
+        (-1.0, 1.0),
+        (-1e-9, -1.0),
+        (123.456, -654.321),
+        (1e-9, 3e-9),
+        (1e9, -1e9),
+        (0.0, 0.0),
+    ];
+    for (y, x) in directions {
+        let (y, x) = (Fixed::from_f64(y), Fixed::from_f64(x));
+        vectors.push(vector("atan2", &[y, x], &[y.atan2(x)]));
+    }
+
+    // Quadrant boundaries, both ends of (-PI, PI], angles past them and random ones
+    let mut angles =
+        vec![Fixed::ZERO, Fixed::HALF_PI, Fixed::PI, -Fixed::PI, -Fixed::HALF_PI, Fixed::TWO_PI];
+    angles.extend([1.0, -2.5, 4.0, 100.0, -1e6].map(Fixed::from_f64));
+    let mut rng = Rng::new(0x51_4e);
+    angles.extend((0..8).map(|_| Fixed::from_f64((rng.next_f64() * 2.0 - 1.0) * 10.0)));
+    for angle in angles {
+        let (sin, cos) = angle.sin_cos();
+        vectors.push(vector("sin_cos", &[angle], &[sin, cos]));
+    }
+    vectors
+}
+
+/// Replay of `ticks` ticks in which the player aims at the nearest enemy,
+/// moving in directions drawn from `seed` so it also runs into enemies
+fn bot_replay(mode: GameMode, seed: u64, ticks: u64) -> Replay {
+    let mut sim = Simulation::new(mode, seed);
+    let mut replay = Replay::new(mode, seed);
+    let mut rng = Rng::new(seed ^ 0xb07);
+    let mut buttons = 0;
+    for tick in 0..ticks {
+        if tick % 30 == 0 {
+            buttons = rng.below(16) as u8;
+        }
+        let player = *sim.player();
+        let distance = |x: f64, y: f64| (x - player.x).hypot(y - player.y);
+        let target = sim
+            .enemies()
+            .iter()
+            .min_by(|a, b| distance(a.x, a.y).total_cmp(&distance(b.x, b.y)))
+            .map_or((player.x, player.y), |enemy| (enemy.x, enemy.y));
+        let input = Input::new(buttons, target.0 as i16, target.1 as i16);
+        sim.step(input);
+        replay.inputs.push(input);
+    }
+    replay
+}
+
+fn replay_vectors() -> Vec<ReplayVector> {
+    // Games that run to the end of their inputs, one that ends long before
+    // them and one with no inputs at all
+    [
+        ("endless_bot", bot_replay(GameMode::Endless, 7, 1200)),
+        ("hardcore_bot", bot_replay(GameMode::Hardcore, 99, 2400)),
+        (
+            "hardcore_idle",
+            Replay { inputs: vec![Input::default(); 2400], ..Replay::new(GameMode::Hardcore, 5) },
+        ),
+        ("time_attack_idle", Replay::new(GameMode::TimeAttack, 3)),
+    ]
+    .into_iter()
+    .map(|(name, replay)| {
+        let encoded = replay.encode();
+        let sim = replay.run();
+        ReplayVector {
+            name: name.to_string(),
+            replay_hash: hex::encode(replay::replay_hash(&encoded)),
+            replay: hex::encode(encoded),
+            ticks: sim.tick(),
+            score: sim.score(),
+            wave: sim.wave(),
+            lives: sim.lives(),
+            state_hash: hex::encode(sim.state_hash()),
+        }
+    })
+    .collect()
+}
+
+/// Compute every vector from the current Rust definitions
+pub fn generate() -> GoldenVectors {
+    GoldenVectors {
+        version: GOLDEN_VERSION,
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        game_hash: game_hash_vectors(),
+        player_name_hash: player_name_hash_vectors(),
+        abi: abi_vectors(),
+        checkpoint_root: checkpoint_root_vectors(),
+        daily_seed: daily_seed_vectors(),
+        fixed: fixed_vectors(),
+        replay: replay_vectors(),
+    }
+}
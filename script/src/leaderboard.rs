@@ -0,0 +1,75 @@
+//! Per-mode leaderboards built from the proof store index.
+//!
+//! Only proofs the guest accepted are ranked, and scores overturned by a
+//! challenge are left out. Each player appears once per board with their
+//! best score; ties go to whoever set the score first.
+
+use std::collections::BTreeMap;
+
+use game_lib::GameMode;
+use serde::Serialize;
+
+use crate::challenge::ChallengeOutcome;
+use crate::store::StoreEntry;
+
+/// One ranked row of a board
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoardEntry {
+    /// 1-based position on the board
+    pub rank: usize,
+    pub player_name_hash: String,
+    pub score: u32,
+    pub timestamp: u64,
+    pub proof_hash: String,
+    /// Set if the score survived a challenge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<ChallengeOutcome>,
+}
+
+/// Verified board for `mode`, best first, truncated to `limit` rows if given
+pub fn board(entries: &[StoreEntry], mode: GameMode, limit: Option<usize>) -> Vec<BoardEntry> {
+    let mut best = BTreeMap::<&str, &StoreEntry>::new();
+    let ranked = entries.iter().filter(|e| {
+        e.verified && e.mode == mode.id() && e.challenge != Some(ChallengeOutcome::Overturned)
+    });
+    for entry in ranked {
+        let current = best.entry(entry.player_name_hash.as_str()).or_insert(entry);
+        if ranks_above(entry, current) {
+            *current = entry;
+        }
+    }
+
+    let mut rows: Vec<&StoreEntry> = best.into_values().collect();
+    rows.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.timestamp.cmp(&b.timestamp))
+            .then_with(|| a.proof_hash.cmp(&b.proof_hash))
+    });
+    rows.truncate(limit.unwrap_or(usize::MAX));
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(position, entry)| BoardEntry {
+            rank: position + 1,
+            player_name_hash: entry.player_name_hash.clone(),
+            score: entry.score,
+            timestamp: entry.timestamp,
+            proof_hash: entry.proof_hash.clone(),
+            challenge: entry.challenge,
+        })
+        .collect()
+}
+
+/// Whether `a` should replace `b` as a player's best entry
+fn ranks_above(a: &StoreEntry, b: &StoreEntry) -> bool {
+    a.score > b.score || (a.score == b.score && a.timestamp < b.timestamp)
+}
+
+/// Entries in `mode` that have been challenged, most recently stored first
+pub fn challenged(entries: &[StoreEntry], mode: GameMode) -> Vec<&StoreEntry> {
+    let mut challenged: Vec<&StoreEntry> =
+        entries.iter().filter(|e| e.mode == mode.id() && e.challenge.is_some()).collect();
+    challenged.sort_by(|a, b| b.stored_at.cmp(&a.stored_at));
+    challenged
+}
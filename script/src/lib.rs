@@ -1,10 +1,79 @@
+use std::convert::Infallible;
+use std::fmt;
 use std::time::{SystemTime, UNIX_EPOCH};
-use sp1_sdk::{SP1Stdin, ProverClient, include_elf};
-use sha2::{Sha256, Digest};
+use sp1_sdk::{
+    SP1Stdin, ProverClient, EnvProver, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+    include_elf,
+};
+use serde::{Serialize, Deserialize};
+use game_lib::checkpoint::{self, Checkpoint};
+use game_lib::session::Session;
+use game_lib::modes::ModeRules;
+use game_lib::replay::REPLAY_CHUNK_LEN;
+use game_lib::{GameMode, GameScorePublicValues};
+
+pub mod achievements;
+pub mod anticheat;
+pub mod batch;
+pub mod challenge;
+pub mod checkpoints;
+pub mod config;
+pub mod coop;
+pub mod daily;
+pub mod golden;
+pub mod leaderboard;
+pub mod progress;
+pub mod rank;
+pub mod report;
+pub mod store;
+pub mod tier;
+pub mod tournament;
+
+use anticheat::{AnticheatReport, GameEvent, Thresholds};
+use progress::{run_stage, NoopObserver, ProgressObserver, Stage};
+use store::{ProofStore, StoredSubmission, DEFAULT_STORE_DIR};
 
 /// RISC-V ELF file for game score verification program
 pub const GAME_SCORE_ELF: &[u8] = include_elf!("game_score_program");
 pub const GAME_VERIFICATION_ELF: &[u8] = include_elf!("game_verification_program");
+/// RISC-V ELF file for the tier program, which keeps the score private
+pub const GAME_TIER_ELF: &[u8] = include_elf!("game_tier_program");
+/// RISC-V ELF file for the top-K leaderboard rank program
+pub const GAME_RANK_ELF: &[u8] = include_elf!("game_rank_program");
+/// RISC-V ELF file for the program proving achievements from a replay
+pub const GAME_ACHIEVEMENTS_ELF: &[u8] = include_elf!("game_achievements_program");
+/// RISC-V ELF file for the two-player co-op program
+pub const GAME_COOP_ELF: &[u8] = include_elf!("game_coop_program");
+/// RISC-V ELF file for the tournament entry program
+pub const GAME_TOURNAMENT_ELF: &[u8] = include_elf!("game_tournament_program");
+/// RISC-V ELF file for the daily challenge program
+pub const GAME_DAILY_ELF: &[u8] = include_elf!("game_daily_program");
+/// RISC-V ELF file for the program timing software and precompile hashing
+pub const GAME_HASH_BENCH_ELF: &[u8] = include_elf!("game_hash_bench_program");
+
+pub use game_lib::{MAX_SCORE, MAX_SCORE_PER_MINUTE, MAX_TIMESTAMP_SKEW_SECS};
+
+/// Host-side limits checked before proving
+///
+/// The guest always applies `MAX_SCORE`, `MAX_SCORE_PER_MINUTE` and
+/// `MAX_TIMESTAMP_SKEW_SECS`, so a policy can only usefully be stricter than
+/// the defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    pub max_timestamp_skew_secs: u64,
+    pub max_score: u32,
+    pub max_score_per_minute: u32,
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> Self {
+        Self {
+            max_timestamp_skew_secs: MAX_TIMESTAMP_SKEW_SECS,
+            max_score: MAX_SCORE,
+            max_score_per_minute: MAX_SCORE_PER_MINUTE,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct GameVerificationResult {
@@ -14,134 +83,476 @@ pub struct GameVerificationResult {
     pub score: u32,
     pub game_hash: String,
     pub proof_hash: String,
+    /// Where the proof was saved in the proof store
+    pub proof_path: String,
+    /// Heuristic analysis of the event log, if the submission had one
+    pub anticheat: Option<AnticheatReport>,
+}
+
+/// Reasons a game score could not be verified
+#[derive(Debug)]
+pub enum VerifyError {
+    /// Game timestamp is too far from the current time
+    TimestampSkew { timestamp: u64, current_time: u64, max_skew: u64 },
+    /// Score exceeds the maximum valid score
+    ScoreCap { score: u32, max_score: u32 },
+    /// Score is not a whole number of kills for its mode
+    ScoreStep { score: u32, points_per_kill: u32 },
+    /// Checkpoints are out of order or inconsistent with the final score
+    CheckpointSequence,
+    /// Game start, end and tick count are out of order or disagree
+    Session(String),
+    /// Score was earned faster than its mode allows
+    ScoreRate { score: u32, ticks: u64, max_score_per_minute: u32 },
+    /// Game hash is not 32 hex-encoded bytes
+    HashEncoding(String),
+    /// Tier thresholds are empty, too many or not strictly ascending
+    TierThresholds(String),
+    /// Replay could not be decoded
+    Replay(String),
+    /// The same player was given for both sides of a co-op game
+    DuplicatePlayer(String),
+    /// Game was not played under the tournament's seed, mode or window
+    Tournament(String),
+    /// Game was not played on the daily challenge's seed or day
+    Daily(String),
+    /// The guest ran but committed `verified = 0`
+    GuestRejected,
+    /// SP1 failed to generate the proof
+    Prover(String),
+    /// Generated proof did not verify against the verification key
+    Verification(String),
+    /// Proof could not be written to disk
+    Save { path: String, reason: String },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::TimestampSkew { timestamp, current_time, max_skew } => write!(
+                f,
+                "timestamp {} is more than {} seconds from current time {}",
+                timestamp, max_skew, current_time
+            ),
+            VerifyError::ScoreCap { score, max_score } => {
+                write!(f, "score {} exceeds maximum of {}", score, max_score)
+            }
+            VerifyError::ScoreStep { score, points_per_kill } => write!(
+                f,
+                "score {} is not a multiple of {} points per kill",
+                score, points_per_kill
+            ),
+            VerifyError::CheckpointSequence => {
+                write!(f, "checkpoints are out of order or inconsistent with the score")
+            }
+            VerifyError::Session(reason) => write!(f, "implausible game session: {}", reason),
+            VerifyError::ScoreRate { score, ticks, max_score_per_minute } => write!(
+                f,
+                "score {} in {} ticks is more than {} points per minute",
+                score, ticks, max_score_per_minute
+            ),
+            VerifyError::HashEncoding(reason) => write!(f, "invalid game hash: {}", reason),
+            VerifyError::TierThresholds(reason) => write!(f, "invalid tier thresholds: {}", reason),
+            VerifyError::Replay(reason) => write!(f, "invalid replay: {}", reason),
+            VerifyError::DuplicatePlayer(player) => {
+                write!(f, "{} cannot play both sides of a co-op game", player)
+            }
+            VerifyError::Tournament(reason) => write!(f, "not a valid tournament entry: {}", reason),
+            VerifyError::Daily(reason) => write!(f, "not a valid daily challenge game: {}", reason),
+            VerifyError::GuestRejected => write!(f, "the guest rejected the score"),
+            VerifyError::Prover(reason) => write!(f, "failed to generate proof: {}", reason),
+            VerifyError::Verification(reason) => write!(f, "proof verification failed: {}", reason),
+            VerifyError::Save { path, reason } => {
+                write!(f, "failed to save proof to {}: {}", path, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl VerifyError {
+    /// Short machine-readable name of the failure, used to group results
+    pub fn kind(&self) -> &'static str {
+        match self {
+            VerifyError::TimestampSkew { .. } => "timestamp_skew",
+            VerifyError::ScoreCap { .. } => "score_cap",
+            VerifyError::ScoreStep { .. } => "score_step",
+            VerifyError::CheckpointSequence => "checkpoint_sequence",
+            VerifyError::Session(_) => "session",
+            VerifyError::ScoreRate { .. } => "score_rate",
+            VerifyError::HashEncoding(_) => "hash_encoding",
+            VerifyError::TierThresholds(_) => "tier_thresholds",
+            VerifyError::Replay(_) => "replay",
+            VerifyError::DuplicatePlayer(_) => "duplicate_player",
+            VerifyError::Tournament(_) => "tournament",
+            VerifyError::Daily(_) => "daily",
+            VerifyError::GuestRejected => "guest_rejected",
+            VerifyError::Prover(_) => "prover",
+            VerifyError::Verification(_) => "verification",
+            VerifyError::Save { .. } => "save",
+        }
+    }
 }
 
 /// Verify game score using SP1
-pub fn verify_game_score(timestamp: u64, player_name: &str, score: u32, game_hash: &str) -> bool {
-    // Output verification information with color
-    println!("\x1b[38;5;213m=== SP1 GAME SCORE VERIFICATION ===\x1b[0m");
-    println!("\x1b[38;5;213mINITIALIZING VERIFICATION PROTOCOL...\x1b[0m");
-    println!("\x1b[38;5;213mAGENT: {}\x1b[0m", player_name);
-    println!("\x1b[38;5;213mMISSION SCORE: {}\x1b[0m", score);
-    println!("\x1b[38;5;213mTIMESTAMP: {}\x1b[0m", timestamp);
-    println!("\x1b[38;5;213mMISSION DATA HASH: {}\x1b[0m", game_hash);
-    
-    // Verify timestamp (not too far in the past or future)
-    let current_time = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    
-    println!("\x1b[38;5;213mCURRENT TIME: {}\x1b[0m", current_time);
-    
-    let time_diff = if current_time > timestamp {
-        current_time - timestamp
-    } else {
-        timestamp - current_time
+pub fn verify_game_score(
+    timestamp: u64,
+    player_name: &str,
+    score: u32,
+    game_hash: &str,
+    session: Session,
+) -> Result<GameVerificationResult, VerifyError> {
+    verify_game_score_with_observer(
+        timestamp,
+        player_name,
+        score,
+        game_hash,
+        session,
+        &NoopObserver,
+    )
+}
+
+/// Verify game score using SP1, reporting each pipeline stage to `observer`
+pub fn verify_game_score_with_observer(
+    timestamp: u64,
+    player_name: &str,
+    score: u32,
+    game_hash: &str,
+    session: Session,
+    observer: &dyn ProgressObserver,
+) -> Result<GameVerificationResult, VerifyError> {
+    let submission = Submission {
+        timestamp,
+        player: player_name.to_string(),
+        score,
+        game_hash: game_hash.to_string(),
+        mode: GameMode::Endless,
+        checkpoints: Vec::new(),
+        session,
+        events: Vec::new(),
+        replay: Vec::new(),
     };
-    
-    println!("\x1b[38;5;213mTIME DIFFERENCE: {} SECONDS\x1b[0m", time_diff);
-    
-    // Timestamp cannot be more than 1 hour different
-    if time_diff > 3600 {
-        println!("\x1b[38;5;197mTIMESTAMP VERIFICATION FAILED: TOO FAR FROM CURRENT TIME\x1b[0m");
-        println!("\x1b[38;5;197mSECURITY PROTOCOL VIOLATED\x1b[0m");
-        return false;
-    }
-    println!("\x1b[38;5;46m[SUCCESS] TIMESTAMP VERIFICATION PASSED\x1b[0m");
-    
-    // Verify score (example: maximum valid score is 10000)
-    if score > 10000 {
-        println!("\x1b[38;5;197mSCORE VERIFICATION FAILED: SCORE ANOMALY DETECTED\x1b[0m");
-        println!("\x1b[38;5;197mSECURITY PROTOCOL VIOLATED\x1b[0m");
-        return false;
-    }
-    println!("\x1b[38;5;46m[SUCCESS] SCORE VERIFICATION PASSED\x1b[0m");
-    
-    // Verify game hash (must be 64 characters long)
-    if game_hash.len() != 64 {
-        println!("\x1b[38;5;197mGAME HASH VERIFICATION FAILED: INVALID HASH LENGTH\x1b[0m");
-        println!("\x1b[38;5;197mSECURITY PROTOCOL VIOLATED\x1b[0m");
-        return false;
-    }
-    println!("\x1b[38;5;46m[SUCCESS] GAME HASH VERIFICATION PASSED\x1b[0m");
-    
-    // Run actual SP1 verification
-    println!("\x1b[38;5;213mINITIALIZING SP1 VERIFICATION...\x1b[0m");
-    
-    // Prepare SP1 client - from_env() does not return Result, so no need to match
-    let client = ProverClient::from_env();
-    println!("\x1b[38;5;46m[SUCCESS] SP1 CLIENT INITIALIZED\x1b[0m");
-    
-    // Prepare SP1 program input
+    let current_time = current_unix_time();
+
+    let policy = VerificationPolicy::default();
+
+    // Reject obviously invalid submissions before the expensive key setup
+    check_submission(&submission, current_time, &policy)?;
+
+    let store = ProofStore::open(DEFAULT_STORE_DIR).map_err(|e| VerifyError::Save {
+        path: DEFAULT_STORE_DIR.to_string(),
+        reason: e.to_string(),
+    })?;
+    let prover = ScoreProver::new(policy, observer);
+    prover.prove(&submission, current_time, &store, observer)
+}
+
+/// A single score submission as sent by the game client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    pub timestamp: u64,
+    pub player: String,
+    pub score: u32,
+    pub game_hash: String,
+    #[serde(default)]
+    pub mode: GameMode,
+    /// Per-wave checkpoints, committed by the guest as a Merkle root
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+    /// When the game was played and for how many ticks
+    #[serde(default)]
+    pub session: Session,
+    /// Client event log, analysed for signs of cheating but not proved
+    #[serde(default)]
+    pub events: Vec<GameEvent>,
+    /// Encoded [`Replay`](game_lib::replay::Replay) of the game, not proved
+    /// but kept so a challenge can re-run the simulation
+    #[serde(default)]
+    pub replay: Vec<u8>,
+}
+
+/// Prover with the game score keys already set up, reusable across submissions
+pub struct ScoreProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+    policy: VerificationPolicy,
+}
+
+impl ScoreProver {
+    /// Create the SP1 client and generate the proving and verification keys
+    pub fn new(policy: VerificationPolicy, observer: &dyn ProgressObserver) -> Self {
+        // Prepare SP1 client - from_env() does not return Result, so no need to match
+        let client = ProverClient::from_env();
+
+        // Setup does not return Result
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_SCORE_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk, policy }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Policy submissions are checked against before proving
+    pub fn policy(&self) -> &VerificationPolicy {
+        &self.policy
+    }
+
+    /// Verify a proof against the game score verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client
+            .verify(proof, &self.vk)
+            .map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Run the guest on a submission without proving, returning its public values
+    pub fn execute(
+        &self,
+        submission: &Submission,
+        current_time: u64,
+    ) -> Result<Vec<u8>, VerifyError> {
+        let stdin = guest_stdin(submission, current_time)?;
+        let (public_values, _) = self
+            .client
+            .execute(GAME_SCORE_ELF, &stdin)
+            .run()
+            .map_err(|e| VerifyError::Prover(e.to_string()))?;
+        Ok(public_values.to_vec())
+    }
+
+    /// Check, prove, verify and store a single submission
+    ///
+    /// `current_time` is the reference time the timestamp is checked against,
+    /// both here and inside the guest.
+    pub fn prove(
+        &self,
+        submission: &Submission,
+        current_time: u64,
+        store: &ProofStore,
+        observer: &dyn ProgressObserver,
+    ) -> Result<GameVerificationResult, VerifyError> {
+        check_submission(submission, current_time, &self.policy)?;
+        let stdin = guest_stdin(submission, current_time)?;
+
+        // Execute first so the prove stage can report its cycle count, and
+        // a score the guest rejects is not proved at all
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (public_values, report) = self
+                .client
+                .execute(GAME_SCORE_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            accepted_values(public_values.as_slice())?;
+            let cycles = report.total_instruction_count();
+            Ok((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        // Only a proof of an accepted score goes into the store
+        accepted_values(proof.public_values.as_slice())?;
+
+        let anticheat = (!submission.events.is_empty())
+            .then(|| anticheat::analyze(&submission.events, &Thresholds::default()));
+
+        let entry = run_stage(observer, Stage::Save, || {
+            store
+                .put(&proof, &self.vk, current_unix_time())
+                .and_then(|entry| {
+                    // Kept so inclusion proofs can be made for individual waves later
+                    if !submission.checkpoints.is_empty() {
+                        store.put_checkpoints(&entry.proof_hash, &submission.checkpoints)?;
+                    }
+                    if let Some(report) = &anticheat {
+                        store.put_anticheat(&entry.proof_hash, report)?;
+                    }
+                    // Kept so the score can be re-examined if it is challenged
+                    let stored = StoredSubmission {
+                        reference_time: current_time,
+                        submission: Submission {
+                            events: Vec::new(),
+                            replay: Vec::new(),
+                            ..submission.clone()
+                        },
+                    };
+                    store.put_submission(&entry.proof_hash, &stored)?;
+                    if !submission.replay.is_empty() {
+                        store.put_replay(&entry.proof_hash, &submission.replay)?;
+                    }
+                    Ok((entry, None))
+                })
+                .map_err(|e| VerifyError::Save {
+                    path: store.root().display().to_string(),
+                    reason: e.to_string(),
+                })
+        })?;
+
+        Ok(GameVerificationResult {
+            success: true,
+            timestamp: submission.timestamp,
+            player_name: submission.player.clone(),
+            score: submission.score,
+            game_hash: submission.game_hash.clone(),
+            proof_path: store.proof_path(&entry.proof_hash).display().to_string(),
+            proof_hash: entry.proof_hash,
+            anticheat,
+        })
+    }
+}
+
+/// Decode a score guest's public values, failing unless it committed `verified = 1`
+pub fn accepted_values(public_values: &[u8]) -> Result<GameScorePublicValues, VerifyError> {
+    let values = game_lib::abi::decode(public_values)
+        .map_err(|e| VerifyError::Verification(format!("invalid public values: {}", e)))?;
+    if values.verified != 1 {
+        return Err(VerifyError::GuestRejected);
+    }
+    Ok(values)
+}
+
+/// Guest input for a submission checked against `current_time`
+///
+/// Only the hex encoding of the game hash is checked here, so the guest can
+/// still be run on submissions it will reject.
+pub fn guest_stdin(submission: &Submission, current_time: u64) -> Result<SP1Stdin, VerifyError> {
+    let game_hash_bytes =
+        hex::decode(&submission.game_hash).map_err(|e| VerifyError::HashEncoding(e.to_string()))?;
+
     let mut stdin = SP1Stdin::new();
-    
-    // Write input to SP1 program
-    stdin.write(&timestamp);
-    
-    // Convert player_name to bytes
-    let player_name_bytes = player_name.as_bytes().to_vec();
-    stdin.write(&player_name_bytes);
-    
-    // Write score
-    stdin.write(&score);
-    
-    // Convert game_hash hex to bytes
-    let game_hash_bytes = match hex::decode(game_hash) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            println!("\x1b[38;5;197mFailed to decode game hash: {}\x1b[0m", e);
-            return false;
-        }
-    };
+    stdin.write(&submission.timestamp);
+    stdin.write(&submission.player.as_bytes().to_vec());
+    stdin.write(&submission.score);
     stdin.write(&game_hash_bytes);
-    
     // Write current time to SP1 program for verification
     stdin.write(&current_time);
-    
-    println!("\x1b[38;5;213mCOMPUTING WITNESS...\x1b[0m");
-    
-    // Run program and generate proof (setup does not return Result)
-    println!("\x1b[38;5;213mGENERATING KEYS...\x1b[0m");
-    let (pk, vk) = client.setup(GAME_SCORE_ELF);
-    println!("\x1b[38;5;46m[SUCCESS] PROVING AND VERIFICATION KEYS GENERATED\x1b[0m");
-
-    println!("\x1b[38;5;213mGENERATING PROOF...\x1b[0m");
-    // prove().run() returns Result
-    let proof = match client.prove(&pk, &stdin).run() {
-        Ok(proof) => proof,
-        Err(e) => {
-            println!("\x1b[38;5;197mFailed to generate proof: {}\x1b[0m", e);
-            return false;
-        }
-    };
-    println!("\x1b[38;5;46m[SUCCESS] ZERO-KNOWLEDGE PROOF GENERATED\x1b[0m");
+    stdin.write(&submission.mode.id());
+    stdin.write(&submission.checkpoints);
+    stdin.write(&submission.session);
+    Ok(stdin)
+}
 
-    println!("\x1b[38;5;213mVERIFYING PROOF...\x1b[0m");
-    // verify requires SP1ProofWithPublicValues, not Result
-    if let Err(e) = client.verify(&proof, &vk) {
-        println!("\x1b[38;5;197mProof verification failed: {}\x1b[0m", e);
-        return false;
+/// Write an encoded replay the way the replay guests stream it: the number
+/// of chunks, then the replay in chunks of [`REPLAY_CHUNK_LEN`] bytes
+pub fn write_replay(stdin: &mut SP1Stdin, replay: &[u8]) {
+    let chunks = replay.chunks(REPLAY_CHUNK_LEN);
+    stdin.write(&(chunks.len() as u32));
+    for chunk in chunks {
+        stdin.write(&chunk.to_vec());
     }
-    println!("\x1b[38;5;46m[SUCCESS] PROOF VERIFIED SUCCESSFULLY\x1b[0m");
+}
+
+/// Host-side checks mirroring the guest
+pub fn check_submission(
+    submission: &Submission,
+    current_time: u64,
+    policy: &VerificationPolicy,
+) -> Result<(), VerifyError> {
+    // Timestamp cannot be too far from the current time
+    if current_time.abs_diff(submission.timestamp) > policy.max_timestamp_skew_secs {
+        return Err(VerifyError::TimestampSkew {
+            timestamp: submission.timestamp,
+            current_time,
+            max_skew: policy.max_timestamp_skew_secs,
+        });
+    }
+
+    // Verify score does not exceed the limit for its mode
+    let rules = submission.mode.rules();
+    let max_score = policy.max_score.min(rules.max_score);
+    if submission.score > max_score {
+        return Err(VerifyError::ScoreCap { score: submission.score, max_score });
+    }
+    if !rules.whole_kills(submission.score) {
+        return Err(VerifyError::ScoreStep {
+            score: submission.score,
+            points_per_kill: rules.points_per_kill,
+        });
+    }
+
+    if !checkpoint::sequence_valid(&submission.checkpoints, submission.score, &rules) {
+        return Err(VerifyError::CheckpointSequence);
+    }
+
+    check_session(submission, &rules, policy)?;
+
+    // Verify game hash (must be 32 bytes encoded as hex)
+    decode_game_hash(&submission.game_hash).map(|_| ())
+}
+
+/// Session checks mirroring the guest
+fn check_session(
+    submission: &Submission,
+    rules: &ModeRules,
+    policy: &VerificationPolicy,
+) -> Result<(), VerifyError> {
+    let session = &submission.session;
+    if !session.ordered(submission.timestamp) {
+        return Err(VerifyError::Session(format!(
+            "game from {} to {} must end by its timestamp {}",
+            session.started_at, session.ended_at, submission.timestamp
+        )));
+    }
+    if !session.duration_matches_ticks() {
+        return Err(VerifyError::Session(format!(
+            "game lasted {} seconds but its {} ticks take {} seconds",
+            session.duration_secs(),
+            session.ticks,
+            session.tick_secs()
+        )));
+    }
+    if !session.within_time_limit(rules) {
+        return Err(VerifyError::Session(format!(
+            "{} ticks run past the {} time limit",
+            session.ticks, submission.mode
+        )));
+    }
+
+    let max_score_per_minute = policy.max_score_per_minute.min(rules.max_score_per_minute);
+    if !session.score_rate_valid(submission.score, max_score_per_minute) {
+        return Err(VerifyError::ScoreRate {
+            score: submission.score,
+            ticks: session.ticks,
+            max_score_per_minute,
+        });
+    }
+    Ok(())
+}
+
+/// Current Unix time in seconds
+pub fn current_unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
 
-    // save also available in SP1ProofWithPublicValues, not Result
-    let proof_path = format!("game_score_proof_{}.bin", timestamp);
-    if let Err(e) = proof.save(&proof_path) {
-        println!("\x1b[38;5;197mWarning: Failed to save proof: {}\x1b[0m", e);
-    } else {
-        println!("\x1b[38;5;46mProof saved to: {}\x1b[0m", proof_path);
+/// Decode a hex game hash, requiring exactly 32 bytes
+pub fn decode_game_hash(game_hash: &str) -> Result<Vec<u8>, VerifyError> {
+    let bytes = hex::decode(game_hash).map_err(|e| VerifyError::HashEncoding(e.to_string()))?;
+    if bytes.len() != 32 {
+        return Err(VerifyError::HashEncoding(format!(
+            "expected 32 bytes, got {}",
+            bytes.len()
+        )));
     }
-    true
+    Ok(bytes)
 }
 
 /// Helper function to generate game hash from game data
 pub fn generate_game_hash(player_name: &str, score: u32, timestamp: u64) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(player_name.as_bytes());
-    hasher.update(&score.to_le_bytes());
-    hasher.update(&timestamp.to_le_bytes());
-    
-    let result = hasher.finalize();
-    hex::encode(result)
-} 
\ No newline at end of file
+    hex::encode(game_lib::game_hash(player_name, score, timestamp))
+}
\ No newline at end of file
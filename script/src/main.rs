@@ -1,49 +1,256 @@
-use std::env;
+//! `bladewarrior` command line tool for executing, proving and checking game scores.
+
+mod commands;
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use commands::achievements::AchievementsCommand;
+use commands::anticheat::AnticheatCommand;
+use commands::challenge::ChallengeCommand;
+use commands::checkpoint::CheckpointCommand;
+use commands::config::ConfigCommand;
+use commands::coop::CoopCommand;
+use commands::daily::DailyCommand;
+use commands::rank::RankCommand;
+use commands::store::StoreCommand;
+use commands::tier::TierCommand;
+use commands::tournament::TournamentCommand;
+use commands::vkey::Program;
+use commands::{exit_code, SubmissionArgs};
+use game_lib::GameMode;
+use game_verification_script::batch::BatchOptions;
+use game_verification_script::config::{CliOverrides, Config, ProverMode};
+use game_verification_script::report::CycleBudget;
+
+#[derive(Parser, Debug)]
+#[command(name = "bladewarrior", author, version, about, long_about = None)]
+struct Cli {
+    /// Config file (defaults to bladewarrior.toml if present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Proof store directory
+    #[arg(long, global = true)]
+    store: Option<PathBuf>,
+
+    /// Directory for cached artifacts such as verification keys
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// SP1 prover backend (cpu, cuda, network or mock)
+    #[arg(long, global = true)]
+    prover_mode: Option<ProverMode>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the guest without proving and report cycle counts
+    Execute {
+        #[command(flatten)]
+        submission: SubmissionArgs,
+
+        /// Write the execution report as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+
+        /// Fail if the guest uses more than this many cycles in total
+        #[arg(long)]
+        max_cycles: Option<u64>,
+
+        /// Fail if a cycle-tracker section exceeds its budget, as SECTION=CYCLES
+        #[arg(long = "section-budget", value_parser = CycleBudget::parse_section)]
+        section_budgets: Vec<(String, u64)>,
+    },
+    /// Prove a score, verify the proof and add it to the proof store
+    Prove {
+        #[command(flatten)]
+        submission: SubmissionArgs,
+    },
+    /// Verify a stored proof or proof file against the current program
+    Verify {
+        /// Proof hash in the store, or path to a proof file
+        proof: String,
+    },
+    /// Print or save the verification key of a guest program
+    Vkey {
+        #[arg(value_enum, default_value = "game-score")]
+        program: Program,
+
+        /// Save the full verification key as JSON to this file
+        /// (defaults to <cache-dir>/<program>_vkey.json)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Decode the public values of a stored proof or proof file
+    Inspect {
+        /// Proof hash in the store, or path to a proof file
+        proof: String,
+    },
+    /// Re-execute a submission and check it reproduces a proof's public values
+    Replay {
+        /// Proof hash in the store, or path to a proof file
+        proof: String,
+
+        #[command(flatten)]
+        submission: SubmissionArgs,
+    },
+    /// Prove every submission in a JSONL file
+    Batch {
+        /// JSONL file with one {"timestamp", "player", "score", "game_hash"} object per line
+        input: PathBuf,
+
+        /// Number of submissions to prove concurrently
+        #[arg(long)]
+        parallelism: Option<usize>,
+
+        /// Per-line result file (JSONL)
+        #[arg(long, default_value = "batch_results.jsonl")]
+        results: PathBuf,
+
+        /// Summary file (JSON)
+        #[arg(long, default_value = "batch_summary.json")]
+        summary: PathBuf,
+
+        /// Unix time to check timestamps against instead of the current time,
+        /// for lines without their own `reference_time`
+        #[arg(long)]
+        reference_time: Option<u64>,
+    },
+    /// List, look up and prune stored proofs
+    #[command(subcommand)]
+    Store(StoreCommand),
+    /// Prove or check that a game passed through a checkpoint
+    #[command(subcommand)]
+    Checkpoint(CheckpointCommand),
+    /// Analyse client event logs for signs of cheating
+    #[command(subcommand)]
+    Anticheat(AnticheatCommand),
+    /// Challenge stored scores and check verdicts
+    #[command(subcommand)]
+    Challenge(ChallengeCommand),
+    /// Prove a score reached a tier without revealing the score
+    #[command(subcommand)]
+    Tier(TierCommand),
+    /// Prove a player is in the top K of a leaderboard without revealing their row
+    #[command(subcommand)]
+    Rank(RankCommand),
+    /// Prove the achievements a replayed game earned
+    #[command(subcommand)]
+    Achievements(AchievementsCommand),
+    /// Prove or verify a two-player co-op game
+    #[command(subcommand)]
+    Coop(CoopCommand),
+    /// Run tournaments played on a fixed seed
+    #[command(subcommand)]
+    Tournament(TournamentCommand),
+    /// Play the daily challenge, whose seed is derived from the UTC date
+    #[command(subcommand)]
+    Daily(DailyCommand),
+    /// Write golden test vectors for hashing and ABI encoding
+    Golden {
+        /// Output file
+        #[arg(long, default_value = "../testdata/golden_vectors.json")]
+        out: PathBuf,
+    },
+    /// Show the verified leaderboard for a game mode
+    Leaderboard {
+        /// Game mode (endless, time-attack or hardcore)
+        #[arg(long, default_value = "endless")]
+        mode: GameMode,
+
+        /// Number of rows to show
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// Print the board as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
 
 fn main() {
-    // Parse command line arguments
-    let args: Vec<String> = env::args().collect();
-    
-    // Check if we're in prove mode
-    if args.len() > 1 && args[1] == "--prove" {
-        let mut timestamp = 0;
-        let mut player_name = String::new();
-        let mut score = 0;
-        let mut game_hash = String::new();
-        
-        // Parse arguments
-        for i in 2..args.len() {
-            if args[i] == "--timestamp" && i + 1 < args.len() {
-                timestamp = args[i + 1].parse().unwrap_or(0);
-            } else if args[i] == "--player" && i + 1 < args.len() {
-                player_name = args[i + 1].clone();
-            } else if args[i] == "--score" && i + 1 < args.len() {
-                score = args[i + 1].parse().unwrap_or(0);
-            } else if args[i] == "--game-hash" && i + 1 < args.len() {
-                game_hash = args[i + 1].clone();
-            }
+    // Setup logger
+    sp1_sdk::utils::setup_logger();
+    dotenv::dotenv().ok();
+
+    let cli = Cli::parse();
+
+    let parallelism = match &cli.command {
+        Command::Batch { parallelism, .. } => *parallelism,
+        _ => None,
+    };
+    let overrides = CliOverrides {
+        config_file: cli.config.clone(),
+        prover_mode: cli.prover_mode,
+        store_dir: cli.store.clone(),
+        cache_dir: cli.cache_dir.clone(),
+        parallelism,
+    };
+    let config = match Config::load(&overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(exit_code::USAGE);
+        }
+    };
+    config.apply_prover_mode();
+    let store_path = config.store_dir.value.as_path();
+
+    let result = match cli.command {
+        Command::Execute { submission, json, max_cycles, section_budgets } => {
+            let budget = CycleBudget {
+                max_total: max_cycles,
+                max_sections: section_budgets.into_iter().collect(),
+            };
+            commands::execute::run(&submission, json.as_deref(), &budget)
+        }
+        Command::Prove { submission } => commands::prove::run(&config, &submission),
+        Command::Verify { proof } => commands::verify::run(&config, &proof),
+        Command::Vkey { program, out } => commands::vkey::run(&config, program, out),
+        Command::Inspect { proof } => commands::inspect::run(store_path, &proof),
+        Command::Replay { proof, submission } => {
+            commands::replay::run(store_path, &proof, &submission)
         }
-        
-        // Verify the game score
-        let result = verify_game_score(timestamp, &player_name, score, &game_hash);
-        println!("Verification result: {}", result);
-    } else {
-        println!("Usage: cargo run --bin prove -- --prove --timestamp <timestamp> --player <name> --score <score> --game-hash <hash>");
+        Command::Batch { input, results, summary, reference_time, .. } => commands::batch::run(
+            &config,
+            &input,
+            &BatchOptions {
+                parallelism: config.parallelism.value,
+                reference_time,
+            },
+            &results,
+            &summary,
+        ),
+        Command::Store(command) => commands::store::run(store_path, command),
+        Command::Checkpoint(command) => commands::checkpoint::run(&config, command),
+        Command::Anticheat(command) => commands::anticheat::run(store_path, command),
+        Command::Challenge(command) => commands::challenge::run(&config, command),
+        Command::Tier(command) => commands::tier::run(&config, command),
+        Command::Rank(command) => commands::rank::run(store_path, command),
+        Command::Achievements(command) => commands::achievements::run(command),
+        Command::Coop(command) => commands::coop::run(&config, command),
+        Command::Tournament(command) => commands::tournament::run(&config, store_path, command),
+        Command::Daily(command) => commands::daily::run(&config, store_path, command),
+        Command::Golden { out } => commands::golden::run(&out),
+        Command::Leaderboard { mode, limit, json } => {
+            commands::leaderboard::run(store_path, mode, limit, json)
+        },
+        Command::Config(ConfigCommand::Show) => {
+            commands::config::run(&config);
+            Ok(())
+        }
+    };
+
+    if let Err(failure) = result {
+        eprintln!("Error: {}", failure.message);
+        std::process::exit(failure.code);
     }
 }
-
-// add function for game score verification
-pub fn verify_game_score(timestamp: u64, player_name: &str, score: u32, game_hash: &str) -> bool {
-    // Implement game score verification using SP1
-    // This is a simple example, actual implementation will depend on specific requirements
-    
-    println!("Verifying game score for player: {}", player_name);
-    println!("Score: {}, Timestamp: {}", score, timestamp);
-    println!("Game data hash: {}", game_hash);
-    
-    // Perform verification
-    // ...
-    
-    // For this example, we always return true
-    true
-} 
\ No newline at end of file
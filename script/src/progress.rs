@@ -0,0 +1,146 @@
+//! Stage events emitted while a score moves through the proving pipeline.
+
+use std::fmt;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// Pipeline stage reported to a [`ProgressObserver`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Keygen,
+    Execute,
+    Prove,
+    Verify,
+    Save,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Keygen => "keygen",
+            Stage::Execute => "execute",
+            Stage::Prove => "prove",
+            Stage::Verify => "verify",
+            Stage::Save => "save",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Event sent as a stage starts, completes or fails
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    Started { stage: Stage },
+    /// `cycles` is set for stages that run the guest (execute and prove)
+    Finished { stage: Stage, elapsed: Duration, cycles: Option<u64> },
+    Failed { stage: Stage, elapsed: Duration, reason: String },
+}
+
+impl ProgressEvent {
+    pub fn stage(&self) -> Stage {
+        match self {
+            ProgressEvent::Started { stage }
+            | ProgressEvent::Finished { stage, .. }
+            | ProgressEvent::Failed { stage, .. } => *stage,
+        }
+    }
+}
+
+/// Receives stage events as they happen
+pub trait ProgressObserver {
+    fn on_event(&self, event: &ProgressEvent);
+}
+
+/// Observer that discards every event
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopObserver;
+
+impl ProgressObserver for NoopObserver {
+    fn on_event(&self, _event: &ProgressEvent) {}
+}
+
+/// Forwards events over a channel; a dropped receiver is ignored
+impl ProgressObserver for Sender<ProgressEvent> {
+    fn on_event(&self, event: &ProgressEvent) {
+        let _ = self.send(event.clone());
+    }
+}
+
+/// Prints events to stdout in the verifier's console style
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConsoleObserver;
+
+impl ProgressObserver for ConsoleObserver {
+    fn on_event(&self, event: &ProgressEvent) {
+        match event {
+            ProgressEvent::Started { stage } => {
+                let label = match stage {
+                    Stage::Keygen => "GENERATING KEYS...",
+                    Stage::Execute => "COMPUTING WITNESS...",
+                    Stage::Prove => "GENERATING PROOF...",
+                    Stage::Verify => "VERIFYING PROOF...",
+                    Stage::Save => "SAVING PROOF...",
+                };
+                println!("\x1b[38;5;213m{}\x1b[0m", label);
+            }
+            ProgressEvent::Finished { stage, elapsed, cycles } => {
+                let label = match stage {
+                    Stage::Keygen => "PROVING AND VERIFICATION KEYS GENERATED",
+                    Stage::Execute => "WITNESS COMPUTED",
+                    Stage::Prove => "ZERO-KNOWLEDGE PROOF GENERATED",
+                    Stage::Verify => "PROOF VERIFIED SUCCESSFULLY",
+                    Stage::Save => "PROOF SAVED",
+                };
+                match cycles {
+                    Some(cycles) => println!(
+                        "\x1b[38;5;46m[SUCCESS] {} ({} CYCLES, {:.2?})\x1b[0m",
+                        label, cycles, elapsed
+                    ),
+                    None => println!("\x1b[38;5;46m[SUCCESS] {} ({:.2?})\x1b[0m", label, elapsed),
+                }
+            }
+            ProgressEvent::Failed { stage, reason, .. } => {
+                println!(
+                    "\x1b[38;5;197m[FAILED] {}: {}\x1b[0m",
+                    stage.as_str().to_uppercase(),
+                    reason
+                );
+            }
+        }
+    }
+}
+
+/// Runs one stage, reporting its start and outcome to `observer`
+///
+/// The closure returns the stage output together with its cycle count, if any.
+pub(crate) fn run_stage<T, E: fmt::Display>(
+    observer: &dyn ProgressObserver,
+    stage: Stage,
+    f: impl FnOnce() -> Result<(T, Option<u64>), E>,
+) -> Result<T, E> {
+    observer.on_event(&ProgressEvent::Started { stage });
+    let started = Instant::now();
+    match f() {
+        Ok((value, cycles)) => {
+            observer.on_event(&ProgressEvent::Finished {
+                stage,
+                elapsed: started.elapsed(),
+                cycles,
+            });
+            Ok(value)
+        }
+        Err(e) => {
+            observer.on_event(&ProgressEvent::Failed {
+                stage,
+                elapsed: started.elapsed(),
+                reason: e.to_string(),
+            });
+            Err(e)
+        }
+    }
+}
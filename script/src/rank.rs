@@ -0,0 +1,228 @@
+//! Host support for private top-K rank proofs.
+//!
+//! The leaderboard operator snapshots a board from the proof store with
+//! [`RankedBoard::from_store`] and publishes only its root. Players are then
+//! proved to be in the top `k` of that snapshot by the rank guest, which
+//! keeps their row and every other row private.
+
+use std::convert::Infallible;
+use std::fmt;
+
+use game_lib::checkpoint::InclusionProof;
+use game_lib::rank::{self, BoardError, RankLeaf, RankPublicValues};
+use game_lib::GameMode;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::leaderboard;
+use crate::progress::{run_stage, ProgressObserver, Stage};
+use crate::store::ProofStore;
+use crate::{VerifyError, GAME_RANK_ELF};
+
+/// Snapshot of a verified board, the input to every rank proof against its root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedBoard {
+    pub mode: GameMode,
+    /// Hex root committed to by rank proofs
+    pub root: String,
+    pub leaves: Vec<RankLeaf>,
+}
+
+#[derive(Debug)]
+pub enum RankError {
+    Board(BoardError),
+    /// The board file's root does not match its rows
+    RootMismatch {
+        expected: String,
+        computed: String,
+    },
+    /// A ranked proof is missing from the store or has malformed hashes
+    Entry(String),
+    /// The player has no row on the board
+    NotOnBoard,
+    /// The player's row is ranked below `k`
+    BelowTopK {
+        rank: u32,
+        k: u32,
+    },
+    Verify(VerifyError),
+}
+
+impl fmt::Display for RankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RankError::Board(e) => write!(f, "invalid board: {}", e),
+            RankError::RootMismatch { expected, computed } => {
+                write!(f, "board rows hash to {} but the board records {}", computed, expected)
+            }
+            RankError::Entry(reason) => write!(f, "invalid board entry: {}", reason),
+            RankError::NotOnBoard => write!(f, "player is not on the board"),
+            RankError::BelowTopK { rank, k } => {
+                write!(f, "player is ranked {}, outside the top {}", rank, k)
+            }
+            RankError::Verify(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RankError {}
+
+impl From<VerifyError> for RankError {
+    fn from(e: VerifyError) -> Self {
+        RankError::Verify(e)
+    }
+}
+
+impl From<BoardError> for RankError {
+    fn from(e: BoardError) -> Self {
+        RankError::Board(e)
+    }
+}
+
+fn decode_hash(hash: &str) -> Result<[u8; 32], RankError> {
+    hex::decode(hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| RankError::Entry(format!("{} is not a 32-byte hex hash", hash)))
+}
+
+impl RankedBoard {
+    /// Snapshot the current verified board for `mode`
+    pub fn from_store(store: &ProofStore, mode: GameMode) -> Result<Self, RankError> {
+        let leaves = leaderboard::board(&store.list(), mode, None)
+            .into_iter()
+            .map(|row| {
+                let entry = store
+                    .get(&row.proof_hash)
+                    .ok_or_else(|| RankError::Entry(format!("no proof {}", row.proof_hash)))?;
+                Ok(RankLeaf {
+                    rank: row.rank as u32,
+                    mode: mode.id(),
+                    player_name_hash: decode_hash(&row.player_name_hash)?,
+                    score: row.score,
+                    timestamp: row.timestamp,
+                    game_hash: decode_hash(&entry.game_hash)?,
+                })
+            })
+            .collect::<Result<Vec<_>, RankError>>()?;
+        Ok(Self { mode, root: hex::encode(rank::board_root(&leaves)), leaves })
+    }
+
+    /// Check the rows are a well-formed board hashing to the recorded root
+    pub fn check(&self) -> Result<(), RankError> {
+        rank::check_board(&self.leaves)?;
+        let computed = hex::encode(rank::board_root(&self.leaves));
+        if computed != self.root {
+            return Err(RankError::RootMismatch { expected: self.root.clone(), computed });
+        }
+        Ok(())
+    }
+
+    /// The player's row and its path to the root, if they are within the top `k`
+    pub fn member(&self, player: &str, k: u32) -> Result<(RankLeaf, InclusionProof), RankError> {
+        let player_name_hash = game_lib::player_name_hash(player.as_bytes());
+        let leaf = *self
+            .leaves
+            .iter()
+            .find(|leaf| leaf.player_name_hash == player_name_hash)
+            .ok_or(RankError::NotOnBoard)?;
+        if leaf.rank > k {
+            return Err(RankError::BelowTopK { rank: leaf.rank, k });
+        }
+        let proof = rank::rank_proof(&self.leaves, leaf.rank).ok_or(RankError::NotOnBoard)?;
+        Ok((leaf, proof))
+    }
+}
+
+/// Guest input proving `player` is in the top `k` of `board`
+pub fn rank_stdin(board: &RankedBoard, player: &str, k: u32) -> Result<SP1Stdin, RankError> {
+    board.check()?;
+    let (leaf, proof) = board.member(player, k)?;
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&decode_hash(&board.root)?);
+    stdin.write(&k);
+    stdin.write(&player.as_bytes().to_vec());
+    stdin.write(&leaf);
+    stdin.write(&proof);
+    Ok(stdin)
+}
+
+/// Decode the public values of a rank proof
+pub fn decode_rank_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<RankPublicValues, VerifyError> {
+    rank::decode(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::Verification(format!("invalid rank public values: {}", e)))
+}
+
+/// A generated rank proof with its decoded public values
+pub struct RankProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub values: RankPublicValues,
+}
+
+/// Prover with the rank program keys set up
+pub struct RankProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+}
+
+impl RankProver {
+    /// Create the SP1 client and generate the rank program keys
+    pub fn new(observer: &dyn ProgressObserver) -> Self {
+        let client = ProverClient::from_env();
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_RANK_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Verify a proof against the rank program verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client.verify(proof, &self.vk).map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Prove and verify that `player` is in the top `k` of `board`
+    pub fn prove(
+        &self,
+        board: &RankedBoard,
+        player: &str,
+        k: u32,
+        observer: &dyn ProgressObserver,
+    ) -> Result<RankProof, RankError> {
+        let stdin = rank_stdin(board, player, k)?;
+
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (_, report) = self
+                .client
+                .execute(GAME_RANK_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            let cycles = report.total_instruction_count();
+            Ok::<_, VerifyError>((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        let values = decode_rank_values(&proof)?;
+        Ok(RankProof { proof, values })
+    }
+}
@@ -0,0 +1,161 @@
+//! Execution reports for the guest program, with optional cycle budgets.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+use sp1_sdk::ExecutionReport;
+
+/// Cycle and syscall counts from a single guest execution
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionSummary {
+    pub total_cycles: u64,
+    pub total_syscalls: u64,
+    /// Non-zero syscall counts keyed by syscall name
+    pub syscalls: BTreeMap<String, u64>,
+    /// Cycles spent in each cycle-tracker section
+    /// (`read_input`, `hash_player_name`, `validate`, `abi_encode`)
+    pub sections: BTreeMap<String, u64>,
+}
+
+impl From<&ExecutionReport> for ExecutionSummary {
+    fn from(report: &ExecutionReport) -> Self {
+        let syscalls = report
+            .syscall_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(code, count)| (format!("{:?}", code), *count))
+            .collect();
+        let sections = report
+            .cycle_tracker
+            .iter()
+            .map(|(name, cycles)| (name.clone(), *cycles))
+            .collect();
+
+        Self {
+            total_cycles: report.total_instruction_count(),
+            total_syscalls: report.total_syscall_count(),
+            syscalls,
+            sections,
+        }
+    }
+}
+
+/// Upper bounds on guest cycles, in total and per section
+#[derive(Debug, Clone, Default)]
+pub struct CycleBudget {
+    pub max_total: Option<u64>,
+    pub max_sections: BTreeMap<String, u64>,
+}
+
+/// A single budget that an execution went over
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetViolation {
+    /// `None` for the total cycle budget
+    pub section: Option<String>,
+    pub cycles: u64,
+    pub budget: u64,
+}
+
+impl fmt::Display for BudgetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.section {
+            Some(section) => write!(
+                f,
+                "section {} used {} cycles (budget {})",
+                section, self.cycles, self.budget
+            ),
+            None => write!(f, "total used {} cycles (budget {})", self.cycles, self.budget),
+        }
+    }
+}
+
+/// A section budget names a section the guest never entered
+#[derive(Debug, Clone)]
+pub struct UnknownSection {
+    pub section: String,
+    /// Sections the report does have
+    pub known: Vec<String>,
+}
+
+impl fmt::Display for UnknownSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no cycle-tracker section named {}", self.section)?;
+        if !self.known.is_empty() {
+            write!(f, " (the report has {})", self.known.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownSection {}
+
+impl CycleBudget {
+    /// Parse a `section=cycles` budget as given on the command line
+    pub fn parse_section(spec: &str) -> Result<(String, u64), String> {
+        let (name, cycles) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected SECTION=CYCLES, got {}", spec))?;
+        let cycles = cycles
+            .parse()
+            .map_err(|e| format!("invalid cycle count in {}: {}", spec, e))?;
+        Ok((name.to_string(), cycles))
+    }
+
+    /// Every budget the summary exceeds; empty when within budget
+    ///
+    /// A budgeted section missing from the report is an error rather than
+    /// zero cycles, so a mistyped or renamed section cannot pass unchecked.
+    pub fn check(
+        &self,
+        summary: &ExecutionSummary,
+    ) -> Result<Vec<BudgetViolation>, UnknownSection> {
+        let mut violations = Vec::new();
+        if let Some(budget) = self.max_total {
+            if summary.total_cycles > budget {
+                violations.push(BudgetViolation {
+                    section: None,
+                    cycles: summary.total_cycles,
+                    budget,
+                });
+            }
+        }
+        for (section, &budget) in &self.max_sections {
+            let cycles = *summary
+                .sections
+                .get(section)
+                .ok_or_else(|| UnknownSection {
+                    section: section.clone(),
+                    known: summary.sections.keys().cloned().collect(),
+                })?;
+            if cycles > budget {
+                violations.push(BudgetViolation {
+                    section: Some(section.clone()),
+                    cycles,
+                    budget,
+                });
+            }
+        }
+        Ok(violations)
+    }
+}
+
+/// Execution summary together with the outcome of the budget check
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionOutcome {
+    #[serde(flatten)]
+    pub summary: ExecutionSummary,
+    pub within_budget: bool,
+    pub violations: Vec<BudgetViolation>,
+}
+
+impl ExecutionOutcome {
+    pub fn new(summary: ExecutionSummary, budget: &CycleBudget) -> Result<Self, UnknownSection> {
+        let violations = budget.check(&summary)?;
+        Ok(Self {
+            summary,
+            within_budget: violations.is_empty(),
+            violations,
+        })
+    }
+}
@@ -0,0 +1,492 @@
+//! Content-addressed proof storage.
+//!
+//! Proofs live under `<root>/<proof_hash>.bin`, where the hash is the
+//! SHA-256 of the serialized proof. `<root>/index.json` maps each proof hash
+//! to its decoded public values and the verification key it was made with.
+//! Files about the same game are kept next to the proof as
+//! `<root>/<proof_hash>.<kind>.json`: the submission it was proved from, its
+//! checkpoints and anti-cheat report when given, and challenge verdicts. The
+//! game's encoded replay, when given, is kept as `<root>/<proof_hash>.replay.bin`.
+//!
+//! The CLI and the backend may use the same store at once, so every change to
+//! the index holds an exclusive lock on `<root>/index.lock` and re-reads
+//! `index.json` before applying the change and saving it. Lookups re-read
+//! `index.json` too, so they see entries another process added or removed.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use game_lib::checkpoint::Checkpoint;
+use serde::de::DeserializeOwned;
+use sp1_sdk::{HashableKey, SP1ProofWithPublicValues, SP1VerifyingKey};
+
+use crate::anticheat::AnticheatReport;
+use crate::challenge::{ChallengeOutcome, SignedVerdict};
+use crate::Submission;
+
+/// Default store location, relative to the working directory
+pub const DEFAULT_STORE_DIR: &str = "proofs";
+
+const INDEX_FILE: &str = "index.json";
+const LOCK_FILE: &str = "index.lock";
+
+/// Extensions of the files kept next to a proof
+const SUBMISSION_EXT: &str = "submission.json";
+const CHECKPOINTS_EXT: &str = "checkpoints.json";
+const ANTICHEAT_EXT: &str = "anticheat.json";
+const CHALLENGES_EXT: &str = "challenges.json";
+const REPLAY_EXT: &str = "replay.bin";
+const SIDECAR_EXTS: [&str; 5] =
+    [SUBMISSION_EXT, CHECKPOINTS_EXT, ANTICHEAT_EXT, CHALLENGES_EXT, REPLAY_EXT];
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    /// Proof could not be serialized or deserialized
+    Encoding(String),
+    /// Committed public values are not a valid `GameScoreData`
+    PublicValues(String),
+    NotFound(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "proof store I/O error: {}", e),
+            StoreError::Encoding(reason) => write!(f, "invalid proof encoding: {}", reason),
+            StoreError::PublicValues(reason) => write!(f, "invalid public values: {}", reason),
+            StoreError::NotFound(hash) => write!(f, "no proof with hash {}", hash),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+/// Index record for one stored proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreEntry {
+    pub proof_hash: String,
+    /// Unix time the proof was added to the store
+    pub stored_at: u64,
+    /// Layout of the committed public values (0 for unversioned proofs)
+    #[serde(default)]
+    pub abi_version: u32,
+    #[serde(default)]
+    pub rules_version: u32,
+    /// Game mode id; entries from before modes existed are endless
+    #[serde(default)]
+    pub mode: u32,
+    pub timestamp: u64,
+    pub player_name_hash: String,
+    pub score: u32,
+    pub game_hash: String,
+    pub verified: bool,
+    /// Hex Merkle root of the game's checkpoints (all zeroes if it had none)
+    #[serde(default)]
+    pub checkpoint_root: String,
+    #[serde(default)]
+    pub checkpoint_count: u32,
+    /// Length of the game in seconds and ticks (0 for proofs from before they were committed)
+    #[serde(default)]
+    pub duration_secs: u32,
+    #[serde(default)]
+    pub ticks: u64,
+    /// `bytes32` form of the verification key
+    pub vkey: String,
+    pub size: u64,
+    /// Outcome of the latest challenge against this proof, if any
+    #[serde(default)]
+    pub challenge: Option<ChallengeOutcome>,
+}
+
+/// Submission a stored proof was generated from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSubmission {
+    /// Time the guest checked the timestamp against
+    pub reference_time: u64,
+    #[serde(flatten)]
+    pub submission: Submission,
+}
+
+/// Rules for which entries `prune` removes
+///
+/// An entry is removed if any enabled rule selects it, unless it is
+/// protected by `keep_best_per_player`.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Remove entries stored longer ago than this many seconds
+    pub max_age_secs: Option<u64>,
+    /// Keep only this many most recently stored entries
+    pub max_entries: Option<usize>,
+    /// Remove entries whose guest verification failed
+    pub drop_unverified: bool,
+    /// Never remove each player's highest verified score
+    pub keep_best_per_player: bool,
+}
+
+pub struct ProofStore {
+    root: PathBuf,
+    index: Mutex<BTreeMap<String, StoreEntry>>,
+}
+
+impl ProofStore {
+    /// Open the store at `root`, creating it if needed
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        let index = read_index(&root)?;
+        Ok(Self { root, index: Mutex::new(index) })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of the proof file for `proof_hash`
+    pub fn proof_path(&self, proof_hash: &str) -> PathBuf {
+        self.root.join(format!("{}.bin", proof_hash))
+    }
+
+    /// Store a proof under its content hash; storing the same proof twice is a no-op
+    pub fn put(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VerifyingKey,
+        stored_at: u64,
+    ) -> Result<StoreEntry, StoreError> {
+        let bytes = bincode::serialize(proof).map_err(|e| StoreError::Encoding(e.to_string()))?;
+        let proof_hash = hex::encode(Sha256::digest(&bytes));
+
+        let (_lock, mut index) = self.lock_index()?;
+        if let Some(entry) = index.get(&proof_hash) {
+            return Ok(entry.clone());
+        }
+
+        let values = game_lib::abi::decode(proof.public_values.as_slice())
+            .map_err(|e| StoreError::PublicValues(e.to_string()))?;
+
+        let path = self.proof_path(&proof_hash);
+        write_atomic(&path, &bytes)?;
+
+        let entry = StoreEntry {
+            proof_hash: proof_hash.clone(),
+            stored_at,
+            abi_version: values.abi_version,
+            rules_version: values.rules_version,
+            mode: values.mode,
+            timestamp: values.timestamp,
+            player_name_hash: hex::encode(values.player_name_hash),
+            score: values.score,
+            game_hash: hex::encode(values.game_hash),
+            verified: values.verified == 1,
+            checkpoint_root: hex::encode(values.checkpoint_root),
+            checkpoint_count: values.checkpoint_count,
+            duration_secs: values.duration_secs,
+            ticks: values.ticks,
+            vkey: vk.bytes32(),
+            size: bytes.len() as u64,
+            challenge: None,
+        };
+        index.insert(proof_hash, entry.clone());
+        self.save_index(&index)?;
+        Ok(entry)
+    }
+
+    /// Path of a file kept next to the proof for `proof_hash`
+    fn sidecar_path(&self, proof_hash: &str, ext: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", proof_hash, ext))
+    }
+
+    fn put_sidecar<T: Serialize + ?Sized>(
+        &self,
+        proof_hash: &str,
+        ext: &str,
+        value: &T,
+    ) -> Result<(), StoreError> {
+        if self.get(proof_hash).is_none() {
+            return Err(StoreError::NotFound(proof_hash.to_string()));
+        }
+        self.write_sidecar(proof_hash, ext, value)
+    }
+
+    fn write_sidecar<T: Serialize + ?Sized>(
+        &self,
+        proof_hash: &str,
+        ext: &str,
+        value: &T,
+    ) -> Result<(), StoreError> {
+        let json =
+            serde_json::to_string_pretty(value).map_err(|e| StoreError::Encoding(e.to_string()))?;
+        write_atomic(&self.sidecar_path(proof_hash, ext), json.as_bytes())?;
+        Ok(())
+    }
+
+    fn sidecar<T: DeserializeOwned>(
+        &self,
+        proof_hash: &str,
+        ext: &str,
+    ) -> Result<Option<T>, StoreError> {
+        let path = self.sidecar_path(proof_hash, ext);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| StoreError::Encoding(e.to_string()))
+    }
+
+    /// Keep the submission a stored proof was generated from
+    pub fn put_submission(
+        &self,
+        proof_hash: &str,
+        submission: &StoredSubmission,
+    ) -> Result<(), StoreError> {
+        self.put_sidecar(proof_hash, SUBMISSION_EXT, submission)
+    }
+
+    /// Submission kept for `proof_hash`; proofs stored by older releases have none
+    pub fn submission(&self, proof_hash: &str) -> Result<Option<StoredSubmission>, StoreError> {
+        self.sidecar(proof_hash, SUBMISSION_EXT)
+    }
+
+    /// Record a challenge verdict and mark the entry with its outcome
+    pub fn add_verdict(&self, verdict: &SignedVerdict) -> Result<StoreEntry, StoreError> {
+        let proof_hash = verdict.body.proof_hash.as_str();
+        let (_lock, mut index) = self.lock_index()?;
+        let entry = index
+            .get_mut(proof_hash)
+            .ok_or_else(|| StoreError::NotFound(proof_hash.to_string()))?;
+        let mut verdicts = self.verdicts(proof_hash)?;
+        verdicts.push(verdict.clone());
+        self.write_sidecar(proof_hash, CHALLENGES_EXT, &verdicts)?;
+        entry.challenge = Some(verdict.body.outcome);
+        let entry = entry.clone();
+        self.save_index(&index)?;
+        Ok(entry)
+    }
+
+    /// Every verdict recorded for `proof_hash`, oldest first
+    pub fn verdicts(&self, proof_hash: &str) -> Result<Vec<SignedVerdict>, StoreError> {
+        Ok(self.sidecar(proof_hash, CHALLENGES_EXT)?.unwrap_or_default())
+    }
+
+    /// Keep the checkpoints a stored proof was generated from
+    pub fn put_checkpoints(
+        &self,
+        proof_hash: &str,
+        checkpoints: &[Checkpoint],
+    ) -> Result<(), StoreError> {
+        self.put_sidecar(proof_hash, CHECKPOINTS_EXT, checkpoints)
+    }
+
+    /// Checkpoints kept for `proof_hash`, if any were stored
+    pub fn checkpoints(&self, proof_hash: &str) -> Result<Option<Vec<Checkpoint>>, StoreError> {
+        self.sidecar(proof_hash, CHECKPOINTS_EXT)
+    }
+
+    /// Keep the anti-cheat report of the submission a stored proof was made from
+    pub fn put_anticheat(
+        &self,
+        proof_hash: &str,
+        report: &AnticheatReport,
+    ) -> Result<(), StoreError> {
+        self.put_sidecar(proof_hash, ANTICHEAT_EXT, report)
+    }
+
+    /// Anti-cheat report kept for `proof_hash`, if the submission had an event log
+    pub fn anticheat(&self, proof_hash: &str) -> Result<Option<AnticheatReport>, StoreError> {
+        self.sidecar(proof_hash, ANTICHEAT_EXT)
+    }
+
+    /// Keep the encoded replay of the game a stored proof was made from
+    pub fn put_replay(&self, proof_hash: &str, replay: &[u8]) -> Result<(), StoreError> {
+        if self.get(proof_hash).is_none() {
+            return Err(StoreError::NotFound(proof_hash.to_string()));
+        }
+        write_atomic(&self.sidecar_path(proof_hash, REPLAY_EXT), replay)?;
+        Ok(())
+    }
+
+    /// Encoded replay kept for `proof_hash`, if the submission had one
+    pub fn replay(&self, proof_hash: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        let path = self.sidecar_path(proof_hash, REPLAY_EXT);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(path)?))
+    }
+
+    /// Index entry for `proof_hash`
+    pub fn get(&self, proof_hash: &str) -> Option<StoreEntry> {
+        self.current_index().get(proof_hash).cloned()
+    }
+
+    /// Load the stored proof for `proof_hash`
+    pub fn load(&self, proof_hash: &str) -> Result<SP1ProofWithPublicValues, StoreError> {
+        if self.get(proof_hash).is_none() {
+            return Err(StoreError::NotFound(proof_hash.to_string()));
+        }
+        read_proof(&self.proof_path(proof_hash))
+    }
+
+    /// All entries, oldest first
+    pub fn list(&self) -> Vec<StoreEntry> {
+        oldest_first(&self.current_index())
+    }
+
+    /// Remove entries selected by `policy`, returning what was removed
+    pub fn prune(&self, policy: &RetentionPolicy, now: u64) -> Result<Vec<StoreEntry>, StoreError> {
+        let (_lock, mut index) = self.lock_index()?;
+        let entries = oldest_first(&index);
+
+        let mut protected = BTreeMap::<&str, &StoreEntry>::new();
+        if policy.keep_best_per_player {
+            for entry in entries.iter().filter(|e| e.verified) {
+                let best = protected.entry(entry.player_name_hash.as_str()).or_insert(entry);
+                if entry.score > best.score {
+                    *best = entry;
+                }
+            }
+        }
+        let is_protected = |entry: &StoreEntry| {
+            protected
+                .get(entry.player_name_hash.as_str())
+                .is_some_and(|best| best.proof_hash == entry.proof_hash)
+        };
+
+        // Entries before this position fall outside the newest `max_entries`
+        let over_limit_before = policy
+            .max_entries
+            .map(|max| entries.len().saturating_sub(max))
+            .unwrap_or(0);
+
+        let removed: Vec<StoreEntry> = entries
+            .iter()
+            .enumerate()
+            .filter(|(position, entry)| {
+                let expired = policy
+                    .max_age_secs
+                    .is_some_and(|max_age| now.saturating_sub(entry.stored_at) > max_age);
+                let over_limit = *position < over_limit_before;
+                let unverified = policy.drop_unverified && !entry.verified;
+                (expired || over_limit || unverified) && !is_protected(entry)
+            })
+            .map(|(_, entry)| entry.clone())
+            .collect();
+
+        // The proof goes last, so an entry whose files could not all be
+        // removed still has its proof and stays in the index
+        for entry in &removed {
+            let sidecars = SIDECAR_EXTS.iter().map(|ext| self.sidecar_path(&entry.proof_hash, ext));
+            for path in sidecars.chain(std::iter::once(self.proof_path(&entry.proof_hash))) {
+                match fs::remove_file(path) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                        // Record the entries removed so far before giving up
+                        self.save_index(&index)?;
+                        return Err(e.into());
+                    }
+                    _ => {}
+                }
+            }
+            index.remove(&entry.proof_hash);
+        }
+        self.save_index(&index)?;
+        Ok(removed)
+    }
+
+    /// Lock the index against other processes and reload it, so changes they
+    /// made since this store was opened are kept when it is saved
+    ///
+    /// The lock is released when the returned file is dropped.
+    fn lock_index(
+        &self,
+    ) -> Result<(File, MutexGuard<'_, BTreeMap<String, StoreEntry>>), StoreError> {
+        let mut index = self.index.lock().unwrap();
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.root.join(LOCK_FILE))?;
+        lock.lock()?;
+        *index = read_index(&self.root)?;
+        Ok((lock, index))
+    }
+
+    /// Reload the index for a lookup, keeping the last copy read if
+    /// `index.json` cannot be read
+    ///
+    /// `index.json` is only ever replaced whole, so no file lock is needed to
+    /// read a consistent copy.
+    fn current_index(&self) -> MutexGuard<'_, BTreeMap<String, StoreEntry>> {
+        let mut index = self.index.lock().unwrap();
+        if let Ok(current) = read_index(&self.root) {
+            *index = current;
+        }
+        index
+    }
+
+    fn save_index(&self, index: &BTreeMap<String, StoreEntry>) -> Result<(), StoreError> {
+        let json = serde_json::to_string_pretty(index).map_err(|e| StoreError::Encoding(e.to_string()))?;
+        write_atomic(&self.root.join(INDEX_FILE), json.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn read_index(root: &Path) -> Result<BTreeMap<String, StoreEntry>, StoreError> {
+    let path = root.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|e| StoreError::Encoding(e.to_string()))
+}
+
+fn oldest_first(index: &BTreeMap<String, StoreEntry>) -> Vec<StoreEntry> {
+    let mut entries: Vec<_> = index.values().cloned().collect();
+    entries.sort_by(|a, b| {
+        a.stored_at
+            .cmp(&b.stored_at)
+            .then_with(|| a.proof_hash.cmp(&b.proof_hash))
+    });
+    entries
+}
+
+/// Read a serialized proof from any file, inside the store or not
+pub fn read_proof(path: &Path) -> Result<SP1ProofWithPublicValues, StoreError> {
+    let bytes = fs::read(path)?;
+    bincode::deserialize(&bytes).map_err(|e| StoreError::Encoding(e.to_string()))
+}
+
+/// Write a serialized proof to any file, as `read_proof` reads it
+pub fn write_proof(path: &Path, proof: &SP1ProofWithPublicValues) -> Result<(), StoreError> {
+    let bytes = bincode::serialize(proof).map_err(|e| StoreError::Encoding(e.to_string()))?;
+    write_atomic(path, &bytes)?;
+    Ok(())
+}
+
+/// Write via a temporary file so readers never see a partial file
+///
+/// The temporary name is unique, so writers of the same file never share one.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{:016x}.tmp", rand::random::<u64>()));
+    let tmp = PathBuf::from(tmp);
+    let written = fs::write(&tmp, bytes).and_then(|()| fs::rename(&tmp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    written
+}
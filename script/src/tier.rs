@@ -0,0 +1,135 @@
+//! Host support for tier proofs, which show a score reached a public
+//! threshold without revealing the score.
+//!
+//! Tier proofs commit [`TierPublicValues`] rather than `GameScoreData`, so
+//! they are written to standalone files instead of the proof store.
+
+use std::convert::Infallible;
+
+use game_lib::tier::{self, TierPublicValues};
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::progress::{run_stage, ProgressObserver, Stage};
+use crate::{
+    check_submission, guest_stdin, Submission, VerificationPolicy, VerifyError, GAME_TIER_ELF,
+};
+
+/// Guest input for a tier proof: the score guest's input followed by the thresholds
+pub fn tier_stdin(
+    submission: &Submission,
+    thresholds: &[u32],
+    current_time: u64,
+) -> Result<SP1Stdin, VerifyError> {
+    let mut stdin = guest_stdin(submission, current_time)?;
+    stdin.write(&thresholds.to_vec());
+    Ok(stdin)
+}
+
+/// Host-side checks mirroring the tier guest
+pub fn check_tier_submission(
+    submission: &Submission,
+    thresholds: &[u32],
+    current_time: u64,
+    policy: &VerificationPolicy,
+) -> Result<(), VerifyError> {
+    tier::check_thresholds(thresholds).map_err(|e| VerifyError::TierThresholds(e.to_string()))?;
+    check_submission(submission, current_time, policy)
+}
+
+/// Decode the public values of a tier proof
+pub fn decode_tier_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<TierPublicValues, VerifyError> {
+    tier::decode(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::Verification(format!("invalid tier public values: {}", e)))
+}
+
+/// A generated tier proof with its decoded public values
+pub struct TierProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub values: TierPublicValues,
+}
+
+/// Prover with the tier program keys set up, reusable across submissions
+pub struct TierProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+    policy: VerificationPolicy,
+}
+
+impl TierProver {
+    /// Create the SP1 client and generate the tier program keys
+    pub fn new(policy: VerificationPolicy, observer: &dyn ProgressObserver) -> Self {
+        let client = ProverClient::from_env();
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_TIER_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk, policy }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Verify a proof against the tier program verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client.verify(proof, &self.vk).map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Run the tier guest without proving, returning its public values
+    pub fn execute(
+        &self,
+        submission: &Submission,
+        thresholds: &[u32],
+        current_time: u64,
+    ) -> Result<TierPublicValues, VerifyError> {
+        let stdin = tier_stdin(submission, thresholds, current_time)?;
+        let (public_values, _) = self
+            .client
+            .execute(GAME_TIER_ELF, &stdin)
+            .run()
+            .map_err(|e| VerifyError::Prover(e.to_string()))?;
+        tier::decode(public_values.as_slice())
+            .map_err(|e| VerifyError::Prover(format!("invalid tier public values: {}", e)))
+    }
+
+    /// Check, prove and verify a tier proof for one submission
+    pub fn prove(
+        &self,
+        submission: &Submission,
+        thresholds: &[u32],
+        current_time: u64,
+        observer: &dyn ProgressObserver,
+    ) -> Result<TierProof, VerifyError> {
+        check_tier_submission(submission, thresholds, current_time, &self.policy)?;
+        let stdin = tier_stdin(submission, thresholds, current_time)?;
+
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (_, report) = self
+                .client
+                .execute(GAME_TIER_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            let cycles = report.total_instruction_count();
+            Ok((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        let values = decode_tier_values(&proof)?;
+        Ok(TierProof { proof, values })
+    }
+}
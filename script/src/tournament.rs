@@ -0,0 +1,438 @@
+//! Tournament management: defining events, registering players, collecting
+//! proved results and producing standings.
+//!
+//! Each tournament is a JSON file under `<store>/tournaments/<id>.json`
+//! holding its [`TournamentRules`], who may and who did register, and every
+//! result accepted so far. Entries are proved by the tournament guest, whose
+//! proofs are kept next to the file in `<store>/tournaments/<id>/`. The
+//! guest checks the seed, mode and window; who may play is checked here when
+//! a result is recorded.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use game_lib::replay::Replay;
+use game_lib::sim::Simulation;
+use game_lib::tournament::{self, TournamentPublicValues, TournamentRules};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    EnvProver, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+
+use crate::progress::{run_stage, ProgressObserver, Stage};
+use crate::{write_replay, VerificationPolicy, VerifyError, GAME_TOURNAMENT_ELF};
+
+/// Directory under the proof store that tournaments are kept in
+pub const TOURNAMENTS_DIR: &str = "tournaments";
+
+/// A tournament and everything recorded for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    #[serde(flatten)]
+    pub rules: TournamentRules,
+    /// Players who may register; anyone may if unset
+    #[serde(default)]
+    pub allowed_players: Option<Vec<String>>,
+    #[serde(default)]
+    pub registered: Vec<String>,
+    /// Accepted results in the order they were recorded
+    #[serde(default)]
+    pub results: Vec<TournamentResult>,
+}
+
+/// One proved entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TournamentResult {
+    pub player: String,
+    pub score: u32,
+    pub timestamp: u64,
+    pub replay_hash: String,
+    /// Where the entry's proof was saved
+    pub proof_path: String,
+}
+
+/// One ranked row of the standings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Standing {
+    /// 1-based position
+    pub rank: usize,
+    pub player: String,
+    pub score: u32,
+    pub timestamp: u64,
+    /// Entries the player had accepted
+    pub entries: usize,
+}
+
+/// A game entered into a tournament as submitted by the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentEntry {
+    pub timestamp: u64,
+    pub player: String,
+    /// Encoded [`Replay`]
+    pub replay: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum TournamentError {
+    /// Ids become file names, so they are limited to letters, digits, `-` and `_`
+    InvalidId(String),
+    /// The window does not end after it starts
+    InvalidWindow {
+        starts_at: u64,
+        ends_at: u64,
+    },
+    Exists(String),
+    NotFound(String),
+    NotAllowed(String),
+    AlreadyRegistered(String),
+    NotRegistered(String),
+    /// Registration is closed once the window has ended
+    Closed,
+    /// The proof was made for a different tournament
+    WrongTournament,
+    /// The proof is for a different player than the result is recorded for
+    WrongPlayer,
+    /// A result for the same replay was already recorded
+    AlreadyRecorded(String),
+    /// The guest rejected the entry
+    Rejected,
+    Io(String),
+    Verify(VerifyError),
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TournamentError::InvalidId(id) => {
+                write!(f, "invalid tournament id {:?}: use only letters, digits, '-' and '_'", id)
+            }
+            TournamentError::InvalidWindow { starts_at, ends_at } => {
+                write!(f, "tournament window ends at {} before it starts at {}", ends_at, starts_at)
+            }
+            TournamentError::Exists(id) => write!(f, "tournament {} already exists", id),
+            TournamentError::NotFound(id) => write!(f, "no tournament {}", id),
+            TournamentError::NotAllowed(player) => {
+                write!(f, "{} is not allowed in this tournament", player)
+            }
+            TournamentError::AlreadyRegistered(player) => {
+                write!(f, "{} is already registered", player)
+            }
+            TournamentError::NotRegistered(player) => write!(f, "{} is not registered", player),
+            TournamentError::Closed => write!(f, "tournament has ended"),
+            TournamentError::WrongTournament => write!(f, "proof is for a different tournament"),
+            TournamentError::WrongPlayer => write!(f, "proof is for a different player"),
+            TournamentError::AlreadyRecorded(replay_hash) => {
+                write!(f, "replay {} was already recorded", replay_hash)
+            }
+            TournamentError::Rejected => {
+                write!(f, "proof is valid but the guest rejected the entry")
+            }
+            TournamentError::Io(reason) => write!(f, "tournament I/O error: {}", reason),
+            TournamentError::Verify(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+impl From<VerifyError> for TournamentError {
+    fn from(e: VerifyError) -> Self {
+        TournamentError::Verify(e)
+    }
+}
+
+fn check_id(id: &str) -> Result<(), TournamentError> {
+    let valid =
+        !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(TournamentError::InvalidId(id.to_string()))
+    }
+}
+
+impl Tournament {
+    pub fn new(
+        rules: TournamentRules,
+        allowed_players: Option<Vec<String>>,
+    ) -> Result<Self, TournamentError> {
+        check_id(&rules.id)?;
+        if rules.ends_at <= rules.starts_at {
+            return Err(TournamentError::InvalidWindow {
+                starts_at: rules.starts_at,
+                ends_at: rules.ends_at,
+            });
+        }
+        Ok(Self { rules, allowed_players, registered: Vec::new(), results: Vec::new() })
+    }
+
+    /// Hex tournament id committed by the guest
+    pub fn commitment(&self) -> String {
+        hex::encode(self.rules.commitment())
+    }
+
+    pub fn is_registered(&self, player: &str) -> bool {
+        self.registered.iter().any(|registered| registered == player)
+    }
+
+    /// Register `player`, any time before the window ends
+    pub fn register(&mut self, player: &str, now: u64) -> Result<(), TournamentError> {
+        if now >= self.rules.ends_at {
+            return Err(TournamentError::Closed);
+        }
+        let allowed = match &self.allowed_players {
+            Some(allowed) => allowed.iter().any(|allowed| allowed == player),
+            None => true,
+        };
+        if !allowed {
+            return Err(TournamentError::NotAllowed(player.to_string()));
+        }
+        if self.is_registered(player) {
+            return Err(TournamentError::AlreadyRegistered(player.to_string()));
+        }
+        self.registered.push(player.to_string());
+        Ok(())
+    }
+
+    /// Accept a verified entry for a registered player
+    pub fn record(
+        &mut self,
+        player: &str,
+        values: &TournamentPublicValues,
+        proof_path: &Path,
+    ) -> Result<&TournamentResult, TournamentError> {
+        if values.tournament_id != self.rules.commitment() {
+            return Err(TournamentError::WrongTournament);
+        }
+        if values.verified != 1 {
+            return Err(TournamentError::Rejected);
+        }
+        if values.player_name_hash != game_lib::player_name_hash(player.as_bytes()) {
+            return Err(TournamentError::WrongPlayer);
+        }
+        if !self.is_registered(player) {
+            return Err(TournamentError::NotRegistered(player.to_string()));
+        }
+        let replay_hash = hex::encode(values.replay_hash);
+        if self.results.iter().any(|result| result.replay_hash == replay_hash) {
+            return Err(TournamentError::AlreadyRecorded(replay_hash));
+        }
+        self.results.push(TournamentResult {
+            player: player.to_string(),
+            score: values.score,
+            timestamp: values.timestamp,
+            replay_hash,
+            proof_path: proof_path.display().to_string(),
+        });
+        Ok(&self.results[self.results.len() - 1])
+    }
+
+    /// Each player's best result, best first; ties go to whoever set the score first
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut best = BTreeMap::<&str, (&TournamentResult, usize)>::new();
+        for result in &self.results {
+            let (current, entries) = best.entry(result.player.as_str()).or_insert((result, 0));
+            *entries += 1;
+            let ranks_above = result.score > current.score
+                || (result.score == current.score && result.timestamp < current.timestamp);
+            if ranks_above {
+                *current = result;
+            }
+        }
+
+        let mut rows: Vec<(&TournamentResult, usize)> = best.into_values().collect();
+        rows.sort_by(|(a, _), (b, _)| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.timestamp.cmp(&b.timestamp))
+                .then_with(|| a.player.cmp(&b.player))
+        });
+        rows.into_iter()
+            .enumerate()
+            .map(|(position, (result, entries))| Standing {
+                rank: position + 1,
+                player: result.player.clone(),
+                score: result.score,
+                timestamp: result.timestamp,
+                entries,
+            })
+            .collect()
+    }
+
+    fn path(dir: &Path, id: &str) -> PathBuf {
+        dir.join(format!("{}.json", id))
+    }
+
+    /// Directory the tournament's proofs are saved in
+    pub fn proof_dir(&self, dir: &Path) -> PathBuf {
+        dir.join(&self.rules.id)
+    }
+
+    /// Save a new tournament, refusing to replace an existing one
+    pub fn create(&self, dir: &Path) -> Result<(), TournamentError> {
+        if Self::path(dir, &self.rules.id).exists() {
+            return Err(TournamentError::Exists(self.rules.id.clone()));
+        }
+        self.save(dir)
+    }
+
+    pub fn load(dir: &Path, id: &str) -> Result<Self, TournamentError> {
+        check_id(id)?;
+        let path = Self::path(dir, id);
+        if !path.exists() {
+            return Err(TournamentError::NotFound(id.to_string()));
+        }
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| TournamentError::Io(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| TournamentError::Io(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), TournamentError> {
+        let path = Self::path(dir, &self.rules.id);
+        let json = serde_json::to_string_pretty(self).expect("Failed to serialize tournament");
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&path, json))
+            .map_err(|e| TournamentError::Io(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Guest input for an entry into the tournament with `rules`
+pub fn tournament_stdin(
+    rules: &TournamentRules,
+    entry: &TournamentEntry,
+    current_time: u64,
+) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write(rules);
+    stdin.write(&entry.timestamp);
+    stdin.write(&entry.player.as_bytes().to_vec());
+    stdin.write(&current_time);
+    write_replay(&mut stdin, &entry.replay);
+    stdin
+}
+
+/// Host-side checks mirroring the tournament guest, returning the replayed game
+pub fn check_entry(
+    rules: &TournamentRules,
+    entry: &TournamentEntry,
+    current_time: u64,
+    policy: &VerificationPolicy,
+) -> Result<Simulation, VerifyError> {
+    if current_time.abs_diff(entry.timestamp) > policy.max_timestamp_skew_secs {
+        return Err(VerifyError::TimestampSkew {
+            timestamp: entry.timestamp,
+            current_time,
+            max_skew: policy.max_timestamp_skew_secs,
+        });
+    }
+    if !rules.in_window(entry.timestamp) {
+        return Err(VerifyError::Tournament(format!(
+            "played at {}, outside the window {}..{}",
+            entry.timestamp, rules.starts_at, rules.ends_at
+        )));
+    }
+
+    let replay = Replay::decode(&entry.replay).map_err(|e| VerifyError::Replay(e.to_string()))?;
+    if replay.seed != rules.seed {
+        return Err(VerifyError::Tournament(format!(
+            "played on seed {}, not the tournament seed {}",
+            replay.seed, rules.seed
+        )));
+    }
+    if replay.mode != rules.mode {
+        return Err(VerifyError::Tournament(format!(
+            "played in {}, not {}",
+            replay.mode, rules.mode
+        )));
+    }
+
+    let sim = replay.run();
+    let max_score = policy.max_score.min(rules.mode.rules().max_score);
+    if sim.score() > max_score {
+        return Err(VerifyError::ScoreCap { score: sim.score(), max_score });
+    }
+    Ok(sim)
+}
+
+/// Decode the public values of a tournament proof
+pub fn decode_tournament_values(
+    proof: &SP1ProofWithPublicValues,
+) -> Result<TournamentPublicValues, VerifyError> {
+    tournament::decode(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::Verification(format!("invalid tournament public values: {}", e)))
+}
+
+/// A generated tournament proof with its decoded public values
+pub struct TournamentProof {
+    pub proof: SP1ProofWithPublicValues,
+    pub values: TournamentPublicValues,
+}
+
+/// Prover with the tournament program keys set up, reusable across entries
+pub struct TournamentProver {
+    client: EnvProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+    policy: VerificationPolicy,
+}
+
+impl TournamentProver {
+    /// Create the SP1 client and generate the tournament program keys
+    pub fn new(policy: VerificationPolicy, observer: &dyn ProgressObserver) -> Self {
+        let client = ProverClient::from_env();
+        let (pk, vk) = run_stage(observer, Stage::Keygen, || {
+            Ok::<_, Infallible>((client.setup(GAME_TOURNAMENT_ELF), None))
+        })
+        .unwrap_or_else(|e| match e {});
+
+        Self { client, pk, vk, policy }
+    }
+
+    pub fn verifying_key(&self) -> &SP1VerifyingKey {
+        &self.vk
+    }
+
+    /// Verify a proof against the tournament program verification key
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+        self.client.verify(proof, &self.vk).map_err(|e| VerifyError::Verification(e.to_string()))
+    }
+
+    /// Check, prove and verify one tournament entry
+    pub fn prove(
+        &self,
+        rules: &TournamentRules,
+        entry: &TournamentEntry,
+        current_time: u64,
+        observer: &dyn ProgressObserver,
+    ) -> Result<TournamentProof, VerifyError> {
+        check_entry(rules, entry, current_time, &self.policy)?;
+        let stdin = tournament_stdin(rules, entry, current_time);
+
+        let cycles = run_stage(observer, Stage::Execute, || {
+            let (_, report) = self
+                .client
+                .execute(GAME_TOURNAMENT_ELF, &stdin)
+                .run()
+                .map_err(|e| VerifyError::Prover(e.to_string()))?;
+            let cycles = report.total_instruction_count();
+            Ok((cycles, Some(cycles)))
+        })?;
+
+        let proof = run_stage(observer, Stage::Prove, || {
+            self.client
+                .prove(&self.pk, &stdin)
+                .run()
+                .map(|proof| (proof, Some(cycles)))
+                .map_err(|e| VerifyError::Prover(e.to_string()))
+        })?;
+
+        run_stage(observer, Stage::Verify, || self.verify(&proof).map(|_| ((), None)))?;
+
+        let values = decode_tournament_values(&proof)?;
+        Ok(TournamentProof { proof, values })
+    }
+}
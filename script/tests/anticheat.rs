@@ -0,0 +1,230 @@
+//! Anti-cheat signals over hand-built event logs.
+
+use std::f64::consts::PI;
+
+use game_verification_script::anticheat::{
+    analyze, parse_events, AnticheatReport, GameEvent, Point, Signal, Thresholds, Verdict,
+};
+
+const START: u64 = 1_700_000_000_000;
+
+fn kill(at_ms: u64, player: Option<Point>) -> GameEvent {
+    GameEvent::EnemyKilled {
+        timestamp: START + at_ms,
+        position: Point { x: 400.0, y: 300.0 },
+        score: 100,
+        player,
+    }
+}
+
+/// Player standing `distance` pixels from the kill
+fn player(distance: f64) -> Option<Point> {
+    Some(Point { x: 400.0 + distance, y: 300.0 })
+}
+
+fn shot(at_ms: u64, angle: f64) -> GameEvent {
+    GameEvent::Shoot { timestamp: START + at_ms, position: Point { x: 400.0, y: 300.0 }, angle }
+}
+
+fn wave(at_ms: u64, wave: u32) -> GameEvent {
+    GameEvent::NewWave { timestamp: START + at_ms, wave }
+}
+
+fn signal<'a>(report: &'a AnticheatReport, name: &str) -> &'a Signal {
+    report.signals.iter().find(|signal| signal.name == name).unwrap()
+}
+
+fn triggered(report: &AnticheatReport) -> Vec<&str> {
+    report
+        .signals
+        .iter()
+        .filter(|signal| signal.triggered())
+        .map(|signal| signal.name.as_str())
+        .collect()
+}
+
+/// A minute of play: a kill every two seconds within blade reach and shots
+/// at uneven intervals with the aim drifting
+fn clean_game() -> Vec<GameEvent> {
+    let mut events = vec![wave(0, 1)];
+    events.extend((1..30).map(|i| kill(i * 2000, player(30.0))));
+    events.extend((0..150).map(|i| shot(i * 380 + (i * 37) % 120, (i as f64 * 0.1).sin())));
+    events.push(wave(60_000, 2));
+    events
+}
+
+#[test]
+fn clean_log() {
+    let report = analyze(&clean_game(), &Thresholds::default());
+    assert_eq!(report.event_count, 181);
+    assert_eq!(report.duration_secs, 60.0);
+    assert!(report.signals.iter().all(|signal| signal.value.is_some()), "{:?}", report.signals);
+    assert!(triggered(&report).is_empty(), "{:?}", report.signals);
+    assert_eq!(report.suspicion, 0.0);
+    assert_eq!(report.verdict, Verdict::Clean);
+}
+
+#[test]
+fn high_kill_rate() {
+    // 2.5 kills a second, never more than three in one second
+    let events: Vec<_> = (0..20).map(|i| kill(i * 400, None)).collect();
+    let report = analyze(&events, &Thresholds::default());
+    assert!(signal(&report, "kill_rate").value.unwrap() > 2.0);
+    assert_eq!(signal(&report, "peak_kills_per_second").value, Some(3.0));
+    assert_eq!(triggered(&report), ["kill_rate"]);
+}
+
+#[test]
+fn burst_of_kills() {
+    let mut events: Vec<_> = (0..8).map(|i| kill(i * 100, None)).collect();
+    events.push(wave(60_000, 2));
+    let report = analyze(&events, &Thresholds::default());
+    assert_eq!(signal(&report, "peak_kills_per_second").value, Some(8.0));
+    assert_eq!(triggered(&report), ["peak_kills_per_second"]);
+}
+
+#[test]
+fn peak_kills_counts_the_busiest_second() {
+    // Bursts of 2, 5 and 3 kills; the 5 straddle a second boundary
+    let mut events: Vec<_> =
+        [0, 300, 5_600, 5_900, 6_100, 6_400, 6_599, 6_600, 9_000, 9_500, 9_999]
+            .into_iter()
+            .map(|at_ms| kill(at_ms, None))
+            .collect();
+    events.push(wave(60_000, 2));
+    let report = analyze(&events, &Thresholds::default());
+    assert_eq!(signal(&report, "peak_kills_per_second").value, Some(5.0));
+}
+
+#[test]
+fn peak_kills_at_the_end_of_time() {
+    let events: Vec<_> = (0..4)
+        .map(|i| GameEvent::EnemyKilled {
+            timestamp: u64::MAX - 300 * i,
+            position: Point { x: 400.0, y: 300.0 },
+            score: 100,
+            player: None,
+        })
+        .collect();
+    let report = analyze(&events, &Thresholds::default());
+    assert_eq!(signal(&report, "peak_kills_per_second").value, Some(4.0));
+}
+
+#[test]
+fn aim_flicks() {
+    // Aim snapping round within 10 to 30 milliseconds, at uneven intervals
+    let events: Vec<_> =
+        (0..12).map(|i| shot(i * 20 + (i % 2) * 10, if i % 2 == 0 { 0.0 } else { PI })).collect();
+    let report = analyze(&events, &Thresholds::default());
+    assert_eq!(signal(&report, "aim_flick_ratio").value, Some(1.0));
+    assert_eq!(signal(&report, "aim_flick_ratio").severity, 1.0);
+    assert_eq!(triggered(&report), ["aim_flick_ratio"]);
+}
+
+#[test]
+fn scripted_shot_timing() {
+    let events: Vec<_> = (0..20).map(|i| shot(i * 100, 0.0)).collect();
+    let report = analyze(&events, &Thresholds::default());
+    assert_eq!(signal(&report, "shot_interval_cv").value, Some(0.0));
+    assert_eq!(signal(&report, "shot_interval_cv").severity, 1.0);
+    assert_eq!(triggered(&report), ["shot_interval_cv"]);
+}
+
+#[test]
+fn kill_out_of_reach() {
+    let mut events = clean_game();
+    events.push(kill(30_000, player(200.0)));
+    let report = analyze(&events, &Thresholds::default());
+    let far = signal(&report, "far_kill_ratio");
+    assert_eq!(far.value, Some(1.0 / 30.0));
+    // A single far kill already counts half, at twice the weight of the other four
+    assert_eq!(far.severity, 0.5);
+    assert_eq!(triggered(&report), ["far_kill_ratio"]);
+    assert_eq!(report.suspicion, 1.0 / 6.0);
+    assert_eq!(report.verdict, Verdict::Clean);
+}
+
+#[test]
+fn only_far_kills_are_likely_cheating() {
+    let events: Vec<_> = (0..10).map(|i| kill(i * 5000, player(300.0))).collect();
+    let report = analyze(&events, &Thresholds::default());
+    // Weight 2 of the 4 measured: kill rate, peak kills and far kills
+    assert_eq!(report.suspicion, 0.5);
+    assert_eq!(report.verdict, Verdict::LikelyCheating);
+}
+
+#[test]
+fn too_little_data_is_not_measured() {
+    let thresholds = Thresholds::default();
+
+    // Nine intervals are one short of judging their regularity
+    let events: Vec<_> =
+        (0..thresholds.min_shot_intervals as u64).map(|i| shot(i * 100, 0.0)).collect();
+    let report = analyze(&events, &thresholds);
+    let cv = signal(&report, "shot_interval_cv");
+    assert_eq!((cv.value, cv.severity), (None, 0.0));
+
+    let report = analyze(&[shot(0, 0.0)], &thresholds);
+    assert_eq!(signal(&report, "aim_flick_ratio").value, None);
+
+    // Kills logged without the player's position
+    let report = analyze(&[kill(0, None)], &thresholds);
+    assert_eq!(signal(&report, "far_kill_ratio").value, None);
+
+    let report = analyze(&[], &thresholds);
+    assert_eq!(report.duration_secs, 1.0);
+    assert_eq!(report.suspicion, 0.0);
+    assert_eq!(report.verdict, Verdict::Clean);
+}
+
+#[test]
+fn parse_bare_array_and_game_data() {
+    let events = r#"[
+        {"type": "enemy_killed", "timestamp": 1000, "position": {"x": 1, "y": 2}, "score": 100,
+         "player": {"x": 3, "y": 4}},
+        {"type": "enemy_killed", "timestamp": 1500, "position": {"x": 1, "y": 2}, "score": 200},
+        {"type": "player_hit", "timestamp": 2000, "lives": 2},
+        {"type": "shoot", "timestamp": 2500, "position": {"x": 5, "y": 6}, "angle": 1.5},
+        {"type": "newWave", "timestamp": 3000, "wave": 2},
+        {"type": "pause", "timestamp": 3500}
+    ]"#;
+    let parsed = parse_events(events).unwrap();
+    assert_eq!(
+        parsed,
+        [
+            GameEvent::EnemyKilled {
+                timestamp: 1000,
+                position: Point { x: 1.0, y: 2.0 },
+                score: 100,
+                player: Some(Point { x: 3.0, y: 4.0 }),
+            },
+            GameEvent::EnemyKilled {
+                timestamp: 1500,
+                position: Point { x: 1.0, y: 2.0 },
+                score: 200,
+                player: None,
+            },
+            GameEvent::PlayerHit { timestamp: 2000, lives: 2 },
+            GameEvent::Shoot { timestamp: 2500, position: Point { x: 5.0, y: 6.0 }, angle: 1.5 },
+            GameEvent::NewWave { timestamp: 3000, wave: 2 },
+            GameEvent::Other,
+        ]
+    );
+    assert_eq!(parsed[5].timestamp(), None);
+
+    let game_data = format!(r#"{{"playerName": "alice", "score": 300, "events": {}}}"#, events);
+    assert_eq!(parse_events(&game_data).unwrap(), parsed);
+}
+
+#[test]
+fn parse_rejects_other_shapes() {
+    for json in [
+        "",
+        "{}",
+        r#"{"events": 3}"#,
+        r#"[{"type": "shoot", "timestamp": 1}]"#,
+        r#"[{"type": "player_hit", "timestamp": -1, "lives": 2}]"#,
+    ] {
+        assert!(parse_events(json).is_err(), "{:?} was accepted", json);
+    }
+}
@@ -0,0 +1,197 @@
+//! Reading, proving and summarising JSONL submission files.
+//!
+//! Every line here is rejected before the guest runs, so the reasons are
+//! the same whichever prover the environment selects.
+
+use std::fs;
+use std::sync::OnceLock;
+
+use game_verification_script::batch::{
+    read_submissions, run_batch, write_results, BatchOptions, BatchSummary, LineResult, LineStatus,
+    INVALID_SUBMISSION,
+};
+use game_verification_script::progress::NoopObserver;
+use game_verification_script::store::ProofStore;
+use game_verification_script::{ScoreProver, VerificationPolicy};
+
+const NOW: u64 = 1_700_000_000;
+
+fn prover() -> &'static ScoreProver {
+    static PROVER: OnceLock<ScoreProver> = OnceLock::new();
+    PROVER.get_or_init(|| ScoreProver::new(VerificationPolicy::default(), &NoopObserver))
+}
+
+fn line(timestamp: u64, score: u32, reference_time: Option<u64>) -> String {
+    let mut line = serde_json::json!({
+        "timestamp": timestamp,
+        "player": "alice",
+        "score": score,
+        "game_hash": hex::encode([0u8; 32]),
+    });
+    if let Some(reference_time) = reference_time {
+        line["reference_time"] = reference_time.into();
+    }
+    line.to_string()
+}
+
+/// Batch file of the given lines, returned with the directory holding it
+fn batch_file(lines: &[String]) -> (tempfile::TempDir, std::path::PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("submissions.jsonl");
+    fs::write(&path, lines.join("\n")).unwrap();
+    (dir, path)
+}
+
+fn reasons(results: &[LineResult]) -> Vec<(usize, Option<&str>)> {
+    results.iter().map(|result| (result.line, result.reason.as_deref())).collect()
+}
+
+#[test]
+fn read_skips_blank_lines_and_keeps_line_numbers() {
+    let (_dir, path) = batch_file(&[
+        line(NOW, 100, None),
+        String::new(),
+        "   ".to_string(),
+        line(NOW, 200, Some(NOW + 60)),
+        "{not json".to_string(),
+    ]);
+    let entries = read_submissions(&path).unwrap();
+
+    let lines: Vec<usize> = entries.iter().map(|entry| entry.line).collect();
+    assert_eq!(lines, [1, 4, 5]);
+    assert_eq!(entries[0].submission.as_ref().unwrap().score, 100);
+    assert_eq!(entries[0].reference_time, None);
+    assert_eq!(entries[1].submission.as_ref().unwrap().score, 200);
+    assert_eq!(entries[1].reference_time, Some(NOW + 60));
+    assert!(entries[2].submission.is_err());
+    assert_eq!(entries[2].reference_time, None);
+}
+
+#[test]
+fn each_line_may_give_its_own_reference_time() {
+    let day = 24 * 60 * 60;
+    // Score 15 is not a whole number of kills, which is only checked once
+    // the timestamp has passed
+    let (dir, path) = batch_file(&[
+        line(NOW, 15, None),
+        line(NOW - day, 15, None),
+        line(NOW - day, 15, Some(NOW - day)),
+        line(NOW, 15, Some(NOW - day)),
+    ]);
+    let store = ProofStore::open(dir.path().join("proofs")).unwrap();
+    let entries = read_submissions(&path).unwrap();
+    let options = BatchOptions { parallelism: 1, reference_time: Some(NOW) };
+    let results = run_batch(prover(), &store, &entries, &options, &NoopObserver);
+
+    assert_eq!(
+        reasons(&results),
+        [
+            (1, Some("score_step")),
+            (2, Some("timestamp_skew")),
+            (3, Some("score_step")),
+            (4, Some("timestamp_skew")),
+        ]
+    );
+    assert!(store.list().is_empty());
+}
+
+#[test]
+fn results_keep_input_order_at_any_parallelism() {
+    let mut lines = Vec::new();
+    for i in 0..12 {
+        lines.push(match i % 3 {
+            0 => "[]".to_string(),
+            1 => line(NOW, 15, None),
+            _ => line(NOW, 2_000_000, None),
+        });
+    }
+    let (dir, path) = batch_file(&lines);
+    let store = ProofStore::open(dir.path().join("proofs")).unwrap();
+    let entries = read_submissions(&path).unwrap();
+
+    let expected: Vec<_> = (1..=12)
+        .map(|line| {
+            let reason = match (line - 1) % 3 {
+                0 => INVALID_SUBMISSION,
+                1 => "score_step",
+                _ => "score_cap",
+            };
+            (line, Some(reason))
+        })
+        .collect();
+    for parallelism in [0, 1, 4, 32] {
+        let options = BatchOptions { parallelism, reference_time: Some(NOW) };
+        let results = run_batch(prover(), &store, &entries, &options, &NoopObserver);
+        assert_eq!(reasons(&results), expected, "parallelism {}", parallelism);
+        assert!(results.iter().all(|result| result.status == LineStatus::Failed));
+        assert!(results.iter().all(|result| result.error.is_some()));
+    }
+
+    let options = BatchOptions { parallelism: 4, reference_time: Some(NOW) };
+    assert!(run_batch(prover(), &store, &[], &options, &NoopObserver).is_empty());
+}
+
+fn result(line: usize, reason: Option<&str>) -> LineResult {
+    LineResult {
+        line,
+        status: if reason.is_some() { LineStatus::Failed } else { LineStatus::Ok },
+        proof_hash: reason.is_none().then(|| format!("{:064x}", line)),
+        proof_path: None,
+        suspicion: reason.is_none().then_some(0.25),
+        reason: reason.map(str::to_string),
+        error: reason.map(|reason| format!("{} error", reason)),
+    }
+}
+
+#[test]
+fn summary_counts_failures_by_reason() {
+    let results = [
+        result(1, None),
+        result(2, Some("score_cap")),
+        result(3, Some(INVALID_SUBMISSION)),
+        result(5, Some("score_cap")),
+        result(6, None),
+        result(7, Some("prover")),
+    ];
+    let summary = BatchSummary::from_results(&results);
+    assert_eq!((summary.total, summary.succeeded, summary.failed), (6, 2, 4));
+    let by_reason: Vec<_> = summary
+        .failures_by_reason
+        .iter()
+        .map(|(reason, count)| (reason.as_str(), *count))
+        .collect();
+    assert_eq!(by_reason, [(INVALID_SUBMISSION, 1), ("prover", 1), ("score_cap", 2)]);
+
+    let empty = BatchSummary::from_results(&[]);
+    assert_eq!((empty.total, empty.succeeded, empty.failed), (0, 0, 0));
+    assert!(empty.failures_by_reason.is_empty());
+}
+
+#[test]
+fn results_file_has_one_line_per_result() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("results.jsonl");
+    write_results(&path, &[result(1, None), result(3, Some("score_cap"))]).unwrap();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<serde_json::Value> =
+        contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert!(contents.ends_with('\n'));
+    assert_eq!(
+        lines,
+        [
+            serde_json::json!({
+                "line": 1,
+                "status": "ok",
+                "proof_hash": format!("{:064x}", 1),
+                "suspicion": 0.25,
+            }),
+            serde_json::json!({
+                "line": 3,
+                "status": "failed",
+                "reason": "score_cap",
+                "error": "score_cap error",
+            }),
+        ]
+    );
+}
@@ -0,0 +1,305 @@
+//! Challenge outcomes and verdict signatures.
+
+use std::sync::OnceLock;
+
+use game_lib::checkpoint::merkle_root;
+use game_lib::replay::Replay;
+use game_lib::session::Session;
+use game_lib::sim::{Input, Rng, Simulation, TICK_RATE};
+use game_lib::{GameMode, GameScorePublicValues, ABI_VERSION, RULES_VERSION};
+use game_verification_script::challenge::{
+    public_key_hex, Adjudicator, ChallengeError, ChallengeOutcome, ChallengeRequest, SignedVerdict,
+};
+use game_verification_script::progress::NoopObserver;
+use game_verification_script::store::{ProofStore, StoredSubmission};
+use game_verification_script::{ScoreProver, Submission, VerificationPolicy, GAME_SCORE_ELF};
+use k256::ecdsa::SigningKey;
+use sp1_sdk::{
+    ProverClient, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues,
+    SP1VerifyingKey,
+};
+
+const PLAYER: &str = "alice";
+const STARTED_AT: u64 = 1_700_000_000;
+
+fn prover() -> &'static ScoreProver {
+    static PROVER: OnceLock<ScoreProver> = OnceLock::new();
+    PROVER.get_or_init(|| ScoreProver::new(VerificationPolicy::default(), &NoopObserver))
+}
+
+fn keys() -> &'static (SP1ProvingKey, SP1VerifyingKey) {
+    static KEYS: OnceLock<(SP1ProvingKey, SP1VerifyingKey)> = OnceLock::new();
+    KEYS.get_or_init(|| ProverClient::from_env().setup(GAME_SCORE_ELF))
+}
+
+fn key(byte: u8) -> SigningKey {
+    SigningKey::from_slice(&[byte; 32]).unwrap()
+}
+
+/// Two minutes of endless mode, sweeping the blade round while drifting
+fn replay() -> Replay {
+    let mut rng = Rng::new(11);
+    let mut replay = Replay::new(GameMode::Endless, 11);
+    let mut buttons = 0;
+    for tick in 0..120 * TICK_RATE {
+        if tick % 20 == 0 {
+            buttons = rng.below(16) as u8;
+        }
+        let angle = f64::from(tick) * 0.2;
+        let aim = |v: f64| (v * 1000.0) as i16;
+        replay.inputs.push(Input::new(buttons, aim(angle.cos()), aim(angle.sin())));
+    }
+    replay
+}
+
+/// Public values a faithful proof of `sim` commits
+fn committed(sim: &Simulation) -> GameScorePublicValues {
+    let ended_at = STARTED_AT + sim.tick() / u64::from(TICK_RATE);
+    GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: GameMode::Endless.id(),
+        timestamp: ended_at,
+        player_name_hash: game_lib::player_name_hash(PLAYER.as_bytes()),
+        score: sim.score(),
+        game_hash: game_lib::game_hash(PLAYER, sim.score(), ended_at),
+        verified: 1,
+        checkpoint_root: merkle_root(sim.checkpoints()),
+        checkpoint_count: sim.checkpoints().len() as u32,
+        duration_secs: (ended_at - STARTED_AT) as u32,
+        ticks: sim.tick(),
+    }
+}
+
+/// Submission matching `values`
+fn submission(values: &GameScorePublicValues, sim: &Simulation) -> StoredSubmission {
+    StoredSubmission {
+        reference_time: values.timestamp,
+        submission: Submission {
+            timestamp: values.timestamp,
+            player: PLAYER.to_string(),
+            score: values.score,
+            game_hash: hex::encode(values.game_hash),
+            mode: GameMode::Endless,
+            checkpoints: sim.checkpoints().to_vec(),
+            session: Session {
+                started_at: STARTED_AT,
+                ended_at: values.timestamp,
+                ticks: values.ticks,
+            },
+            events: Vec::new(),
+            replay: Vec::new(),
+        },
+    }
+}
+
+/// Store a mock proof of `values` with its submission and, if given, replay
+fn store_game(
+    store: &ProofStore,
+    values: &GameScorePublicValues,
+    submission: Option<&StoredSubmission>,
+    replay: Option<&[u8]>,
+) -> String {
+    let (pk, vk) = keys();
+    let public_values = SP1PublicValues::from(game_lib::abi::encode(values.clone()).as_slice());
+    let proof =
+        SP1ProofWithPublicValues::create_mock_proof(pk, public_values, SP1ProofMode::Core, "mock");
+    let hash = store.put(&proof, vk, 100).unwrap().proof_hash;
+    if let Some(submission) = submission {
+        store.put_submission(&hash, submission).unwrap();
+    }
+    if let Some(replay) = replay {
+        store.put_replay(&hash, replay).unwrap();
+    }
+    hash
+}
+
+fn challenge(store: &ProofStore, hash: &str) -> Result<SignedVerdict, ChallengeError> {
+    let request = ChallengeRequest {
+        proof_hash: hash.to_string(),
+        challenger: "bob".to_string(),
+        reason: "too good".to_string(),
+    };
+    Adjudicator::new(prover(), key(7)).challenge(store, &request, 2_000_000_000)
+}
+
+/// Outcome of challenging a game committing `values`, checking it is recorded
+fn challenged(
+    values: &GameScorePublicValues,
+    submission: &StoredSubmission,
+    replay: Option<&[u8]>,
+) -> (ChallengeOutcome, Vec<String>) {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let hash = store_game(&store, values, Some(submission), replay);
+    let verdict = challenge(&store, &hash).unwrap();
+    verdict.verify().unwrap();
+    assert_eq!(store.get(&hash).unwrap().challenge, Some(verdict.body.outcome));
+    assert_eq!(store.verdicts(&hash).unwrap(), std::slice::from_ref(&verdict));
+    (verdict.body.outcome, verdict.body.findings)
+}
+
+#[test]
+fn the_test_game_scores() {
+    let sim = replay().run();
+    assert!(sim.score() > 0, "the test replay never kills anything");
+    assert!(!sim.checkpoints().is_empty());
+}
+
+#[test]
+fn faithful_replay_is_upheld() {
+    let replay = replay();
+    let sim = replay.run();
+    let values = committed(&sim);
+    let (outcome, findings) =
+        challenged(&values, &submission(&values, &sim), Some(&replay.encode()));
+    assert_eq!((outcome, findings), (ChallengeOutcome::Upheld, Vec::new()));
+}
+
+#[test]
+fn proof_the_guest_rejected_is_overturned() {
+    let replay = replay();
+    let sim = replay.run();
+    let values = GameScorePublicValues { verified: 0, ..committed(&sim) };
+    let (outcome, findings) =
+        challenged(&values, &submission(&values, &sim), Some(&replay.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Overturned);
+    assert_eq!(findings, ["guest rejected the score when it was proved"]);
+}
+
+#[test]
+fn replay_that_does_not_reproduce_the_game_is_overturned() {
+    let replay = replay();
+    let sim = replay.run();
+    let faithful = committed(&sim);
+    let score = faithful.score + 10;
+    let inflated = GameScorePublicValues {
+        score,
+        game_hash: game_lib::game_hash(PLAYER, score, faithful.timestamp),
+        ..faithful.clone()
+    };
+    let (outcome, findings) =
+        challenged(&inflated, &submission(&inflated, &sim), Some(&replay.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Overturned);
+    assert!(findings.contains(&format!(
+        "replay scores {}, not the committed {}",
+        sim.score(),
+        score
+    )));
+
+    // Ticks and checkpoints are checked too
+    let longer = GameScorePublicValues { ticks: faithful.ticks + 1, ..faithful.clone() };
+    let (outcome, findings) =
+        challenged(&longer, &submission(&faithful, &sim), Some(&replay.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Overturned);
+    assert_eq!(
+        findings,
+        [format!("replay runs {} ticks, not the committed {}", sim.tick(), faithful.ticks + 1)]
+    );
+
+    let rerooted = GameScorePublicValues { checkpoint_root: [1; 32], ..faithful.clone() };
+    let (outcome, findings) =
+        challenged(&rerooted, &submission(&faithful, &sim), Some(&replay.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Overturned);
+    assert_eq!(findings, ["replay checkpoints do not match the committed root"]);
+}
+
+#[test]
+fn unusable_replay_is_overturned() {
+    let replay = replay();
+    let sim = replay.run();
+    let values = committed(&sim);
+    let submission = submission(&values, &sim);
+
+    let (outcome, findings) = challenged(&values, &submission, Some(b"not a replay"));
+    assert_eq!(outcome, ChallengeOutcome::Overturned);
+    assert!(findings[0].starts_with("stored replay is invalid"), "{:?}", findings);
+
+    let hardcore = Replay { mode: GameMode::Hardcore, ..replay };
+    let (outcome, findings) = challenged(&values, &submission, Some(&hardcore.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Overturned);
+    assert_eq!(findings, ["replay is of hardcore, not the committed mode 0"]);
+}
+
+#[test]
+fn mismatch_under_older_rules_is_inconclusive() {
+    let replay = replay();
+    let sim = replay.run();
+    let values =
+        GameScorePublicValues { rules_version: RULES_VERSION - 1, ticks: 1, ..committed(&sim) };
+    let (outcome, findings) =
+        challenged(&values, &submission(&values, &sim), Some(&replay.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Inconclusive);
+    assert_eq!(
+        findings.last().unwrap(),
+        &format!(
+            "proved under rules version {}, which the current simulation cannot reproduce",
+            RULES_VERSION - 1
+        )
+    );
+
+    // A game older rules still reproduce is upheld
+    let values = GameScorePublicValues { rules_version: RULES_VERSION - 1, ..committed(&sim) };
+    let (outcome, _) = challenged(&values, &submission(&values, &sim), Some(&replay.encode()));
+    assert_eq!(outcome, ChallengeOutcome::Upheld);
+}
+
+#[test]
+fn game_without_a_replay_is_inconclusive() {
+    let sim = replay().run();
+    let values = committed(&sim);
+    let (outcome, findings) = challenged(&values, &submission(&values, &sim), None);
+    assert_eq!(outcome, ChallengeOutcome::Inconclusive);
+    assert_eq!(findings, ["no replay was stored with the proof, so the game cannot be re-run"]);
+}
+
+#[test]
+fn proof_without_a_submission_cannot_be_challenged() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let replay = replay();
+    let hash = store_game(&store, &committed(&replay.run()), None, Some(&replay.encode()));
+    assert!(matches!(challenge(&store, &hash), Err(ChallengeError::NoSubmission(_))));
+    assert!(store.verdicts(&hash).unwrap().is_empty());
+    assert!(matches!(challenge(&store, "00"), Err(ChallengeError::Store(_))));
+}
+
+#[test]
+fn verdict_signature_covers_the_body_and_signer() {
+    let replay = replay();
+    let sim = replay.run();
+    let values = committed(&sim);
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let hash =
+        store_game(&store, &values, Some(&submission(&values, &sim)), Some(&replay.encode()));
+    let verdict = challenge(&store, &hash).unwrap();
+    assert_eq!(verdict.signer, public_key_hex(&key(7)));
+    assert!(verdict.verify().is_ok());
+
+    // Round trips through the JSON it is published as
+    let published: SignedVerdict =
+        serde_json::from_str(&serde_json::to_string(&verdict).unwrap()).unwrap();
+    assert!(published.verify().is_ok());
+
+    let mut tampered = verdict.clone();
+    tampered.body.outcome = ChallengeOutcome::Overturned;
+    assert!(matches!(tampered.verify(), Err(ChallengeError::Signature(_))));
+
+    let mut tampered = verdict.clone();
+    tampered.body.findings.push("forged".to_string());
+    assert!(matches!(tampered.verify(), Err(ChallengeError::Signature(_))));
+
+    let mut wrong_signer = verdict.clone();
+    wrong_signer.signer = public_key_hex(&key(8));
+    assert!(matches!(wrong_signer.verify(), Err(ChallengeError::Signature(_))));
+
+    for (signer, signature) in [("zz", verdict.signature.as_str()), (&verdict.signer, "00")] {
+        let malformed = SignedVerdict {
+            signer: signer.to_string(),
+            signature: signature.to_string(),
+            ..verdict.clone()
+        };
+        assert!(matches!(malformed.verify(), Err(ChallengeError::Signature(_))));
+    }
+}
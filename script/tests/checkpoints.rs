@@ -0,0 +1,76 @@
+//! Checkpoint claims checked against a proof's public values.
+
+use game_lib::checkpoint::{merkle_root, Checkpoint};
+use game_lib::{GameMode, GameScorePublicValues, ABI_VERSION, RULES_VERSION};
+use game_verification_script::checkpoints::{claim, verify_claim, ClaimError};
+
+fn game() -> Vec<Checkpoint> {
+    (1..=5)
+        .map(|wave| Checkpoint {
+            wave,
+            tick: u64::from(wave) * 600,
+            score: wave * 150,
+            lives: 3,
+            state_hash: [wave as u8; 32],
+        })
+        .collect()
+}
+
+fn public_values(checkpoints: &[Checkpoint]) -> GameScorePublicValues {
+    GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: GameMode::Endless.id(),
+        timestamp: 1_700_000_000,
+        player_name_hash: [1; 32],
+        score: 900,
+        game_hash: [2; 32],
+        verified: 1,
+        checkpoint_root: merkle_root(checkpoints),
+        checkpoint_count: checkpoints.len() as u32,
+        duration_secs: 60,
+        ticks: 3600,
+    }
+}
+
+#[test]
+fn claims_verify_against_the_committed_root() {
+    let checkpoints = game();
+    let values = public_values(&checkpoints);
+    for wave in 1..=5 {
+        let claim = claim(&checkpoints, &values, wave).unwrap();
+        assert_eq!(claim.checkpoint.wave, wave);
+        verify_claim(&claim, &values).unwrap();
+    }
+    assert!(matches!(claim(&checkpoints, &values, 6), Err(ClaimError::NoCheckpoint { wave: 6 })));
+    assert!(matches!(claim(&checkpoints[..4], &values, 1), Err(ClaimError::RootMismatch { .. })));
+}
+
+#[test]
+fn leaf_count_must_match_the_committed_count() {
+    let checkpoints = game();
+    let values = public_values(&checkpoints);
+    let mut claim = claim(&checkpoints, &values, 1).unwrap();
+    // Eight leaves give the first checkpoint the same path as five do
+    claim.inclusion.leaf_count = 8;
+    assert!(claim.inclusion.verify(&claim.checkpoint, &values.checkpoint_root));
+    assert!(matches!(verify_claim(&claim, &values), Err(ClaimError::InvalidInclusion)));
+}
+
+#[test]
+fn tampered_claims_are_rejected() {
+    let checkpoints = game();
+    let values = public_values(&checkpoints);
+    let honest = claim(&checkpoints, &values, 3).unwrap();
+
+    let mut score = honest.clone();
+    score.checkpoint.score += 150;
+    assert!(verify_claim(&score, &values).is_err());
+
+    let mut sibling = honest.clone();
+    sibling.inclusion.siblings[0][0] ^= 1;
+    assert!(verify_claim(&sibling, &values).is_err());
+
+    let other_game = public_values(&checkpoints[..3]);
+    assert!(verify_claim(&honest, &other_game).is_err());
+}
@@ -0,0 +1,231 @@
+//! Layering and validation of `Config::resolve`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use game_verification_script::config::{CliOverrides, Config, ConfigError, ProverMode, Source};
+use tempfile::TempDir;
+
+/// Config file holding `contents` in a fresh directory
+fn config_file(contents: &str) -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bladewarrior.toml");
+    fs::write(&path, contents).unwrap();
+    (dir, path)
+}
+
+fn cli(path: &Path) -> CliOverrides {
+    CliOverrides { config_file: Some(path.to_path_buf()), ..CliOverrides::default() }
+}
+
+fn resolve(cli: &CliOverrides, env: &[(&str, &str)]) -> Result<Config, ConfigError> {
+    let env: HashMap<String, String> =
+        env.iter().map(|(var, value)| (var.to_string(), value.to_string())).collect();
+    Config::resolve(cli, |var| env.get(var).cloned())
+}
+
+const FILE: &str = r#"
+[prover]
+mode = "mock"
+
+[paths]
+store_dir = "file-proofs"
+
+[policy]
+max_score = 9000
+max_score_per_minute = 12000
+
+[concurrency]
+parallelism = 2
+
+[server]
+bind = "127.0.0.1:4000"
+"#;
+
+#[test]
+fn empty_file_keeps_defaults() {
+    let (_dir, path) = config_file("");
+    let config = resolve(&cli(&path), &[]).unwrap();
+    let defaults = Config::default();
+    assert_eq!(config.file, Some(path));
+    for ((key, value, source), (_, default, _)) in config.rows().into_iter().zip(defaults.rows()) {
+        assert_eq!(value, default, "{}", key);
+        assert_eq!(*source, Source::Default, "{}", key);
+    }
+}
+
+#[test]
+fn file_overrides_defaults() {
+    let (_dir, path) = config_file(FILE);
+    let config = resolve(&cli(&path), &[]).unwrap();
+    let file = Source::File(path);
+    assert_eq!(config.prover_mode.value, ProverMode::Mock);
+    assert_eq!(config.prover_mode.source, file);
+    assert_eq!(config.store_dir.value, PathBuf::from("file-proofs"));
+    assert_eq!(config.max_score.value, 9000);
+    assert_eq!(config.max_score.source, file);
+    assert_eq!(config.max_score_per_minute.value, 12000);
+    assert_eq!(config.parallelism.value, 2);
+    assert_eq!(config.server_bind.value, "127.0.0.1:4000");
+    // Keys the file leaves out keep their defaults
+    assert_eq!(config.cache_dir.source, Source::Default);
+    assert_eq!(config.max_timestamp_skew_secs.source, Source::Default);
+}
+
+#[test]
+fn env_overrides_file_and_cli_overrides_env() {
+    let (_dir, path) = config_file(FILE);
+    let env = [
+        ("BLADEWARRIOR_PROVER_MODE", "cuda"),
+        ("BLADEWARRIOR_STORE_DIR", "env-proofs"),
+        ("BLADEWARRIOR_MAX_SCORE", "8000"),
+        ("BLADEWARRIOR_PARALLELISM", "3"),
+    ];
+    let config = resolve(&cli(&path), &env).unwrap();
+    assert_eq!(config.prover_mode.value, ProverMode::Cuda);
+    assert_eq!(config.prover_mode.source, Source::Env("BLADEWARRIOR_PROVER_MODE"));
+    assert_eq!(config.store_dir.value, PathBuf::from("env-proofs"));
+    assert_eq!(config.max_score.value, 8000);
+    assert_eq!(config.max_score.source, Source::Env("BLADEWARRIOR_MAX_SCORE"));
+    assert_eq!(config.max_score_per_minute.source, Source::File(path.clone()));
+
+    let cli = CliOverrides {
+        prover_mode: Some(ProverMode::Network),
+        store_dir: Some(PathBuf::from("cli-proofs")),
+        ..cli(&path)
+    };
+    let config = resolve(&cli, &env).unwrap();
+    assert_eq!(config.prover_mode.value, ProverMode::Network);
+    assert_eq!(config.prover_mode.source, Source::Cli);
+    assert_eq!(config.store_dir.value, PathBuf::from("cli-proofs"));
+    assert_eq!(config.store_dir.source, Source::Cli);
+    assert_eq!(config.parallelism.value, 3);
+    assert_eq!(config.parallelism.source, Source::Env("BLADEWARRIOR_PARALLELISM"));
+}
+
+#[test]
+fn our_prover_variable_beats_sp1s() {
+    let (_dir, path) = config_file("");
+    let config = resolve(&cli(&path), &[("SP1_PROVER", "mock")]).unwrap();
+    assert_eq!(config.prover_mode.value, ProverMode::Mock);
+    assert_eq!(config.prover_mode.source, Source::Env("SP1_PROVER"));
+
+    let env = [("SP1_PROVER", "mock"), ("BLADEWARRIOR_PROVER_MODE", "cpu")];
+    let config = resolve(&cli(&path), &env).unwrap();
+    assert_eq!(config.prover_mode.value, ProverMode::Cpu);
+}
+
+#[test]
+fn config_file_named_by_env_or_cli() {
+    let (_env_dir, env_path) = config_file("[concurrency]\nparallelism = 4\n");
+    let (_cli_dir, cli_path) = config_file("[concurrency]\nparallelism = 5\n");
+    let env = [("BLADEWARRIOR_CONFIG", env_path.to_str().unwrap())];
+
+    let config = resolve(&CliOverrides::default(), &env).unwrap();
+    assert_eq!(config.file.as_deref(), Some(env_path.as_path()));
+    assert_eq!(config.parallelism.value, 4);
+
+    let config = resolve(&cli(&cli_path), &env).unwrap();
+    assert_eq!(config.file, Some(cli_path));
+    assert_eq!(config.parallelism.value, 5);
+}
+
+#[test]
+fn named_config_file_must_exist() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.toml");
+    assert!(matches!(resolve(&cli(&missing), &[]), Err(ConfigError::File { .. })));
+}
+
+#[test]
+fn invalid_file_is_rejected() {
+    for contents in ["[prover]\nmode = \"gpu\"\n", "[policy]\nmax_score = -1\n", "[extra]\n"] {
+        let (_dir, path) = config_file(contents);
+        assert!(
+            matches!(resolve(&cli(&path), &[]), Err(ConfigError::File { .. })),
+            "{:?} was accepted",
+            contents
+        );
+    }
+}
+
+#[test]
+fn invalid_env_value_names_the_variable() {
+    let (_dir, path) = config_file("");
+    for (var, value) in [
+        ("BLADEWARRIOR_PROVER_MODE", "gpu"),
+        ("SP1_PROVER", "gpu"),
+        ("BLADEWARRIOR_MAX_SCORE", "lots"),
+        ("BLADEWARRIOR_MAX_TIMESTAMP_SKEW_SECS", "-5"),
+        ("BLADEWARRIOR_PARALLELISM", "1.5"),
+    ] {
+        match resolve(&cli(&path), &[(var, value)]) {
+            Err(ConfigError::Env { var: named, .. }) => assert_eq!(named, var),
+            other => panic!("{}={} gave {:?}", var, value, other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn policy_may_be_tightened() {
+    let (_dir, path) = config_file(
+        "[policy]\nmax_timestamp_skew_secs = 60\nmax_score = 100\nmax_score_per_minute = 100\n",
+    );
+    let policy = resolve(&cli(&path), &[]).unwrap().policy();
+    assert_eq!(policy.max_timestamp_skew_secs, 60);
+    assert_eq!(policy.max_score, 100);
+    assert_eq!(policy.max_score_per_minute, 100);
+}
+
+#[test]
+fn policy_looser_than_the_guest_is_rejected() {
+    let loose = [
+        (
+            "max_timestamp_skew_secs",
+            "BLADEWARRIOR_MAX_TIMESTAMP_SKEW_SECS",
+            game_lib::MAX_TIMESTAMP_SKEW_SECS,
+        ),
+        ("max_score", "BLADEWARRIOR_MAX_SCORE", u64::from(game_lib::MAX_SCORE)),
+        (
+            "max_score_per_minute",
+            "BLADEWARRIOR_MAX_SCORE_PER_MINUTE",
+            u64::from(game_lib::MAX_SCORE_PER_MINUTE),
+        ),
+    ];
+    for (key, var, limit) in loose {
+        let (_dir, path) = config_file(&format!("[policy]\n{} = {}\n", key, limit));
+        assert!(resolve(&cli(&path), &[]).is_ok(), "{} at the guest's limit", key);
+
+        let (_dir, path) = config_file(&format!("[policy]\n{} = {}\n", key, limit + 1));
+        match resolve(&cli(&path), &[]) {
+            Err(ConfigError::Value { source, .. }) => assert_eq!(source, Source::File(path)),
+            other => panic!("{} above the limit gave {:?}", key, other.map(|_| ())),
+        }
+
+        let value = (limit + 1).to_string();
+        match resolve(&CliOverrides::default(), &[(var, &value)]) {
+            Err(ConfigError::Value { source, .. }) => assert_eq!(source, Source::Env(var)),
+            other => panic!("{} above the limit gave {:?}", var, other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn server_bind_needs_a_port() {
+    let (_dir, path) = config_file("");
+    for bind in ["0.0.0.0:8080", "localhost:1", "[::]:3000"] {
+        let config = resolve(&cli(&path), &[("BLADEWARRIOR_SERVER_BIND", bind)]).unwrap();
+        assert_eq!(config.server_bind.value, bind);
+    }
+    for bind in ["0.0.0.0", ":3000", "0.0.0.0:", "0.0.0.0:70000", "host:port"] {
+        assert!(
+            matches!(
+                resolve(&cli(&path), &[("BLADEWARRIOR_SERVER_BIND", bind)]),
+                Err(ConfigError::Value { key: "server.bind", .. })
+            ),
+            "{} was accepted",
+            bind
+        );
+    }
+}
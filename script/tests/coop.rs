@@ -0,0 +1,91 @@
+//! Host checks on co-op submissions before the guest runs.
+
+use game_lib::coop::CoopReplay;
+use game_lib::replay::Replay;
+use game_lib::sim::{Input, Rng};
+use game_lib::GameMode;
+use game_verification_script::coop::{check_coop_submission, CoopSubmission};
+use game_verification_script::{VerificationPolicy, VerifyError};
+
+const NOW: u64 = 1_700_000_000;
+
+/// A minute of both players moving and aiming at random
+fn replay() -> CoopReplay {
+    let mut rng = Rng::new(3);
+    let mut replay = CoopReplay::new(GameMode::Endless, 3);
+    for _ in 0..3600 {
+        let mut input = || Input::new(rng.below(16) as u8, rng.below(800) as i16, 300);
+        replay.inputs.push([input(), input()]);
+    }
+    replay
+}
+
+fn submission(players: [&str; 2], replay: Vec<u8>) -> CoopSubmission {
+    CoopSubmission { timestamp: NOW, players: players.map(str::to_string), replay }
+}
+
+#[test]
+fn accepted_game_is_replayed() {
+    let replay = replay();
+    let sim = check_coop_submission(
+        &submission(["alice", "bob"], replay.encode()),
+        NOW + 60,
+        &VerificationPolicy::default(),
+    )
+    .unwrap();
+    assert_eq!(sim, replay.run());
+}
+
+#[test]
+fn each_player_slot_needs_a_different_player() {
+    let result = check_coop_submission(
+        &submission(["alice", "alice"], replay().encode()),
+        NOW,
+        &VerificationPolicy::default(),
+    );
+    assert!(matches!(result, Err(VerifyError::DuplicatePlayer(player)) if player == "alice"));
+
+    // Names that differ only in case are different players
+    let result = check_coop_submission(
+        &submission(["alice", "Alice"], replay().encode()),
+        NOW,
+        &VerificationPolicy::default(),
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn stale_or_malformed_submissions_are_rejected() {
+    let policy = VerificationPolicy::default();
+    let stale = check_coop_submission(
+        &submission(["alice", "bob"], replay().encode()),
+        NOW + policy.max_timestamp_skew_secs + 1,
+        &policy,
+    );
+    assert!(matches!(stale, Err(VerifyError::TimestampSkew { .. })));
+
+    let mut truncated = replay().encode();
+    truncated.pop();
+    let mut single = Replay::new(GameMode::Endless, 3);
+    single.inputs = vec![Input::default(); 4];
+    for bytes in [Vec::new(), truncated, single.encode()] {
+        let result = check_coop_submission(&submission(["alice", "bob"], bytes), NOW, &policy);
+        assert!(matches!(result, Err(VerifyError::Replay(_))), "{:?}", result.err());
+    }
+}
+
+#[test]
+fn either_players_score_is_capped() {
+    let replay = replay();
+    let scores = replay.run().scores();
+    let best = scores.into_iter().max().unwrap();
+    assert!(best > 0, "{:?}", scores);
+
+    let policy = VerificationPolicy { max_score: best - 1, ..VerificationPolicy::default() };
+    let result =
+        check_coop_submission(&submission(["alice", "bob"], replay.encode()), NOW, &policy);
+    assert!(matches!(
+        result,
+        Err(VerifyError::ScoreCap { score, max_score }) if score == best && max_score == best - 1
+    ));
+}
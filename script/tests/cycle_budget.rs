@@ -0,0 +1,66 @@
+//! Cycle budgets checked against execution summaries.
+
+use std::collections::BTreeMap;
+
+use game_verification_script::report::{CycleBudget, ExecutionOutcome, ExecutionSummary};
+
+fn summary() -> ExecutionSummary {
+    ExecutionSummary {
+        total_cycles: 10_000,
+        total_syscalls: 12,
+        syscalls: BTreeMap::new(),
+        sections: [("read_input", 1_000), ("validate", 6_000)]
+            .into_iter()
+            .map(|(name, cycles)| (name.to_string(), cycles))
+            .collect(),
+    }
+}
+
+fn budget(max_total: Option<u64>, sections: &[(&str, u64)]) -> CycleBudget {
+    CycleBudget {
+        max_total,
+        max_sections: sections.iter().map(|(name, cycles)| (name.to_string(), *cycles)).collect(),
+    }
+}
+
+#[test]
+fn within_budget() {
+    let budget = budget(Some(10_000), &[("read_input", 1_000), ("validate", 6_500)]);
+    assert!(budget.check(&summary()).unwrap().is_empty());
+    let outcome = ExecutionOutcome::new(summary(), &budget).unwrap();
+    assert!(outcome.within_budget);
+
+    // No budget at all always passes
+    assert!(CycleBudget::default().check(&summary()).unwrap().is_empty());
+}
+
+#[test]
+fn over_budget() {
+    let budget = budget(Some(9_999), &[("read_input", 2_000), ("validate", 5_999)]);
+    let violations = budget.check(&summary()).unwrap();
+    assert_eq!(violations.len(), 2);
+    assert_eq!(violations[0].section, None);
+    assert_eq!((violations[0].cycles, violations[0].budget), (10_000, 9_999));
+    assert_eq!(violations[1].section.as_deref(), Some("validate"));
+    assert_eq!((violations[1].cycles, violations[1].budget), (6_000, 5_999));
+    assert_eq!(violations[1].to_string(), "section validate used 6000 cycles (budget 5999)");
+
+    let outcome = ExecutionOutcome::new(summary(), &budget).unwrap();
+    assert!(!outcome.within_budget);
+}
+
+#[test]
+fn unknown_section_is_an_error() {
+    let budget = budget(None, &[("read_input", 2_000), ("validat", 1)]);
+    let error = budget.check(&summary()).unwrap_err();
+    assert_eq!(error.section, "validat");
+    assert_eq!(error.known, ["read_input", "validate"]);
+    assert!(ExecutionOutcome::new(summary(), &budget).is_err());
+}
+
+#[test]
+fn parse_section() {
+    assert_eq!(CycleBudget::parse_section("validate=500"), Ok(("validate".to_string(), 500)));
+    assert!(CycleBudget::parse_section("validate").is_err());
+    assert!(CycleBudget::parse_section("validate=lots").is_err());
+}
@@ -0,0 +1,205 @@
+//! Daily challenges, their boards and entry checks.
+
+use std::path::Path;
+
+use game_lib::daily::{DailyPublicValues, DAILY_ABI_VERSION, DAILY_DOMAIN};
+use game_lib::replay::Replay;
+use game_lib::sim::{Input, Rng};
+use game_lib::{GameMode, RULES_VERSION};
+use game_verification_script::daily::{
+    check_daily_entry, DailyBoard, DailyChallenge, DailyEntry, DailyError, DailyStanding,
+};
+use game_verification_script::{VerificationPolicy, VerifyError};
+
+/// 2024-01-01, day 19723
+const NEW_YEAR: u64 = 1_704_067_200;
+const DAY: u64 = 86_400;
+
+fn challenge() -> DailyChallenge {
+    DailyChallenge::for_date("2024-01-01").unwrap()
+}
+
+#[test]
+fn challenge_of_a_date() {
+    let challenge = challenge();
+    assert_eq!(challenge.date, "2024-01-01");
+    assert_eq!(challenge.day, 19_723);
+    assert_eq!(challenge.seed, game_lib::daily::daily_seed(19_723));
+    assert_eq!(challenge.domain, DAILY_DOMAIN);
+    assert_eq!((challenge.starts_at, challenge.ends_at), (NEW_YEAR, NEW_YEAR + DAY));
+    assert_eq!(DailyChallenge::for_day(19_723), challenge);
+
+    for date in ["2024-02-30", "2024-1-1", "yesterday", "1969-12-31"] {
+        assert!(matches!(
+            DailyChallenge::for_date(date),
+            Err(DailyError::InvalidDate(invalid)) if invalid == date
+        ));
+    }
+}
+
+#[test]
+fn challenge_changes_at_utc_midnight() {
+    assert_eq!(DailyChallenge::at(NEW_YEAR - 1).date, "2023-12-31");
+    assert_eq!(DailyChallenge::at(NEW_YEAR), challenge());
+    assert_eq!(DailyChallenge::at(NEW_YEAR + DAY - 1), challenge());
+    assert_eq!(DailyChallenge::at(NEW_YEAR + DAY).date, "2024-01-02");
+    assert_ne!(DailyChallenge::at(NEW_YEAR + DAY).seed, challenge().seed);
+}
+
+/// Public values the guest commits for an accepted game on `day`
+fn values(
+    day: u32,
+    player: &str,
+    mode: GameMode,
+    score: u32,
+    at: u64,
+    replay: u8,
+) -> DailyPublicValues {
+    DailyPublicValues {
+        abi_version: DAILY_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        day,
+        seed: game_lib::daily::daily_seed(day),
+        mode: mode.id(),
+        timestamp: game_lib::daily::day_start(day) + at,
+        player_name_hash: game_lib::player_name_hash(player.as_bytes()),
+        score,
+        replay_hash: [replay; 32],
+        verified: 1,
+    }
+}
+
+fn record(board: &mut DailyBoard, player: &str, mode: GameMode, score: u32, at: u64, replay: u8) {
+    let values = values(board.challenge.day, player, mode, score, at, replay);
+    board.record(player, &values, Path::new("game.bin")).unwrap();
+}
+
+#[test]
+fn record_rejects_games_of_other_days_players_or_repeats() {
+    let mut board = DailyBoard::new(challenge());
+    let path = Path::new("game.bin");
+    let mode = GameMode::Endless;
+
+    for day in [19_722, 19_724] {
+        let result = board.record("alice", &values(day, "alice", mode, 100, 0, 1), path);
+        assert!(matches!(
+            result,
+            Err(DailyError::WrongDay { expected: 19_723, actual }) if actual == day
+        ));
+    }
+    let rejected = DailyPublicValues { verified: 0, ..values(19_723, "alice", mode, 100, 0, 1) };
+    assert!(matches!(board.record("alice", &rejected, path), Err(DailyError::Rejected)));
+    let bobs = values(19_723, "bob", mode, 100, 0, 1);
+    assert!(matches!(board.record("alice", &bobs, path), Err(DailyError::WrongPlayer)));
+    let unknown_mode = DailyPublicValues { mode: 99, ..values(19_723, "alice", mode, 100, 0, 1) };
+    assert!(matches!(
+        board.record("alice", &unknown_mode, path),
+        Err(DailyError::Verify(VerifyError::Verification(_)))
+    ));
+    assert!(board.results.is_empty());
+
+    let result = board.record("alice", &values(19_723, "alice", mode, 100, 5, 1), path).unwrap();
+    assert_eq!((result.mode, result.score, result.timestamp), (mode, 100, NEW_YEAR + 5));
+    let again = board.record("alice", &values(19_723, "alice", mode, 100, 5, 1), path);
+    assert!(
+        matches!(again, Err(DailyError::AlreadyRecorded(hash)) if hash == hex::encode([1; 32]))
+    );
+    assert_eq!(board.results.len(), 1);
+}
+
+#[test]
+fn each_mode_has_its_own_standings() {
+    let mut board = DailyBoard::new(challenge());
+    record(&mut board, "alice", GameMode::Endless, 300, 10, 1);
+    record(&mut board, "bob", GameMode::Endless, 500, 20, 2);
+    record(&mut board, "alice", GameMode::Endless, 500, 15, 3);
+    record(&mut board, "carol", GameMode::Endless, 200, 30, 4);
+    record(&mut board, "carol", GameMode::Hardcore, 900, 40, 5);
+
+    let standing = |rank, player: &str, score, at, attempts| DailyStanding {
+        rank,
+        player: player.to_string(),
+        score,
+        timestamp: NEW_YEAR + at,
+        attempts,
+    };
+    // Alice ties Bob's 500 but set it first
+    assert_eq!(
+        board.standings(GameMode::Endless, None),
+        [
+            standing(1, "alice", 500, 15, 2),
+            standing(2, "bob", 500, 20, 1),
+            standing(3, "carol", 200, 30, 1)
+        ]
+    );
+    assert_eq!(board.standings(GameMode::Endless, Some(1)), [standing(1, "alice", 500, 15, 2)]);
+    assert!(board.standings(GameMode::Endless, Some(0)).is_empty());
+    assert_eq!(board.standings(GameMode::Hardcore, None), [standing(1, "carol", 900, 40, 1)]);
+    assert!(board.standings(GameMode::TimeAttack, None).is_empty());
+}
+
+#[test]
+fn each_day_has_its_own_board() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut today = DailyBoard::load(dir.path(), challenge()).unwrap();
+    assert!(today.results.is_empty());
+    record(&mut today, "alice", GameMode::Endless, 300, 10, 1);
+    today.save(dir.path()).unwrap();
+
+    let tomorrow_challenge = DailyChallenge::at(NEW_YEAR + DAY);
+    let mut tomorrow = DailyBoard::load(dir.path(), tomorrow_challenge.clone()).unwrap();
+    assert!(tomorrow.results.is_empty());
+    record(&mut tomorrow, "bob", GameMode::Endless, 100, 10, 1);
+    tomorrow.save(dir.path()).unwrap();
+
+    let reloaded = DailyBoard::load(dir.path(), challenge()).unwrap();
+    assert_eq!(reloaded.challenge, challenge());
+    assert_eq!(reloaded.results, today.results);
+    let reloaded = DailyBoard::load(dir.path(), tomorrow_challenge).unwrap();
+    assert_eq!(reloaded.results, tomorrow.results);
+
+    assert!(dir.path().join("2024-01-01.json").exists());
+    assert!(dir.path().join("2024-01-02.json").exists());
+    assert_eq!(today.proof_dir(dir.path()), dir.path().join("2024-01-01"));
+}
+
+/// Entry with a short replay on `seed`, played at `timestamp`
+fn entry(seed: u64, timestamp: u64) -> DailyEntry {
+    let mut rng = Rng::new(seed);
+    let mut replay = Replay::new(GameMode::Endless, seed);
+    for _ in 0..600 {
+        replay.inputs.push(Input::new(rng.below(16) as u8, rng.below(800) as i16, 300));
+    }
+    DailyEntry { timestamp, player: "alice".to_string(), replay: replay.encode() }
+}
+
+fn check(entry: &DailyEntry) -> Result<(), VerifyError> {
+    check_daily_entry(&challenge(), entry, entry.timestamp, &VerificationPolicy::default())
+        .map(|_| ())
+}
+
+#[test]
+fn entries_must_be_played_on_the_day_and_its_seed() {
+    let seed = challenge().seed;
+    assert!(check(&entry(seed, NEW_YEAR)).is_ok());
+    assert!(check(&entry(seed, NEW_YEAR + DAY - 1)).is_ok());
+    for outside in [NEW_YEAR - 1, NEW_YEAR + DAY] {
+        assert!(matches!(check(&entry(seed, outside)), Err(VerifyError::Daily(_))), "{}", outside);
+    }
+
+    let tomorrow = DailyChallenge::at(NEW_YEAR + DAY).seed;
+    assert!(matches!(check(&entry(tomorrow, NEW_YEAR)), Err(VerifyError::Daily(_))));
+
+    let mut truncated = entry(seed, NEW_YEAR);
+    truncated.replay.pop();
+    assert!(matches!(check(&truncated), Err(VerifyError::Replay(_))));
+
+    let policy = VerificationPolicy::default();
+    let skewed = check_daily_entry(
+        &challenge(),
+        &entry(seed, NEW_YEAR),
+        NEW_YEAR + policy.max_timestamp_skew_secs + 1,
+        &policy,
+    );
+    assert!(matches!(skewed, Err(VerifyError::TimestampSkew { .. })));
+}
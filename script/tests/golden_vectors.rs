@@ -0,0 +1,129 @@
+//! Pins hashing and ABI layouts to `testdata/golden_vectors.json`.
+//!
+//! If a layout changes on purpose, regenerate the file with
+//! `bladewarrior golden` and bump the relevant version.
+
+use game_lib::checkpoint;
+use game_lib::fixed::Fixed;
+use game_lib::replay::{replay_hash, Replay};
+use game_verification_script::generate_game_hash;
+use game_verification_script::golden::{self, GoldenVectors, PublicValuesVector, GOLDEN_VERSION};
+
+const VECTORS: &str = include_str!("../../testdata/golden_vectors.json");
+
+fn vectors() -> GoldenVectors {
+    serde_json::from_str(VECTORS).expect("golden vectors file parses")
+}
+
+#[test]
+fn file_matches_generator() {
+    let file = vectors();
+    assert_eq!(file.version, GOLDEN_VERSION);
+    assert_eq!(file.abi_version, game_lib::ABI_VERSION);
+    assert_eq!(file.rules_version, game_lib::RULES_VERSION);
+    assert!(file == golden::generate(), "golden vectors are stale; run `bladewarrior golden`");
+}
+
+#[test]
+fn game_hash() {
+    for vector in vectors().game_hash {
+        assert_eq!(hex::encode(&vector.player), vector.player_utf8);
+        assert_eq!(
+            generate_game_hash(&vector.player, vector.score, vector.timestamp),
+            vector.game_hash,
+            "game hash of {:?}",
+            vector.player
+        );
+    }
+}
+
+#[test]
+fn player_name_hash() {
+    for vector in vectors().player_name_hash {
+        let bytes = hex::decode(&vector.player_utf8).unwrap();
+        assert_eq!(bytes, vector.player.as_bytes());
+        assert_eq!(
+            hex::encode(game_lib::player_name_hash(&bytes)),
+            vector.player_name_hash,
+            "player name hash of {:?}",
+            vector.player
+        );
+    }
+}
+
+#[test]
+fn abi_encode_and_decode() {
+    for vector in vectors().abi {
+        let encoded = hex::decode(&vector.encoded).unwrap();
+        assert_eq!(game_lib::abi::version(&encoded).unwrap(), vector.layout, "{}", vector.name);
+
+        let decoded = game_lib::abi::decode(&encoded).unwrap();
+        assert_eq!(PublicValuesVector::from(&decoded), vector.values, "{}", vector.name);
+
+        // Only the current layout is ever written
+        if vector.layout == game_lib::ABI_VERSION {
+            let reencoded = game_lib::abi::encode(vector.values.to_values());
+            assert_eq!(hex::encode(reencoded), vector.encoded, "{}", vector.name);
+        }
+    }
+}
+
+#[test]
+fn checkpoint_root() {
+    for vector in vectors().checkpoint_root {
+        let leaves: Vec<String> =
+            vector.checkpoints.iter().map(|c| hex::encode(c.leaf_hash())).collect();
+        assert_eq!(leaves, vector.leaf_hashes, "{}", vector.name);
+        assert_eq!(
+            hex::encode(checkpoint::merkle_root(&vector.checkpoints)),
+            vector.root,
+            "{}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn daily_seed() {
+    for vector in vectors().daily_seed {
+        assert_eq!(game_lib::daily::date(vector.day), vector.date);
+        assert_eq!(game_lib::daily::parse_date(&vector.date), Some(vector.day));
+        let digest = hex::decode(&vector.digest).unwrap();
+        assert_eq!(u64::from_le_bytes(digest[..8].try_into().unwrap()), vector.seed);
+        assert_eq!(game_lib::daily::daily_seed(vector.day), vector.seed, "{}", vector.date);
+    }
+}
+
+#[test]
+fn fixed_point() {
+    for vector in vectors().fixed {
+        let args: Vec<Fixed> = vector.args.iter().map(|&raw| Fixed::from_raw(raw)).collect();
+        let result = match (vector.op.as_str(), args.as_slice()) {
+            ("sqrt", [value]) => vec![value.sqrt()],
+            ("hypot", [x, y]) => vec![x.hypot(*y)],
+            ("atan2", [y, x]) => vec![y.atan2(*x)],
+            ("sin_cos", [angle]) => {
+                let (sin, cos) = angle.sin_cos();
+                vec![sin, cos]
+            }
+            (op, args) => panic!("unknown vector {}{:?}", op, args),
+        };
+        let result: Vec<i64> = result.iter().map(|value| value.raw()).collect();
+        assert_eq!(result, vector.result, "{}{:?}", vector.op, vector.args);
+    }
+}
+
+#[test]
+fn replay() {
+    for vector in vectors().replay {
+        let encoded = hex::decode(&vector.replay).unwrap();
+        assert_eq!(hex::encode(replay_hash(&encoded)), vector.replay_hash, "{}", vector.name);
+
+        let sim = Replay::decode(&encoded).unwrap().run();
+        assert_eq!(sim.tick(), vector.ticks, "{}", vector.name);
+        assert_eq!(sim.score(), vector.score, "{}", vector.name);
+        assert_eq!(sim.wave(), vector.wave, "{}", vector.name);
+        assert_eq!(sim.lives(), vector.lives, "{}", vector.name);
+        assert_eq!(hex::encode(sim.state_hash()), vector.state_hash, "{}", vector.name);
+    }
+}
@@ -0,0 +1,106 @@
+//! Stage events reported through a channel observer.
+
+use std::sync::mpsc::{self, Receiver};
+
+use game_lib::session::Session;
+use game_lib::GameMode;
+use game_verification_script::progress::{ProgressEvent, Stage};
+use game_verification_script::store::ProofStore;
+use game_verification_script::{
+    ScoreProver, Submission, VerificationPolicy, VerifyError, MAX_TIMESTAMP_SKEW_SECS,
+};
+
+const NOW: u64 = 1_700_000_000;
+
+/// Submission of a minute-long game that ended at `timestamp`
+fn submission(timestamp: u64, score: u32) -> Submission {
+    Submission {
+        timestamp,
+        player: "alice".to_string(),
+        score,
+        game_hash: hex::encode([0u8; 32]),
+        mode: GameMode::Endless,
+        checkpoints: Vec::new(),
+        session: Session { started_at: timestamp - 60, ended_at: timestamp, ticks: 3600 },
+        events: Vec::new(),
+        replay: Vec::new(),
+    }
+}
+
+/// Events received so far, without their timings
+fn received(events: &Receiver<ProgressEvent>) -> Vec<(&'static str, Stage)> {
+    events
+        .try_iter()
+        .map(|event| match event {
+            ProgressEvent::Started { stage } => ("started", stage),
+            ProgressEvent::Finished { stage, cycles, .. } => {
+                assert_eq!(cycles.is_some(), matches!(stage, Stage::Execute | Stage::Prove));
+                ("finished", stage)
+            }
+            ProgressEvent::Failed { stage, .. } => ("failed", stage),
+        })
+        .collect()
+}
+
+#[test]
+fn stages_report_their_start_and_outcome() {
+    // The host allows more skew than the guest, so only the guest rejects
+    let policy = VerificationPolicy {
+        max_timestamp_skew_secs: MAX_TIMESTAMP_SKEW_SECS + 60,
+        ..VerificationPolicy::default()
+    };
+    let (sender, events) = mpsc::channel();
+    let prover = ScoreProver::new(policy, &sender);
+    assert_eq!(received(&events), [("started", Stage::Keygen), ("finished", Stage::Keygen)]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let skewed = submission(NOW - MAX_TIMESTAMP_SKEW_SECS - 30, 100);
+    let error = prover.prove(&skewed, NOW, &store, &sender).unwrap_err();
+
+    // Execute fails, and no later stage starts
+    let events: Vec<ProgressEvent> = events.try_iter().collect();
+    assert_eq!(events.len(), 2, "{:?}", events);
+    assert_eq!(events[0], ProgressEvent::Started { stage: Stage::Execute });
+    match &events[1] {
+        ProgressEvent::Failed { stage: Stage::Execute, reason, .. } => {
+            assert_eq!(*reason, error.to_string());
+        }
+        other => panic!("expected execute to fail, got {:?}", other),
+    }
+    assert!(store.list().is_empty());
+}
+
+#[test]
+fn host_checks_run_before_any_stage() {
+    let (sender, events) = mpsc::channel();
+    let prover = ScoreProver::new(VerificationPolicy::default(), &sender);
+    received(&events);
+
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let result = prover.prove(&submission(NOW, 15), NOW, &store, &sender);
+    assert!(matches!(result, Err(VerifyError::ScoreStep { .. })));
+    assert!(received(&events).is_empty());
+}
+
+#[test]
+fn stage_of_each_event() {
+    let stages = [Stage::Keygen, Stage::Execute, Stage::Prove, Stage::Verify, Stage::Save];
+    let names: Vec<String> = stages.iter().map(Stage::to_string).collect();
+    assert_eq!(names, ["keygen", "execute", "prove", "verify", "save"]);
+
+    let elapsed = std::time::Duration::from_millis(5);
+    let events = [
+        ProgressEvent::Started { stage: Stage::Prove },
+        ProgressEvent::Finished { stage: Stage::Prove, elapsed, cycles: Some(10) },
+        ProgressEvent::Failed { stage: Stage::Save, elapsed, reason: "disk full".to_string() },
+    ];
+    let stages: Vec<Stage> = events.iter().map(ProgressEvent::stage).collect();
+    assert_eq!(stages, [Stage::Prove, Stage::Prove, Stage::Save]);
+
+    // A dropped receiver is ignored
+    let (sender, events) = mpsc::channel();
+    drop(events);
+    let _ = ScoreProver::new(VerificationPolicy::default(), &sender);
+}
@@ -0,0 +1,55 @@
+//! Creating and reloading the verdict signing key.
+
+use std::fs;
+
+use game_verification_script::challenge::{load_or_create_signing_key, public_key_hex};
+
+#[test]
+fn created_once_then_reloaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("keys").join("verdict_signing.key");
+
+    let created = load_or_create_signing_key(&path).unwrap();
+    let reloaded = load_or_create_signing_key(&path).unwrap();
+    assert_eq!(public_key_hex(&created), public_key_hex(&reloaded));
+    assert_eq!(fs::read_to_string(&path).unwrap(), hex::encode(created.to_bytes()));
+
+    // Only the key itself is left behind
+    let files: Vec<_> = fs::read_dir(path.parent().unwrap()).unwrap().collect();
+    assert_eq!(files.len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn readable_by_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("verdict_signing.key");
+    load_or_create_signing_key(&path).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+}
+
+#[test]
+fn concurrent_creators_agree() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("verdict_signing.key");
+    let keys: Vec<String> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8)
+            .map(|_| scope.spawn(|| public_key_hex(&load_or_create_signing_key(&path).unwrap())))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    assert!(keys.iter().all(|key| *key == keys[0]));
+    assert_eq!(public_key_hex(&load_or_create_signing_key(&path).unwrap()), keys[0]);
+}
+
+#[test]
+fn malformed_key_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("verdict_signing.key");
+    fs::write(&path, "not hex").unwrap();
+    assert!(load_or_create_signing_key(&path).is_err());
+    fs::write(&path, "00").unwrap();
+    assert!(load_or_create_signing_key(&path).is_err());
+}
@@ -0,0 +1,381 @@
+//! Storing, annotating and pruning proofs in a `ProofStore`.
+
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use game_lib::checkpoint::Checkpoint;
+use game_lib::{GameMode, GameScorePublicValues, ABI_VERSION, RULES_VERSION};
+use game_verification_script::anticheat::{analyze, Thresholds};
+use game_verification_script::challenge::{ChallengeOutcome, SignedVerdict, VerdictBody};
+use game_verification_script::store::{
+    ProofStore, RetentionPolicy, StoreEntry, StoreError, StoredSubmission,
+};
+use game_verification_script::{Submission, GAME_SCORE_ELF};
+use sp1_sdk::{
+    ProverClient, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues,
+    SP1VerifyingKey,
+};
+
+fn keys() -> &'static (SP1ProvingKey, SP1VerifyingKey) {
+    static KEYS: OnceLock<(SP1ProvingKey, SP1VerifyingKey)> = OnceLock::new();
+    KEYS.get_or_init(|| ProverClient::from_env().setup(GAME_SCORE_ELF))
+}
+
+fn values(player: &str, score: u32, timestamp: u64, verified: bool) -> GameScorePublicValues {
+    GameScorePublicValues {
+        abi_version: ABI_VERSION,
+        rules_version: RULES_VERSION,
+        mode: GameMode::Endless.id(),
+        timestamp,
+        player_name_hash: game_lib::player_name_hash(player.as_bytes()),
+        score,
+        game_hash: game_lib::game_hash(player, score, timestamp),
+        verified: u32::from(verified),
+        checkpoint_root: [0; 32],
+        checkpoint_count: 0,
+        duration_secs: 60,
+        ticks: 3600,
+    }
+}
+
+/// Mock proof committing `values`
+fn proof(values: GameScorePublicValues) -> SP1ProofWithPublicValues {
+    let public_values = SP1PublicValues::from(game_lib::abi::encode(values).as_slice());
+    SP1ProofWithPublicValues::create_mock_proof(
+        &keys().0,
+        public_values,
+        SP1ProofMode::Core,
+        "mock",
+    )
+}
+
+fn put(store: &ProofStore, player: &str, score: u32, verified: bool, stored_at: u64) -> StoreEntry {
+    let proof = proof(values(player, score, stored_at, verified));
+    store.put(&proof, &keys().1, stored_at).unwrap()
+}
+
+fn hashes(entries: &[StoreEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.proof_hash.as_str()).collect()
+}
+
+fn files(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(root)
+        .unwrap()
+        .map(|file| file.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn put_stores_each_proof_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let proof = proof(values("alice", 1200, 1_700_000_000, true));
+
+    let entry = store.put(&proof, &keys().1, 100).unwrap();
+    assert_eq!(entry.score, 1200);
+    assert_eq!(entry.player_name_hash, hex::encode(game_lib::player_name_hash(b"alice")));
+    assert_eq!(entry.timestamp, 1_700_000_000);
+    assert_eq!((entry.abi_version, entry.rules_version), (ABI_VERSION, RULES_VERSION));
+    assert!(entry.verified);
+    assert_eq!(entry.size, fs::metadata(store.proof_path(&entry.proof_hash)).unwrap().len());
+
+    // Storing it again keeps the first entry
+    let again = store.put(&proof, &keys().1, 200).unwrap();
+    assert_eq!(again.stored_at, 100);
+    assert_eq!(hashes(&store.list()), [entry.proof_hash.as_str()]);
+
+    let loaded = store.load(&entry.proof_hash).unwrap();
+    assert_eq!(loaded.public_values.to_vec(), proof.public_values.to_vec());
+
+    let reopened = ProofStore::open(dir.path()).unwrap();
+    assert_eq!(reopened.get(&entry.proof_hash).unwrap().stored_at, 100);
+    assert!(matches!(reopened.load("00"), Err(StoreError::NotFound(_))));
+}
+
+#[test]
+fn put_rejects_undecodable_public_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let public_values = SP1PublicValues::from(&[1, 2, 3][..]);
+    let proof = SP1ProofWithPublicValues::create_mock_proof(
+        &keys().0,
+        public_values,
+        SP1ProofMode::Core,
+        "mock",
+    );
+    assert!(matches!(store.put(&proof, &keys().1, 0), Err(StoreError::PublicValues(_))));
+    assert!(store.list().is_empty());
+}
+
+#[test]
+fn sidecars_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let hash = put(&store, "alice", 1200, true, 100).proof_hash;
+
+    assert!(store.submission(&hash).unwrap().is_none());
+    assert!(store.checkpoints(&hash).unwrap().is_none());
+    assert!(store.anticheat(&hash).unwrap().is_none());
+    assert!(store.verdicts(&hash).unwrap().is_empty());
+    assert!(store.replay(&hash).unwrap().is_none());
+
+    let submission = StoredSubmission {
+        reference_time: 1_700_000_060,
+        submission: Submission {
+            timestamp: 1_700_000_000,
+            player: "alice".to_string(),
+            score: 1200,
+            game_hash: hex::encode(game_lib::game_hash("alice", 1200, 1_700_000_000)),
+            mode: GameMode::Endless,
+            checkpoints: Vec::new(),
+            session: Default::default(),
+            events: Vec::new(),
+            replay: Vec::new(),
+        },
+    };
+    store.put_submission(&hash, &submission).unwrap();
+    let stored = store.submission(&hash).unwrap().unwrap();
+    assert_eq!(stored.reference_time, submission.reference_time);
+    assert_eq!(stored.submission.player, "alice");
+
+    let checkpoints =
+        vec![Checkpoint { wave: 1, tick: 0, score: 0, lives: 3, state_hash: [7; 32] }];
+    store.put_checkpoints(&hash, &checkpoints).unwrap();
+    assert_eq!(store.checkpoints(&hash).unwrap(), Some(checkpoints));
+
+    let report = analyze(&[], &Thresholds::default());
+    store.put_anticheat(&hash, &report).unwrap();
+    assert_eq!(store.anticheat(&hash).unwrap().unwrap().verdict, report.verdict);
+
+    store.put_replay(&hash, b"BWRP replay").unwrap();
+    assert_eq!(store.replay(&hash).unwrap().unwrap(), b"BWRP replay");
+
+    // Only proofs in the store can have sidecars
+    assert!(matches!(store.put_submission("00", &submission), Err(StoreError::NotFound(_))));
+    assert!(matches!(store.put_replay("00", &[1]), Err(StoreError::NotFound(_))));
+}
+
+fn verdict(proof_hash: &str, outcome: ChallengeOutcome) -> SignedVerdict {
+    SignedVerdict {
+        body: VerdictBody {
+            proof_hash: proof_hash.to_string(),
+            challenger: "bob".to_string(),
+            reason: "too good".to_string(),
+            outcome,
+            findings: Vec::new(),
+            rules_version: RULES_VERSION,
+            decided_at: 500,
+        },
+        signer: String::new(),
+        signature: String::new(),
+    }
+}
+
+#[test]
+fn verdicts_accumulate_and_mark_the_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let hash = put(&store, "alice", 1200, true, 100).proof_hash;
+
+    let entry = store.add_verdict(&verdict(&hash, ChallengeOutcome::Upheld)).unwrap();
+    assert_eq!(entry.challenge, Some(ChallengeOutcome::Upheld));
+    let entry = store.add_verdict(&verdict(&hash, ChallengeOutcome::Overturned)).unwrap();
+    assert_eq!(entry.challenge, Some(ChallengeOutcome::Overturned));
+
+    let outcomes: Vec<_> =
+        store.verdicts(&hash).unwrap().into_iter().map(|verdict| verdict.body.outcome).collect();
+    assert_eq!(outcomes, [ChallengeOutcome::Upheld, ChallengeOutcome::Overturned]);
+    let reopened = ProofStore::open(dir.path()).unwrap();
+    assert_eq!(reopened.get(&hash).unwrap().challenge, Some(ChallengeOutcome::Overturned));
+
+    assert!(matches!(
+        store.add_verdict(&verdict("00", ChallengeOutcome::Upheld)),
+        Err(StoreError::NotFound(_))
+    ));
+}
+
+#[test]
+fn prune_by_age_count_and_verification() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let old = put(&store, "alice", 100, true, 100);
+    let unverified = put(&store, "bob", 200, false, 200);
+    let newer = put(&store, "carol", 300, true, 300);
+    let newest = put(&store, "dave", 400, true, 400);
+
+    let removed = store.prune(&RetentionPolicy::default(), 1000).unwrap();
+    assert!(removed.is_empty());
+
+    let by_age = RetentionPolicy { max_age_secs: Some(850), ..RetentionPolicy::default() };
+    assert_eq!(hashes(&store.prune(&by_age, 1000).unwrap()), [old.proof_hash.as_str()]);
+
+    let by_verification = RetentionPolicy { drop_unverified: true, ..RetentionPolicy::default() };
+    let removed = store.prune(&by_verification, 1000).unwrap();
+    assert_eq!(hashes(&removed), [unverified.proof_hash.as_str()]);
+
+    let by_count = RetentionPolicy { max_entries: Some(1), ..RetentionPolicy::default() };
+    assert_eq!(hashes(&store.prune(&by_count, 1000).unwrap()), [newer.proof_hash.as_str()]);
+    assert_eq!(hashes(&store.list()), [newest.proof_hash.as_str()]);
+}
+
+#[test]
+fn prune_keeps_each_players_best_verified_score() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let best = put(&store, "alice", 900, true, 100);
+    let worse = put(&store, "alice", 500, true, 200);
+    let unverified_higher = put(&store, "alice", 1000, false, 300);
+    let only = put(&store, "bob", 100, true, 400);
+
+    let policy = RetentionPolicy {
+        max_age_secs: Some(0),
+        keep_best_per_player: true,
+        ..RetentionPolicy::default()
+    };
+    let removed = store.prune(&policy, 1000).unwrap();
+    assert_eq!(
+        hashes(&removed),
+        [worse.proof_hash.as_str(), unverified_higher.proof_hash.as_str()]
+    );
+    assert_eq!(hashes(&store.list()), [best.proof_hash.as_str(), only.proof_hash.as_str()]);
+}
+
+#[test]
+fn prune_removes_proof_files_and_sidecars() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let kept = put(&store, "alice", 100, true, 100).proof_hash;
+    let pruned = put(&store, "bob", 200, false, 200).proof_hash;
+    for hash in [&kept, &pruned] {
+        store.put_checkpoints(hash, &[]).unwrap();
+        store.put_anticheat(hash, &analyze(&[], &Thresholds::default())).unwrap();
+        store.add_verdict(&verdict(hash, ChallengeOutcome::Upheld)).unwrap();
+        store.put_replay(hash, &[1, 2, 3]).unwrap();
+    }
+
+    let policy = RetentionPolicy { drop_unverified: true, ..RetentionPolicy::default() };
+    store.prune(&policy, 1000).unwrap();
+    assert!(files(dir.path()).iter().all(|file| !file.starts_with(&pruned)));
+    assert_eq!(
+        files(dir.path()),
+        [
+            format!("{}.anticheat.json", kept),
+            format!("{}.bin", kept),
+            format!("{}.challenges.json", kept),
+            format!("{}.checkpoints.json", kept),
+            format!("{}.replay.bin", kept),
+            "index.json".to_string(),
+            "index.lock".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn handles_on_one_store_keep_each_others_changes() {
+    // Two handles stand in for the CLI and the backend sharing a store
+    let dir = tempfile::tempdir().unwrap();
+    let cli = ProofStore::open(dir.path()).unwrap();
+    let backend = ProofStore::open(dir.path()).unwrap();
+
+    let first = put(&cli, "alice", 100, true, 100);
+    let second = put(&backend, "bob", 200, true, 200);
+    cli.add_verdict(&verdict(&second.proof_hash, ChallengeOutcome::Overturned)).unwrap();
+    let third = put(&backend, "carol", 300, true, 300);
+
+    let reopened = ProofStore::open(dir.path()).unwrap();
+    let entries = reopened.list();
+    assert_eq!(
+        hashes(&entries),
+        [first.proof_hash.as_str(), second.proof_hash.as_str(), third.proof_hash.as_str()]
+    );
+    assert_eq!(entries[1].challenge, Some(ChallengeOutcome::Overturned));
+
+    // Pruning through one handle sees entries the other added
+    let policy = RetentionPolicy { max_entries: Some(1), ..RetentionPolicy::default() };
+    assert_eq!(cli.prune(&policy, 1000).unwrap().len(), 2);
+    assert_eq!(hashes(&ProofStore::open(dir.path()).unwrap().list()), [third.proof_hash.as_str()]);
+}
+
+#[test]
+fn concurrent_writers_lose_no_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    std::thread::scope(|scope| {
+        for writer in 0..8u32 {
+            let root = dir.path();
+            scope.spawn(move || {
+                let store = ProofStore::open(root).unwrap();
+                for game in 0..4u32 {
+                    put(
+                        &store,
+                        &format!("player{}", writer),
+                        game,
+                        true,
+                        u64::from(writer * 10 + game),
+                    );
+                }
+            });
+        }
+    });
+    assert_eq!(ProofStore::open(dir.path()).unwrap().list().len(), 32);
+}
+
+#[test]
+fn lookups_see_changes_made_through_other_handles() {
+    let dir = tempfile::tempdir().unwrap();
+    let cli = ProofStore::open(dir.path()).unwrap();
+    let backend = ProofStore::open(dir.path()).unwrap();
+
+    let added = put(&backend, "alice", 100, true, 100);
+    assert_eq!(cli.get(&added.proof_hash).map(|entry| entry.score), Some(100));
+    assert_eq!(hashes(&cli.list()), [added.proof_hash.as_str()]);
+    assert!(cli.load(&added.proof_hash).is_ok());
+
+    let policy = RetentionPolicy { max_entries: Some(0), ..RetentionPolicy::default() };
+    backend.prune(&policy, 1000).unwrap();
+    assert!(cli.get(&added.proof_hash).is_none());
+    assert!(cli.list().is_empty());
+    assert!(matches!(cli.load(&added.proof_hash), Err(StoreError::NotFound(_))));
+}
+
+#[test]
+fn failed_prune_keeps_the_entries_already_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let first = put(&store, "alice", 100, false, 100).proof_hash;
+    let stuck = put(&store, "bob", 200, false, 200).proof_hash;
+    let last = put(&store, "carol", 300, false, 300).proof_hash;
+    let kept = put(&store, "dave", 400, true, 400).proof_hash;
+    // A directory where a sidecar belongs cannot be removed as a file
+    fs::create_dir(dir.path().join(format!("{}.replay.bin", stuck))).unwrap();
+    fs::write(dir.path().join(format!("{}.replay.bin/file", stuck)), b"").unwrap();
+
+    let policy = RetentionPolicy { drop_unverified: true, ..RetentionPolicy::default() };
+    assert!(matches!(store.prune(&policy, 1000), Err(StoreError::Io(_))));
+    let reopened = ProofStore::open(dir.path()).unwrap();
+    assert_eq!(hashes(&reopened.list()), [stuck.as_str(), last.as_str(), kept.as_str()]);
+    assert!(!reopened.proof_path(&first).exists());
+    assert!(reopened.load(&stuck).is_ok());
+}
+
+#[test]
+fn concurrent_writes_of_one_file_all_succeed() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = ProofStore::open(dir.path()).unwrap();
+    let hash = put(&store, "alice", 100, true, 100).proof_hash;
+    std::thread::scope(|scope| {
+        for writer in 0..8u8 {
+            let (store, hash) = (&store, &hash);
+            scope.spawn(move || {
+                for _ in 0..20 {
+                    store.put_replay(hash, &[writer; 64]).unwrap();
+                }
+            });
+        }
+    });
+    let replay = store.replay(&hash).unwrap().unwrap();
+    assert!(replay.len() == 64 && replay.iter().all(|&byte| byte == replay[0]));
+    assert!(files(dir.path()).iter().all(|file| !file.ends_with(".tmp")));
+}
@@ -0,0 +1,29 @@
+//! Host checks on tier proof requests.
+
+use game_verification_script::tier::check_tier_submission;
+use game_verification_script::{Submission, VerificationPolicy, VerifyError};
+
+const NOW: u64 = 1_700_000_000;
+
+fn submission(score: u32) -> Submission {
+    serde_json::from_value(serde_json::json!({
+        "timestamp": NOW,
+        "player": "alice",
+        "score": score,
+        "game_hash": hex::encode([0u8; 32]),
+    }))
+    .unwrap()
+}
+
+#[test]
+fn thresholds_are_checked_before_the_submission() {
+    let policy = VerificationPolicy::default();
+    for thresholds in [&[][..], &[5000, 1000], &[1000, 1000, 5000]] {
+        // Score 15 is not a whole number of kills, but the thresholds fail first
+        let result = check_tier_submission(&submission(15), thresholds, NOW, &policy);
+        assert!(matches!(result, Err(VerifyError::TierThresholds(_))), "{:?}", thresholds);
+        assert_eq!(result.unwrap_err().kind(), "tier_thresholds");
+    }
+    let result = check_tier_submission(&submission(15), &[1000, 5000], NOW, &policy);
+    assert!(matches!(result, Err(VerifyError::ScoreStep { .. })), "{:?}", result);
+}
@@ -0,0 +1,254 @@
+//! Registration, recording results, standings and entry checks.
+
+use std::path::Path;
+
+use game_lib::replay::Replay;
+use game_lib::sim::{Input, Rng};
+use game_lib::tournament::{TournamentPublicValues, TournamentRules, TOURNAMENT_ABI_VERSION};
+use game_lib::{GameMode, RULES_VERSION};
+use game_verification_script::tournament::{
+    check_entry, Standing, Tournament, TournamentEntry, TournamentError,
+};
+use game_verification_script::{VerificationPolicy, VerifyError};
+
+const STARTS_AT: u64 = 1_700_000_000;
+const ENDS_AT: u64 = STARTS_AT + 86_400;
+
+fn rules() -> TournamentRules {
+    TournamentRules {
+        id: "spring-cup".to_string(),
+        mode: GameMode::TimeAttack,
+        seed: 42,
+        starts_at: STARTS_AT,
+        ends_at: ENDS_AT,
+    }
+}
+
+fn tournament() -> Tournament {
+    Tournament::new(rules(), None).unwrap()
+}
+
+/// Public values the guest commits for an accepted entry
+fn values(player: &str, score: u32, timestamp: u64, replay: u8) -> TournamentPublicValues {
+    TournamentPublicValues {
+        abi_version: TOURNAMENT_ABI_VERSION,
+        rules_version: RULES_VERSION,
+        tournament_id: rules().commitment(),
+        mode: GameMode::TimeAttack.id(),
+        seed: 42,
+        timestamp,
+        player_name_hash: game_lib::player_name_hash(player.as_bytes()),
+        score,
+        replay_hash: [replay; 32],
+        verified: 1,
+    }
+}
+
+fn record(tournament: &mut Tournament, player: &str, score: u32, timestamp: u64, replay: u8) {
+    let values = values(player, score, timestamp, replay);
+    tournament.record(player, &values, Path::new("entry.bin")).unwrap();
+}
+
+#[test]
+fn new_checks_the_id_and_window() {
+    for id in ["", "spring cup", "../cup", "cup.json"] {
+        let rules = TournamentRules { id: id.to_string(), ..rules() };
+        assert!(
+            matches!(Tournament::new(rules, None), Err(TournamentError::InvalidId(_))),
+            "{}",
+            id
+        );
+    }
+    for ends_at in [STARTS_AT - 1, STARTS_AT] {
+        let rules = TournamentRules { ends_at, ..rules() };
+        assert!(matches!(
+            Tournament::new(rules, None),
+            Err(TournamentError::InvalidWindow { starts_at: STARTS_AT, ends_at: end }) if end == ends_at
+        ));
+    }
+    assert!(Tournament::new(TournamentRules { ends_at: STARTS_AT + 1, ..rules() }, None).is_ok());
+}
+
+#[test]
+fn registration_is_open_until_the_window_ends() {
+    let mut tournament = tournament();
+    // Players may register ahead of the start
+    tournament.register("early", 0).unwrap();
+    tournament.register("on-time", STARTS_AT).unwrap();
+    tournament.register("last", ENDS_AT - 1).unwrap();
+    assert!(matches!(tournament.register("late", ENDS_AT), Err(TournamentError::Closed)));
+    assert!(matches!(tournament.register("later", u64::MAX), Err(TournamentError::Closed)));
+    assert_eq!(tournament.registered, ["early", "on-time", "last"]);
+    assert!(tournament.is_registered("last") && !tournament.is_registered("late"));
+}
+
+#[test]
+fn players_register_once_and_only_if_allowed() {
+    let allowed = Some(vec!["alice".to_string(), "bob".to_string()]);
+    let mut tournament = Tournament::new(rules(), allowed).unwrap();
+    tournament.register("alice", STARTS_AT).unwrap();
+    assert!(matches!(
+        tournament.register("alice", STARTS_AT + 1),
+        Err(TournamentError::AlreadyRegistered(player)) if player == "alice"
+    ));
+    assert!(matches!(
+        tournament.register("mallory", STARTS_AT),
+        Err(TournamentError::NotAllowed(player)) if player == "mallory"
+    ));
+    // Names are matched exactly
+    assert!(matches!(tournament.register("Bob", STARTS_AT), Err(TournamentError::NotAllowed(_))));
+    tournament.register("bob", STARTS_AT).unwrap();
+    assert_eq!(tournament.registered, ["alice", "bob"]);
+}
+
+#[test]
+fn record_accepts_registered_players_proofs() {
+    let mut tournament = tournament();
+    tournament.register("alice", STARTS_AT).unwrap();
+    let values = values("alice", 500, STARTS_AT + 10, 1);
+    let result = tournament.record("alice", &values, Path::new("proofs/a.bin")).unwrap();
+    assert_eq!(result.player, "alice");
+    assert_eq!((result.score, result.timestamp), (500, STARTS_AT + 10));
+    assert_eq!(result.replay_hash, hex::encode([1; 32]));
+    assert_eq!(result.proof_path, "proofs/a.bin");
+    assert_eq!(tournament.results.len(), 1);
+}
+
+#[test]
+fn record_rejects_mismatched_or_repeated_proofs() {
+    let mut tournament = tournament();
+    tournament.register("alice", STARTS_AT).unwrap();
+    let path = Path::new("entry.bin");
+    let record = |tournament: &mut Tournament, player: &str, values: TournamentPublicValues| {
+        tournament.record(player, &values, path).map(|_| ()).unwrap_err()
+    };
+
+    let other = TournamentRules { seed: 43, ..rules() };
+    let foreign = TournamentPublicValues {
+        tournament_id: other.commitment(),
+        ..values("alice", 500, STARTS_AT, 1)
+    };
+    assert!(matches!(record(&mut tournament, "alice", foreign), TournamentError::WrongTournament));
+
+    let rejected = TournamentPublicValues { verified: 0, ..values("alice", 500, STARTS_AT, 1) };
+    assert!(matches!(record(&mut tournament, "alice", rejected), TournamentError::Rejected));
+
+    let bobs = values("bob", 500, STARTS_AT, 1);
+    assert!(matches!(record(&mut tournament, "alice", bobs), TournamentError::WrongPlayer));
+    assert!(matches!(
+        record(&mut tournament, "bob", bobs),
+        TournamentError::NotRegistered(player) if player == "bob"
+    ));
+
+    tournament.record("alice", &values("alice", 500, STARTS_AT, 1), path).unwrap();
+    assert!(matches!(
+        record(&mut tournament, "alice", values("alice", 500, STARTS_AT, 1)),
+        TournamentError::AlreadyRecorded(hash) if hash == hex::encode([1; 32])
+    ));
+    // Only the first attempt was kept
+    assert_eq!(tournament.results.len(), 1);
+}
+
+#[test]
+fn standings_rank_each_players_best_result() {
+    // Dave registers but records nothing, so gets no row
+    let mut cup = tournament();
+    for player in ["alice", "bob", "carol", "dave"] {
+        cup.register(player, STARTS_AT).unwrap();
+    }
+    record(&mut cup, "alice", 300, STARTS_AT + 10, 1);
+    record(&mut cup, "bob", 500, STARTS_AT + 20, 2);
+    record(&mut cup, "alice", 700, STARTS_AT + 30, 3);
+    // Carol ties Bob's best but set it later
+    record(&mut cup, "carol", 500, STARTS_AT + 40, 4);
+    // A later repeat of Bob's best score only adds to the entry count
+    record(&mut cup, "bob", 500, STARTS_AT + 50, 5);
+    record(&mut cup, "alice", 100, STARTS_AT + 60, 6);
+
+    let standing = |rank, player: &str, score, at, entries| Standing {
+        rank,
+        player: player.to_string(),
+        score,
+        timestamp: STARTS_AT + at,
+        entries,
+    };
+    assert_eq!(
+        cup.standings(),
+        [
+            standing(1, "alice", 700, 30, 3),
+            standing(2, "bob", 500, 20, 2),
+            standing(3, "carol", 500, 40, 1),
+        ]
+    );
+
+    // Identical score and time fall back to the player name
+    let mut tied = tournament();
+    tied.register("zed", STARTS_AT).unwrap();
+    tied.register("amy", STARTS_AT).unwrap();
+    record(&mut tied, "zed", 200, STARTS_AT, 1);
+    record(&mut tied, "amy", 200, STARTS_AT, 2);
+    let players: Vec<_> = tied.standings().into_iter().map(|row| row.player).collect();
+    assert_eq!(players, ["amy", "zed"]);
+    assert!(tournament().standings().is_empty());
+}
+
+#[test]
+fn create_load_and_save() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tournament = tournament();
+    tournament.create(dir.path()).unwrap();
+    assert!(
+        matches!(tournament.create(dir.path()), Err(TournamentError::Exists(id)) if id == "spring-cup")
+    );
+
+    tournament.register("alice", STARTS_AT).unwrap();
+    record(&mut tournament, "alice", 300, STARTS_AT, 1);
+    tournament.save(dir.path()).unwrap();
+    let loaded = Tournament::load(dir.path(), "spring-cup").unwrap();
+    assert_eq!(loaded.rules, rules());
+    assert_eq!(loaded.registered, ["alice"]);
+    assert_eq!(loaded.results, tournament.results);
+    assert_eq!(tournament.proof_dir(dir.path()), dir.path().join("spring-cup"));
+
+    assert!(matches!(Tournament::load(dir.path(), "autumn"), Err(TournamentError::NotFound(_))));
+    assert!(matches!(Tournament::load(dir.path(), "../x"), Err(TournamentError::InvalidId(_))));
+}
+
+/// Entry with a short replay of `mode` on `seed`, played at `timestamp`
+fn entry(mode: GameMode, seed: u64, timestamp: u64) -> TournamentEntry {
+    let mut rng = Rng::new(seed);
+    let mut replay = Replay::new(mode, seed);
+    for _ in 0..600 {
+        replay.inputs.push(Input::new(rng.below(16) as u8, rng.below(800) as i16, 300));
+    }
+    TournamentEntry { timestamp, player: "alice".to_string(), replay: replay.encode() }
+}
+
+fn check(entry: &TournamentEntry) -> Result<(), VerifyError> {
+    check_entry(&rules(), entry, entry.timestamp, &VerificationPolicy::default()).map(|_| ())
+}
+
+#[test]
+fn entries_are_checked_against_the_rules() {
+    let mode = GameMode::TimeAttack;
+    assert!(check(&entry(mode, 42, STARTS_AT)).is_ok());
+    assert!(check(&entry(mode, 42, ENDS_AT - 1)).is_ok());
+
+    for outside in [STARTS_AT - 1, ENDS_AT] {
+        let result = check(&entry(mode, 42, outside));
+        assert!(matches!(result, Err(VerifyError::Tournament(_))), "played at {}", outside);
+    }
+    assert!(matches!(check(&entry(mode, 43, STARTS_AT)), Err(VerifyError::Tournament(_))));
+    let other_mode = check(&entry(GameMode::Endless, 42, STARTS_AT));
+    assert!(matches!(other_mode, Err(VerifyError::Tournament(_))));
+
+    let mut truncated = entry(mode, 42, STARTS_AT);
+    truncated.replay.pop();
+    assert!(matches!(check(&truncated), Err(VerifyError::Replay(_))));
+
+    let policy = VerificationPolicy::default();
+    let late = entry(mode, 42, STARTS_AT);
+    let skewed =
+        check_entry(&rules(), &late, STARTS_AT + policy.max_timestamp_skew_secs + 1, &policy);
+    assert!(matches!(skewed, Err(VerifyError::TimestampSkew { .. })));
+}
@@ -0,0 +1,167 @@
+//! WebAssembly bindings to `game_lib` for the browser client.
+//!
+//! Build with `wasm-pack build --target web --out-dir ../web/pkg`. The client
+//! steps a [`Game`] once per frame instead of running its own copy of the
+//! rules, records the replay as it goes, and computes the game hash and
+//! commitments with the same code the guest runs.
+
+use game_lib::checkpoint::{self, Checkpoint};
+use game_lib::replay::{self, Replay};
+use game_lib::sim::{Input, Simulation};
+use game_lib::GameMode;
+use wasm_bindgen::prelude::*;
+
+/// A game in progress and the replay recorded so far
+#[wasm_bindgen]
+pub struct Game {
+    sim: Simulation,
+    replay: Replay,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Start a game; `mode` is a mode name such as `"endless"` or `"time-attack"`
+    #[wasm_bindgen(constructor)]
+    pub fn new(mode: &str, seed: u64) -> Result<Game, JsError> {
+        let mode: GameMode = mode.parse().map_err(|e: String| JsError::new(&e))?;
+        Ok(Game { sim: Simulation::new(mode, seed), replay: Replay::new(mode, seed) })
+    }
+
+    /// Advance one tick with the held direction `buttons` and the pointer
+    /// position; returns the tick's events as `{type, ...}` objects
+    pub fn step(&mut self, buttons: u8, aim_x: i16, aim_y: i16) -> Result<JsValue, JsError> {
+        let input = Input::new(buttons, aim_x, aim_y);
+        // Ticks after game over are not played, so they are not recorded
+        if !self.sim.is_over() {
+            self.replay.inputs.push(input);
+        }
+        let events = self.sim.step(input);
+        serde_wasm_bindgen::to_value(&events).map_err(JsError::from)
+    }
+
+    pub fn mode(&self) -> String {
+        self.sim.mode().to_string()
+    }
+
+    /// Ticks played; a JS number rather than a `BigInt` for convenience
+    pub fn tick(&self) -> f64 {
+        self.sim.tick() as f64
+    }
+
+    pub fn score(&self) -> u32 {
+        self.sim.score()
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.sim.wave()
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.sim.lives()
+    }
+
+    pub fn kills(&self) -> u32 {
+        self.sim.kills()
+    }
+
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        self.sim.is_over()
+    }
+
+    /// `[x, y, bladeAngle, invulnerableTicks]`
+    pub fn player(&self) -> Vec<f64> {
+        let player = self.sim.player();
+        vec![player.x, player.y, player.blade_angle, f64::from(player.invulnerable_ticks)]
+    }
+
+    /// `[id, x, y]` for each enemy, flattened
+    pub fn enemies(&self) -> Vec<f64> {
+        self.sim.enemies().iter().flat_map(|e| [f64::from(e.id), e.x, e.y]).collect()
+    }
+
+    /// Hex hash of the full simulation state
+    #[wasm_bindgen(js_name = stateHash)]
+    pub fn state_hash(&self) -> String {
+        hex::encode(self.sim.state_hash())
+    }
+
+    /// Per-wave checkpoints in the JSON shape the prover accepts
+    pub fn checkpoints(&self) -> Result<JsValue, JsError> {
+        serde_wasm_bindgen::to_value(self.sim.checkpoints()).map_err(JsError::from)
+    }
+
+    /// Hex Merkle root of the checkpoints, as the guest commits it
+    #[wasm_bindgen(js_name = checkpointRoot)]
+    pub fn checkpoint_root(&self) -> String {
+        hex::encode(checkpoint::merkle_root(self.sim.checkpoints()))
+    }
+
+    /// Encoded replay of every tick played so far
+    pub fn replay(&self) -> Vec<u8> {
+        self.replay.encode()
+    }
+
+    /// Hex SHA-256 of [`Game::replay`]
+    #[wasm_bindgen(js_name = replayHash)]
+    pub fn replay_hash(&self) -> String {
+        hex::encode(replay::replay_hash(&self.replay.encode()))
+    }
+}
+
+/// Hex game hash of a finished game, see `game_lib::game_hash`
+#[wasm_bindgen(js_name = gameHash)]
+pub fn game_hash(player: &str, score: u32, timestamp: u64) -> String {
+    hex::encode(game_lib::game_hash(player, score, timestamp))
+}
+
+/// Hex hash of the player name committed in the public values
+#[wasm_bindgen(js_name = playerNameHash)]
+pub fn player_name_hash(player: &str) -> String {
+    hex::encode(game_lib::player_name_hash(player.as_bytes()))
+}
+
+/// Hex SHA-256 of an encoded replay
+#[wasm_bindgen(js_name = replayHash)]
+pub fn replay_hash(encoded: &[u8]) -> String {
+    hex::encode(replay::replay_hash(encoded))
+}
+
+/// Hex Merkle root of `{wave, tick, score, lives, state_hash}` checkpoints
+#[wasm_bindgen(js_name = checkpointRoot)]
+pub fn checkpoint_root(checkpoints: JsValue) -> Result<String, JsError> {
+    let checkpoints: Vec<Checkpoint> = serde_wasm_bindgen::from_value(checkpoints)?;
+    Ok(hex::encode(checkpoint::merkle_root(&checkpoints)))
+}
+
+/// Run an encoded replay and summarise the game it reproduces
+#[wasm_bindgen(js_name = runReplay)]
+pub fn run_replay(encoded: &[u8]) -> Result<ReplaySummary, JsError> {
+    let replay = Replay::decode(encoded)?;
+    let sim = replay.run();
+    Ok(ReplaySummary {
+        mode: replay.mode.to_string(),
+        ticks: sim.tick() as f64,
+        score: sim.score(),
+        wave: sim.wave(),
+        lives: sim.lives(),
+        over: sim.is_over(),
+        state_hash: hex::encode(sim.state_hash()),
+        checkpoint_root: hex::encode(checkpoint::merkle_root(sim.checkpoints())),
+    })
+}
+
+/// Outcome of [`run_replay`]
+#[wasm_bindgen(getter_with_clone)]
+pub struct ReplaySummary {
+    pub mode: String,
+    pub ticks: f64,
+    pub score: u32,
+    pub wave: u32,
+    pub lives: u32,
+    pub over: bool,
+    #[wasm_bindgen(js_name = stateHash)]
+    pub state_hash: String,
+    #[wasm_bindgen(js_name = checkpointRoot)]
+    pub checkpoint_root: String,
+}
@@ -0,0 +1,128 @@
+//! Headless tests of the wasm build, run under Node with
+//! `wasm-pack test --node`.
+
+#![cfg(target_arch = "wasm32")]
+
+use game_lib::checkpoint::Checkpoint;
+use game_wasm::{checkpoint_root, game_hash, player_name_hash, replay_hash, run_replay, Game};
+use serde::Deserialize;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const VECTORS: &str = include_str!("../../testdata/golden_vectors.json");
+
+#[derive(Deserialize)]
+struct Vectors {
+    game_hash: Vec<GameHashVector>,
+    player_name_hash: Vec<PlayerNameHashVector>,
+    checkpoint_root: Vec<CheckpointRootVector>,
+}
+
+#[derive(Deserialize)]
+struct GameHashVector {
+    player: String,
+    score: u32,
+    timestamp: u64,
+    game_hash: String,
+}
+
+#[derive(Deserialize)]
+struct PlayerNameHashVector {
+    player: String,
+    player_name_hash: String,
+}
+
+#[derive(Deserialize)]
+struct CheckpointRootVector {
+    checkpoints: Vec<Checkpoint>,
+    root: String,
+}
+
+fn vectors() -> Vectors {
+    serde_json::from_str(VECTORS).unwrap()
+}
+
+/// Circle the arena aiming in a slow spiral, so enemies are both killed and let through
+fn play(game: &mut Game, ticks: u32) {
+    for tick in 0..ticks {
+        let angle = f64::from(tick) * 0.05;
+        let buttons = [1, 8, 2, 4][(tick / 30 % 4) as usize];
+        let aim_x = (400.0 + 300.0 * angle.cos()) as i16;
+        let aim_y = (300.0 + 300.0 * angle.sin()) as i16;
+        game.step(buttons, aim_x, aim_y).unwrap();
+    }
+}
+
+#[wasm_bindgen_test]
+fn game_hash_matches_golden_vectors() {
+    for vector in vectors().game_hash {
+        assert_eq!(game_hash(&vector.player, vector.score, vector.timestamp), vector.game_hash);
+    }
+}
+
+#[wasm_bindgen_test]
+fn player_name_hash_matches_golden_vectors() {
+    for vector in vectors().player_name_hash {
+        assert_eq!(player_name_hash(&vector.player), vector.player_name_hash);
+    }
+}
+
+#[wasm_bindgen_test]
+fn checkpoint_root_matches_golden_vectors() {
+    for vector in vectors().checkpoint_root {
+        let checkpoints = serde_wasm_bindgen::to_value(&vector.checkpoints).unwrap();
+        assert_eq!(checkpoint_root(checkpoints).unwrap(), vector.root);
+    }
+}
+
+#[wasm_bindgen_test]
+fn same_seed_and_inputs_give_same_game() {
+    let mut a = Game::new("endless", 42).unwrap();
+    let mut b = Game::new("endless", 42).unwrap();
+    play(&mut a, 600);
+    play(&mut b, 600);
+    assert_eq!(a.state_hash(), b.state_hash());
+    assert_eq!(a.replay(), b.replay());
+
+    let mut other = Game::new("endless", 43).unwrap();
+    play(&mut other, 600);
+    assert_ne!(a.state_hash(), other.state_hash());
+}
+
+#[wasm_bindgen_test]
+fn replay_reproduces_the_game() {
+    for mode in ["endless", "time-attack", "hardcore"] {
+        let mut game = Game::new(mode, 7).unwrap();
+        play(&mut game, 2000);
+
+        let replay = game.replay();
+        assert_eq!(replay_hash(&replay), game.replay_hash());
+        let summary = run_replay(&replay).unwrap();
+        assert_eq!(summary.ticks, game.tick());
+        assert_eq!(summary.score, game.score());
+        assert_eq!(summary.wave, game.wave());
+        assert_eq!(summary.lives, game.lives());
+        assert_eq!(summary.over, game.is_over());
+        assert_eq!(summary.state_hash, game.state_hash());
+        assert_eq!(summary.checkpoint_root, game.checkpoint_root());
+    }
+}
+
+#[wasm_bindgen_test]
+fn inputs_after_game_over_are_not_recorded() {
+    let mut game = Game::new("hardcore", 1).unwrap();
+    while !game.is_over() {
+        // Standing still, the player is hit sooner or later
+        game.step(0, 0, 0).unwrap();
+    }
+    let ticks = game.tick();
+    let replay = game.replay();
+    game.step(0, 0, 0).unwrap();
+    assert_eq!(game.tick(), ticks);
+    assert_eq!(game.replay(), replay);
+}
+
+#[wasm_bindgen_test]
+fn rejects_unknown_mode_and_corrupt_replay() {
+    assert!(Game::new("speedrun", 1).is_err());
+    assert!(run_replay(b"not a replay").is_err());
+}
@@ -1,3 +1,9 @@
+// Simulation ticks per second, game_lib::sim::TICK_RATE
+const TICK_RATE = 60;
+const TICK_MS = 1000 / TICK_RATE;
+// Longest stall the loop catches up on; time beyond it stops the session clock
+const MAX_CATCH_UP_MS = 250;
+
 class BladeWarrior {
     constructor(canvasId) {
         this.canvas = document.getElementById(canvasId);
@@ -19,6 +25,9 @@ class BladeWarrior {
         
         // Game state
         this.gameActive = false;
+        this.session = null;
+        this.lastFrameTime = null;
+        this.tickAccumulator = 0;
         this.score = 0;
         this.wave = 1;
         this.lives = 3;
@@ -134,9 +143,12 @@ class BladeWarrior {
                 }
             },
             
-            generateGameHash: function(playerName, score) {
+            generateGameHash: function(playerName, score, timestamp) {
                 // Implementation from gameState.js
-                const timestamp = Math.floor(Date.now() / 1000);
+                timestamp = timestamp !== undefined ? timestamp : Math.floor(Date.now() / 1000);
+                if (window.gameWasm) {
+                    return window.gameWasm.gameHash(playerName, score, BigInt(timestamp));
+                }
                 const input = `${playerName}-${score}-${timestamp}`;
                 let hash = '';
                 
@@ -358,7 +370,9 @@ class BladeWarrior {
         
         // Initialize enemies
         this.enemies = [];
-        this.spawnEnemies();
+        if (!this.startSimulation()) {
+            this.spawnEnemies();
+        }
         
         // Initialize particles
         this.particles = [];
@@ -374,9 +388,23 @@ class BladeWarrior {
     }
     
     gameLoop() {
-        // Update game state
+        const now = performance.now();
+        const elapsed = this.lastFrameTime === null ? 0 : now - this.lastFrameTime;
+        this.lastFrameTime = now;
+        
+        // Step at the fixed tick rate whatever the display refreshes at. After a
+        // stall, such as a background tab, catch up at most MAX_CATCH_UP_MS and
+        // stop the session clock for the rest, so ticks keep matching play time.
         if (this.gameActive) {
-            this.update();
+            const counted = Math.min(elapsed, MAX_CATCH_UP_MS);
+            if (this.session) {
+                this.session.pausedMs += elapsed - counted;
+            }
+            this.tickAccumulator += counted;
+            while (this.gameActive && this.tickAccumulator >= TICK_MS) {
+                this.update();
+                this.tickAccumulator -= TICK_MS;
+            }
         }
         
         // Render game
@@ -391,6 +419,17 @@ class BladeWarrior {
             return;
         }
         
+        if (this.session) {
+            this.session.ticks++;
+        }
+        
+        // The wasm rules engine runs the game when it is loaded
+        if (this.sim) {
+            this.updateFromSimulation();
+            this.updateParticles();
+            return;
+        }
+        
         // Update player position based on keyboard input
         if (this.keys.ArrowUp || this.keys.w || this.keys.W) {
             this.player.y -= this.player.speed;
@@ -473,7 +512,11 @@ class BladeWarrior {
                 this.updateUI();
                 
                 // Add game event
-                this.addGameEvent('enemy_killed', { position: { x: enemy.x, y: enemy.y }, score: this.score });
+                this.addGameEvent('enemy_killed', {
+                    position: { x: enemy.x, y: enemy.y },
+                    player: { x: this.player.x, y: this.player.y },
+                    score: this.score
+                });
                 
                 // Skip the player collision check for this enemy since it's already removed
                 continue;
@@ -521,6 +564,136 @@ class BladeWarrior {
         }
     }
     
+    // Start the wall clock and tick count the prover checks against each other
+    startSession() {
+        this.session = { startedAtMs: Date.now(), pausedMs: 0, ticks: 0 };
+        this.finalSession = null;
+        this.lastFrameTime = null;
+        this.tickAccumulator = 0;
+    }
+    
+    // Start, end and tick count of the game just finished, with the time the
+    // loop was stalled taken off the start
+    finishSession() {
+        if (!this.session) {
+            return null;
+        }
+        const session = {
+            startedAt: Math.floor((this.session.startedAtMs + this.session.pausedMs) / 1000),
+            endedAt: Math.floor(Date.now() / 1000),
+            ticks: this.sim ? this.sim.tick() : this.session.ticks
+        };
+        this.session = null;
+        return session;
+    }
+    
+    // Start a game in the wasm build of game_lib, if it has loaded.
+    // The same rules then run here, in replays and in the prover.
+    startSimulation() {
+        this.sim = null;
+        this.finalReplay = null;
+        this.finalReplayHash = null;
+        this.finalCheckpoints = null;
+        this.startSession();
+        
+        if (!window.gameWasm) {
+            console.warn('Wasm rules engine not loaded, using JS rules');
+            return false;
+        }
+        
+        try {
+            const seed = new BigUint64Array(1);
+            crypto.getRandomValues(seed);
+            this.sim = new window.gameWasm.Game(this.mode || 'endless', seed[0]);
+            this.enemyColors = new Map();
+            this.syncFromSimulation();
+            console.log(`Simulation started in ${this.sim.mode()} mode`);
+            return true;
+        } catch (error) {
+            console.error('Error starting simulation, using JS rules:', error);
+            this.sim = null;
+            return false;
+        }
+    }
+    
+    // Held directions as the simulation's button bits
+    simulationButtons() {
+        let buttons = 0;
+        if (this.keys.ArrowUp || this.keys.w || this.keys.W) buttons |= 1;
+        if (this.keys.ArrowDown || this.keys.s || this.keys.S) buttons |= 2;
+        if (this.keys.ArrowLeft || this.keys.a || this.keys.A) buttons |= 4;
+        if (this.keys.ArrowRight || this.keys.d || this.keys.D) buttons |= 8;
+        return buttons;
+    }
+    
+    // Step the simulation one tick and react to what happened
+    updateFromSimulation() {
+        const aimX = Math.max(-32768, Math.min(32767, Math.round(this.mousePosition.x)));
+        const aimY = Math.max(-32768, Math.min(32767, Math.round(this.mousePosition.y)));
+        const events = this.sim.step(this.simulationButtons(), aimX, aimY);
+        this.syncFromSimulation();
+        
+        for (const event of events) {
+            switch (event.type) {
+                case 'enemy_killed':
+                    this.createParticles(event.x, event.y, 20, this.enemyColors.get(event.enemy) || '#ff0000', 3, 2);
+                    this.enemyColors.delete(event.enemy);
+                    this.addGameEvent('enemy_killed', {
+                        position: { x: event.x, y: event.y },
+                        player: { x: this.player.x, y: this.player.y },
+                        score: event.score
+                    });
+                    break;
+                case 'player_hit':
+                    this.enemyColors.delete(event.enemy);
+                    this.addGameEvent('player_hit', { lives: event.lives });
+                    this.shakeCanvas(10, 300);
+                    break;
+                case 'wave_started':
+                    this.showWaveNotification();
+                    break;
+                case 'game_over':
+                    this.handleGameOver();
+                    break;
+            }
+        }
+        
+        if (events.length > 0) {
+            this.updateUI();
+        }
+    }
+    
+    // Copy simulation state into the fields render() draws from
+    syncFromSimulation() {
+        const [x, y, bladeRotation, invulnerableTicks] = this.sim.player();
+        this.player.x = x;
+        this.player.y = y;
+        this.player.bladeRotation = bladeRotation;
+        this.player.invulnerable = invulnerableTicks > 0;
+        this.player.invulnerableTime = invulnerableTicks;
+        
+        this.score = this.sim.score();
+        this.wave = this.sim.wave();
+        this.lives = this.sim.lives();
+        
+        const flat = this.sim.enemies();
+        this.enemies = [];
+        for (let i = 0; i < flat.length; i += 3) {
+            const id = flat[i];
+            if (!this.enemyColors.has(id)) {
+                this.enemyColors.set(id, this.getRandomEnemyColor());
+            }
+            this.enemies.push({
+                id,
+                x: flat[i + 1],
+                y: flat[i + 2],
+                radius: 20,
+                size: 20,
+                color: this.enemyColors.get(id)
+            });
+        }
+    }
+    
     spawnEnemies() {
         if (!this.gameActive) return;
         
@@ -619,6 +792,17 @@ class BladeWarrior {
         this.finalScore = this.score;
         this.finalWave = this.wave;
         
+        // When and for how long it ran, checked against the ticks, with
+        // either rules engine
+        this.finalSession = this.finishSession();
+        
+        // Keep what the prover needs to re-run the game
+        if (this.sim) {
+            this.finalReplay = this.sim.replay();
+            this.finalReplayHash = this.sim.replayHash();
+            this.finalCheckpoints = this.sim.checkpoints();
+        }
+        
         // Update global game state
         window.gameState.updateState({
             finalScore: this.finalScore,
@@ -1524,7 +1708,9 @@ class BladeWarrior {
             this.updateUI();
             
             // IMPORTANT: Spawn enemies with correct initial speed
-            this.spawnEnemies();
+            if (!this.startSimulation()) {
+                this.spawnEnemies();
+            }
             
             // Update gameState global
             if (window.gameState) {
@@ -1684,6 +1870,8 @@ BladeWarrior.prototype.startGame = function() {
     this.lives = 3;
     this.enemies = [];
     this.gameData = { events: [] };
+    this.sim = null;
+    this.startSession();
     
     // Reset player position
     this.player.x = this.width / 2;
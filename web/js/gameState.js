@@ -17,9 +17,15 @@ window.gameState = window.gameState || {
     },
     
     // Generate game hash
-    generateGameHash: function(playerName, score) {
+    generateGameHash: function(playerName, score, timestamp) {
+        timestamp = timestamp !== undefined ? timestamp : Math.floor(Date.now() / 1000);
+        
+        // Same hash the prover checks, from the wasm build of game_lib
+        if (window.gameWasm) {
+            return window.gameWasm.gameHash(playerName, score, BigInt(timestamp));
+        }
+        
         // Simple implementation for demo
-        const timestamp = Math.floor(Date.now() / 1000);
         const input = `${playerName}-${score}-${timestamp}`;
         let hash = '';
         
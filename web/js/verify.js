@@ -49,15 +49,17 @@
                 window.gameState.playerName = playerName;
             }
             
+            // Get current timestamp; the hash must be made over the same one that is submitted
+            const timestamp = Math.floor(Date.now() / 1000);
+            
             // Generate game hash
             let gameHash;
             if (window.gameState && typeof window.gameState.generateGameHash === 'function') {
-                gameHash = window.gameState.generateGameHash(playerName, finalScore);
+                gameHash = window.gameState.generateGameHash(playerName, finalScore, timestamp);
             } else if (gameInstance && typeof gameInstance.generateGameHash === 'function') {
-                gameHash = gameInstance.generateGameHash(playerName, finalScore);
+                gameHash = gameInstance.generateGameHash(playerName, finalScore, timestamp);
             } else {
                 // Fallback implementation
-                const timestamp = Math.floor(Date.now() / 1000);
                 gameHash = `${playerName}-${finalScore}-${timestamp}`.split('').map(c => c.charCodeAt(0).toString(16)).join('');
             }
             
@@ -68,9 +70,6 @@
                 gameHash = gameHash.slice(0, 64);
             }
             
-            // Get current timestamp
-            const timestamp = Math.floor(Date.now() / 1000);
-            
             // Show proof log
             const proofLog = document.getElementById('proof-log');
             if (proofLog) {
@@ -161,7 +160,18 @@
                         playerName,
                         score: finalScore,
                         timestamp,
-                        gameHash
+                        gameHash,
+                        mode: gameInstance.sim ? gameInstance.sim.mode() : undefined,
+                        // Event log for the server-side anti-cheat analysis
+                        events: (gameInstance.gameData && gameInstance.gameData.events) || [],
+                        // Per-wave checkpoints recorded by the wasm simulation
+                        checkpoints: gameInstance.finalCheckpoints || undefined,
+                        // Start, end and tick count of the game, checked against each other
+                        session: gameInstance.finalSession || undefined,
+                        // Hex encoded replay, kept so the score can be challenged
+                        replay: gameInstance.finalReplay
+                            ? Array.from(gameInstance.finalReplay, byte => byte.toString(16).padStart(2, '0')).join('')
+                            : undefined
                     })
                 }).catch(error => {
                     console.error('Error when sending verification data:', error);